@@ -0,0 +1,80 @@
+//! Timings of the typed sorts at growing input sizes, used to pick
+//! `array::RADIX_SORT_THRESHOLD`.
+//!
+//! Run with `cargo bench --bench sort`. For each element type the table shows the time per
+//! element of `merge_sort`, `quick_sort` and the radix sort, and the last column marks the sizes
+//! where radix sort is the fastest. The crossover is the first size marked.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rust_scripts::array::{merge_sort, merge_sort_by, quick_sort, radix_sort_f32, radix_sort_i32, radix_sort_u32, NanPlacement};
+
+/// Minimum measured time per algorithm and size, so that the small sizes run many times.
+const MIN_DURATION: Duration = Duration::from_millis(200);
+
+fn main() {
+    let sizes: Vec<usize> = (4..=20).map(|shift| 1 << shift).collect();
+
+    println!("i32 (ns per element)");
+    table(&sizes, |len| {
+        let input: Vec<i32> = random(len).map(|v| v as i32).collect();
+        [
+            time(|| merge_sort(black_box(&input))),
+            time(|| quick_sort(black_box(&input))),
+            time(|| radix_sort_i32(black_box(&input))),
+        ]
+    });
+
+    println!("\nu32 (ns per element)");
+    table(&sizes, |len| {
+        let input: Vec<u32> = random(len).collect();
+        [
+            time(|| merge_sort(black_box(&input))),
+            time(|| quick_sort(black_box(&input))),
+            time(|| radix_sort_u32(black_box(&input))),
+        ]
+    });
+
+    println!("\nf32 (ns per element)");
+    table(&sizes, |len| {
+        let input: Vec<f32> = random(len).map(|v| v as i32 as f32 / 1024.0).collect();
+        [
+            time(|| merge_sort_by(black_box(&input), |a, b| a.total_cmp(b))),
+            time(|| quick_sort(black_box(&input))),
+            time(|| radix_sort_f32(black_box(&input), NanPlacement::Last)),
+        ]
+    });
+}
+
+fn table(sizes: &[usize], mut run: impl FnMut(usize) -> [f64; 3]) {
+    println!("{:>9} {:>8} {:>8} {:>8}", "len", "merge", "quick", "radix");
+    for &len in sizes {
+        let [merge, quick, radix] = run(len);
+        let mark = if radix < merge && radix < quick { "  *" } else { "" };
+        println!("{:>9} {:>8.2} {:>8.2} {:>8.2}{}", len, merge, quick, radix, mark);
+    }
+}
+
+/// Average time per element of one call to `sort`, in nanoseconds.
+fn time<T>(mut sort: impl FnMut() -> Vec<T>) -> f64 {
+    let mut runs = 0u32;
+    let mut len = 0;
+    let start = Instant::now();
+    while start.elapsed() < MIN_DURATION {
+        len = black_box(sort()).len();
+        runs += 1;
+    }
+    start.elapsed().as_nanos() as f64 / f64::from(runs) / len.max(1) as f64
+}
+
+/// Xorshift values, so that every run sorts the same input.
+fn random(len: usize) -> impl Iterator<Item = u32> {
+    let mut state = 0x9E37_79B9u32;
+    (0..len).map(move |_| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    })
+}
@@ -0,0 +1,24 @@
+//! Generates the C header for the `ffi` feature.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "ffi")]
+    generate_c_header();
+}
+
+/// Writes `include/rust_scripts.h` from the `extern "C"` declarations in `src/ffi/`.
+///
+/// The file is committed so C/C++ callers do not need cbindgen; it is only rewritten when its
+/// contents change.
+#[cfg(feature = "ffi")]
+fn generate_c_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src/ffi");
+
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).expect("invalid cbindgen.toml");
+    cbindgen::generate_with_config(&crate_dir, config)
+        .expect("failed to generate the C header")
+        .write_to_file(format!("{}/include/rust_scripts.h", crate_dir));
+}
@@ -0,0 +1,176 @@
+/* rust-scripts C API. See src/ffi/mod.rs for the ownership and error conventions. */
+
+#ifndef RUST_SCRIPTS_H
+#define RUST_SCRIPTS_H
+
+/* Generated by cbindgen from src/ffi/ when building with `--features ffi`; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The call succeeded.
+#define RS_OK 0
+
+// The input could not be decompressed (`DECOMPRESSION_FAILED`).
+#define RS_ERR_DECOMPRESSION 1
+
+// The CSV input could not be parsed (`CSV_PARSE_FAILED`).
+#define RS_ERR_CSV 2
+
+// A value could not be serialized (`SERIALIZATION_FAILED`).
+#define RS_ERR_SERIALIZATION 3
+
+// The input does not have the expected shape, or a required pointer is `NULL` (`INVALID_INPUT`).
+#define RS_ERR_INVALID_INPUT 4
+
+// The operation was cancelled (`CANCELLED`).
+#define RS_ERR_CANCELLED 5
+
+// The compressor failed internally (`COMPRESSION_FAILED`).
+#define RS_ERR_COMPRESSION 6
+
+// A byte buffer allocated by the library.
+//
+// Returned through an out-parameter by the functions whose output size is only known once it
+// has been computed. The caller owns it and must release it with `rs_buffer_free`, exactly once.
+// A zero-initialized `RsBuffer` (`data == NULL`, `len == 0`) is valid and empty.
+typedef struct RsBuffer {
+  // Start of the bytes, or `NULL` for an empty buffer.
+  uint8_t *data;
+  // Number of bytes at `data`.
+  size_t len;
+} RsBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Releases the memory of a buffer returned by the library and resets it to empty.
+//
+// Passing `NULL` or an already empty buffer does nothing.
+//
+// # Safety
+//
+// `buffer` must be `NULL` or point to an `RsBuffer` that is empty or was filled by this library
+// and not modified since.
+void rs_buffer_free(struct RsBuffer *buffer);
+
+// Returns the message of the last error returned on the calling thread, or `NULL` if no call
+// has failed yet.
+//
+// The string is NUL-terminated UTF-8 owned by the library. It stays valid until the next
+// failing call on the same thread; copy it to keep it longer.
+const char *rs_last_error_message(void);
+
+// Sorts `len` integers in place with pdqsort. See `array::quick_sort_in_place`.
+//
+// # Safety
+//
+// `values` must be valid for reads and writes of `len` elements.
+int32_t rs_quick_sort_i32(int32_t *values, size_t len);
+
+// Sorts `len` integers in place with the stable MergeSort. See `array::merge_sort`.
+//
+// # Safety
+//
+// `values` must be valid for reads and writes of `len` elements.
+int32_t rs_merge_sort_i32(int32_t *values, size_t len);
+
+// Sorts `len` doubles in place with pdqsort. See `array::quick_sort_in_place`.
+//
+// # Safety
+//
+// `values` must be valid for reads and writes of `len` elements.
+int32_t rs_quick_sort_f64(double *values, size_t len);
+
+// Sorts `len` doubles in place with the stable MergeSort. See `array::merge_sort`.
+//
+// # Safety
+//
+// `values` must be valid for reads and writes of `len` elements.
+int32_t rs_merge_sort_f64(double *values, size_t len);
+
+// Computes the CRC32 checksum of `data` into `*out`. See `crypto::calculate_crc32`.
+//
+// # Safety
+//
+// `data` must be valid for reads of `len` bytes and `out` valid for a `uint32_t` write.
+int32_t rs_calculate_crc32(const uint8_t *data, size_t len, uint32_t *out);
+
+// Writes the 32-byte SHA-256 digest of `data` to `out`. See `crypto::sha256_hash`.
+//
+// # Safety
+//
+// `data` must be valid for reads of `len` bytes and `out` valid for writes of 32 bytes.
+int32_t rs_sha256_hash(const uint8_t *data, size_t len, uint8_t *out);
+
+// LZ4-compresses `data` with the original size prepended into a new buffer. See
+// `crypto::compress_data`.
+//
+// # Safety
+//
+// `data` must be valid for reads of `len` bytes and `out` valid for an `RsBuffer` write. A
+// previous buffer in `*out` is overwritten, not freed.
+int32_t rs_compress_data(const uint8_t *data, size_t len, struct RsBuffer *out);
+
+// Decompresses the output of `rs_compress_data` into a new buffer. See `crypto::decompress_data`.
+//
+// Returns `RS_ERR_DECOMPRESSION` on invalid input.
+//
+// # Safety
+//
+// Same as `rs_compress_data`.
+int32_t rs_decompress_data(const uint8_t *data, size_t len, struct RsBuffer *out);
+
+// Deflate-compresses `data` into a new buffer. See `crypto::deflate_compress`.
+//
+// # Safety
+//
+// Same as `rs_compress_data`.
+int32_t rs_deflate_compress(const uint8_t *data, size_t len, struct RsBuffer *out);
+
+// Decompresses a raw Deflate stream into a new buffer. See `crypto::deflate_decompress`.
+//
+// Returns `RS_ERR_DECOMPRESSION` on invalid input.
+//
+// # Safety
+//
+// Same as `rs_compress_data`.
+int32_t rs_deflate_decompress(const uint8_t *data, size_t len, struct RsBuffer *out);
+
+// Inverts every byte of an RGBA buffer in place. See `images::invert_colors`.
+//
+// # Safety
+//
+// `pixels` must be valid for reads and writes of `len` bytes.
+int32_t rs_invert_colors(uint8_t *pixels, size_t len);
+
+// Converts an RGBA buffer to grayscale in place. See `images::grayscale_in_place`.
+//
+// Returns `RS_ERR_INVALID_INPUT` if `len` is not a multiple of 4.
+//
+// # Safety
+//
+// `pixels` must be valid for reads and writes of `len` bytes.
+int32_t rs_grayscale(uint8_t *pixels, size_t len);
+
+// Parses UTF-8 CSV text into JSON Lines: one JSON array per record, each followed by `\n`, in a
+// new buffer (the same output as `rust-scripts csv2json`). See `json::parse_csv_to_json`.
+//
+// Returns `RS_ERR_INVALID_INPUT` if `content` is not UTF-8, or `RS_ERR_CSV` /
+// `RS_ERR_SERIALIZATION`.
+//
+// # Safety
+//
+// `content` must be valid for reads of `len` bytes and `out` valid for an `RsBuffer` write. A
+// previous buffer in `*out` is overwritten, not freed.
+int32_t rs_parse_csv_to_json(const uint8_t *content, size_t len, struct RsBuffer *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUST_SCRIPTS_H */
@@ -1,78 +1,585 @@
-use wasm_bindgen::prelude::*;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::ops::{Add, Mul};
+use std::ops::Range;
 
-/// Sorts a vector of integers using the quicksort algorithm.
+use crate::error::{Error, Result};
+#[cfg(feature = "parallel")]
+use crate::parallel;
+use crate::progress::{checkpoint, Progress};
+
+/// Sorts a slice using quicksort, returning a sorted copy.
 ///
-/// The `quick_sort` function implements the classic quicksort algorithm to sort a vector of integers
-/// in ascending order. It recursively partitions the array around a pivot element, then sorts the
-/// sub-arrays to the left and right of the pivot. Finally, the function concatenates the results
-/// to produce the sorted array.
+/// The copy is sorted in place with [`quick_sort_in_place`], a pattern-defeating quicksort
+/// (pdqsort): pivots are chosen by median-of-three (ninther for longer slices), small partitions
+/// are finished with insertion sort, and if partitioning keeps producing unbalanced splits the
+/// slice falls back to heapsort. Already sorted, reversed and all-equal inputs are handled in
+/// O(n), and the worst case is O(n log n).
 ///
-/// The pivot element is chosen as the last element of the vector for simplicity. This function
-/// has a time complexity of O(n log n) on average, but can degrade to O(n^2) in the worst case if the
-/// pivot selection consistently results in unbalanced partitions.
+/// Like any quicksort the order of equal elements is not preserved; use [`merge_sort`] when that
+/// matters.
 ///
 /// # Arguments
 ///
-/// * `arr` - A vector of integers (`Vec<i32>`) to be sorted.
+/// * `arr` - A slice of values (`&[T]`) to be sorted. Any type implementing `PartialOrd + Clone`
+///   is accepted (integers, floats, strings, ...).
 ///
 /// # Returns
 ///
-/// * `Vec<i32>` - A new vector containing the sorted integers from the input vector in ascending order.
+/// * `Vec<T>` - A new vector containing the sorted values from the input slice in ascending order.
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::array::quick_sort;
+///
 /// let unsorted_array = vec![5, 2, 9, 1, 5, 6];
-/// let sorted_array = quick_sort(unsorted_array);
-/// println!("{:?}", sorted_array); // Outputs: [1, 2, 5, 5, 6, 9]
+/// let sorted_array = quick_sort(&unsorted_array);
+/// assert_eq!(sorted_array, vec![1, 2, 5, 5, 6, 9]);
 /// ```
 ///
 /// # Performance Considerations
 ///
-/// - **Average Time Complexity**: The average time complexity of quicksort is O(n log n), where `n` is the
-///   number of elements in the vector. The algorithm works by partitioning the array into sub-arrays around a pivot,
-///   and then recursively sorting those sub-arrays.
-///
-/// - **Worst-Case Time Complexity**: The worst-case time complexity occurs when the pivot chosen results in
-///   unbalanced partitions (e.g., the pivot is always the smallest or largest element). In such cases, the algorithm
-///   can degrade to O(n^2).
-///
-/// - **Memory Usage**: The function is recursive and creates new vectors during each partitioning step. Thus, the
-///   memory usage grows with the size of the input array, and the function has a space complexity of O(n) in the worst case,
-///   due to the stack depth from recursion.
-#[wasm_bindgen]
-pub fn quick_sort(mut arr: Vec<i32>) -> Vec<i32> {
-    // Base case: an array of length 0 or 1 is already sorted
-    if arr.len() <= 1 {
-        return arr;
-    }
+/// - **Time Complexity**: O(n log n) in the worst case, O(n) for sorted, reversed or
+///   all-equal inputs.
+/// - **Memory Usage**: one copy of the input; the sort itself allocates nothing and recurses at
+///   most O(log n) levels deep, since it always recurses into the shorter partition.
+pub fn quick_sort<T: PartialOrd + Clone>(arr: &[T]) -> Vec<T> {
+    let mut sorted = arr.to_vec();
+    quick_sort_in_place(&mut sorted);
+    sorted
+}
+
+/// Sorts a slice in place with a pattern-defeating quicksort (pdqsort).
+///
+/// This is the algorithm behind [`quick_sort`], without the copy. Elements that are not
+/// comparable with each other (e.g. `NaN`) never cause a panic, but end up in an unspecified
+/// position.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::quick_sort_in_place;
+///
+/// let mut values = vec![3.5, -1.0, 2.0, 0.0];
+/// quick_sort_in_place(&mut values);
+/// assert_eq!(values, vec![-1.0, 0.0, 2.0, 3.5]);
+/// ```
+pub fn quick_sort_in_place<T: PartialOrd>(arr: &mut [T]) {
+    // Allow floor(log2(n)) + 1 imbalanced partitions (the bit length of n) before switching to heapsort
+    let limit = usize::BITS - arr.len().leading_zeros();
+    pdq_recurse(arr, None, limit);
+}
+
+/// Main pdqsort loop: partitions `v`, recurses into the shorter side and loops on the longer one.
+///
+/// `pred` is the pivot of the parent partition, which is known to be `<=` every element of `v`;
+/// `limit` is the number of imbalanced partitions allowed before falling back to heapsort.
+fn pdq_recurse<'a, T: PartialOrd>(mut v: &'a mut [T], mut pred: Option<&'a T>, mut limit: u32) {
+    // Slices of up to this length are sorted with insertion sort
+    const MAX_INSERTION: usize = 20;
+
+    let mut was_balanced = true;
+    let mut was_partitioned = true;
 
-    // Choose a pivot (for simplicity, choose the last element)
-    let pivot = arr.pop().unwrap(); // Removes and returns the last element as pivot
+    loop {
+        let len = v.len();
+        if len <= MAX_INSERTION {
+            insertion_sort(v);
+            return;
+        }
+
+        // Too many bad pivots: guarantee O(n log n) with heapsort
+        if limit == 0 {
+            heapsort(v);
+            return;
+        }
+
+        // The last partitioning was imbalanced: shuffle a few elements to break the pattern
+        if !was_balanced {
+            break_patterns(v);
+            limit -= 1;
+        }
+
+        let (pivot, likely_sorted) = choose_pivot(v);
+
+        // The slice looks (almost) sorted: try to finish it with a few insertion steps
+        if was_balanced && was_partitioned && likely_sorted && partial_insertion_sort(v) {
+            return;
+        }
 
-    // Partition into two arrays: one for elements < pivot, and one for elements >= pivot
-    let mut left: Vec<i32> = Vec::new();
-    let mut right: Vec<i32> = Vec::new();
+        // The pivot equals the parent pivot, which is the smallest value in `v`: move all the
+        // elements equal to it to the left and continue with the greater ones only
+        if let Some(pred) = pred {
+            if !is_less(pred, &v[pivot]) {
+                let mid = partition_equal(v, pivot);
+                v = &mut std::mem::take(&mut v)[mid..];
+                continue;
+            }
+        }
+
+        let (mid, partitioned) = partition(v, pivot);
+        was_balanced = mid.min(len - mid) >= len / 8;
+        was_partitioned = partitioned;
 
-    for x in arr {
-        if x < pivot {
-            left.push(x);
+        let (left, right) = std::mem::take(&mut v).split_at_mut(mid);
+        let (pivot, right) = right.split_at_mut(1);
+        let pivot = &pivot[0];
+
+        // Recurse into the shorter side to keep the stack depth at O(log n)
+        if left.len() < right.len() {
+            pdq_recurse(left, pred, limit);
+            v = right;
+            pred = Some(pivot);
         } else {
-            right.push(x);
+            pdq_recurse(right, Some(pivot), limit);
+            v = left;
+        }
+    }
+}
+
+/// `a < b`. Written as a function so that negations (`!is_less(a, b)`, i.e. "not less", which is
+/// also true for incomparable values such as `NaN`) read the same everywhere in pdqsort.
+#[inline]
+fn is_less<T: PartialOrd>(a: &T, b: &T) -> bool {
+    a < b
+}
+
+/// Sorts `v` with insertion sort; used for short slices.
+fn insertion_sort<T: PartialOrd>(v: &mut [T]) {
+    for i in 1..v.len() {
+        shift_tail(&mut v[..=i]);
+    }
+}
+
+/// Moves the last element of `v` left until it is not less than its predecessor.
+fn shift_tail<T: PartialOrd>(v: &mut [T]) {
+    let mut j = v.len().saturating_sub(1);
+    while j > 0 && is_less(&v[j], &v[j - 1]) {
+        v.swap(j, j - 1);
+        j -= 1;
+    }
+}
+
+/// Moves the first element of `v` right until it is not greater than its successor.
+fn shift_head<T: PartialOrd>(v: &mut [T]) {
+    let mut j = 0;
+    while j + 1 < v.len() && is_less(&v[j + 1], &v[j]) {
+        v.swap(j, j + 1);
+        j += 1;
+    }
+}
+
+/// Sorts `v` with heapsort, the O(n log n) fallback of `pdq_recurse`.
+fn heapsort<T: PartialOrd>(v: &mut [T]) {
+    fn sift_down<T: PartialOrd>(v: &mut [T], mut node: usize) {
+        loop {
+            let mut child = 2 * node + 1;
+            if child >= v.len() {
+                break;
+            }
+            if child + 1 < v.len() && is_less(&v[child], &v[child + 1]) {
+                child += 1;
+            }
+            if !is_less(&v[node], &v[child]) {
+                break;
+            }
+            v.swap(node, child);
+            node = child;
+        }
+    }
+
+    for node in (0..v.len() / 2).rev() {
+        sift_down(v, node);
+    }
+    for end in (1..v.len()).rev() {
+        v.swap(0, end);
+        sift_down(&mut v[..end], 0);
+    }
+}
+
+/// Picks a pivot index for `v` (longer than `MAX_INSERTION`) and reports whether the slice is
+/// likely already sorted.
+///
+/// Uses the median of three elements, or for long slices the median of three medians of three
+/// (Tukey's ninther). If the samples were in descending order the slice is reversed first, since
+/// it is probably descending.
+fn choose_pivot<T: PartialOrd>(v: &mut [T]) -> (usize, bool) {
+    // Use the ninther from this length on
+    const SHORTEST_NINTHER: usize = 50;
+    // Maximum number of swaps `sort3` can perform for the ninther (4 calls of 3 swaps)
+    const MAX_SWAPS: usize = 4 * 3;
+
+    let len = v.len();
+    let (mut a, mut b, mut c) = (len / 4, len / 4 * 2, len / 4 * 3);
+    let mut swaps = 0;
+
+    if len >= SHORTEST_NINTHER {
+        for x in [&mut a, &mut b, &mut c] {
+            let (mut lo, mut hi) = (*x - 1, *x + 1);
+            sort3(v, &mut lo, x, &mut hi, &mut swaps);
+        }
+    }
+    sort3(v, &mut a, &mut b, &mut c, &mut swaps);
+
+    if swaps < MAX_SWAPS {
+        (b, swaps == 0)
+    } else {
+        // Every comparison was descending: the slice is probably reversed
+        v.reverse();
+        (len - 1 - b, true)
+    }
+}
+
+/// Orders the indices `a`, `b`, `c` so that `v[a] <= v[b] <= v[c]`, counting the swaps.
+fn sort3<T: PartialOrd>(v: &[T], a: &mut usize, b: &mut usize, c: &mut usize, swaps: &mut usize) {
+    let mut sort2 = |x: &mut usize, y: &mut usize| {
+        if is_less(&v[*y], &v[*x]) {
+            std::mem::swap(x, y);
+            *swaps += 1;
+        }
+    };
+    sort2(a, b);
+    sort2(b, c);
+    sort2(a, b);
+}
+
+/// Fixes a nearly sorted slice with at most a few insertion steps. Returns `true` if `v` ended
+/// up fully sorted.
+fn partial_insertion_sort<T: PartialOrd>(v: &mut [T]) -> bool {
+    // Maximum number of adjacent out-of-order pairs that get fixed
+    const MAX_STEPS: usize = 5;
+    // Shorter slices are not shifted at all (the caller sorts them properly anyway)
+    const SHORTEST_SHIFTING: usize = 50;
+
+    let len = v.len();
+    let mut i = 1;
+    for _ in 0..MAX_STEPS {
+        while i < len && !is_less(&v[i], &v[i - 1]) {
+            i += 1;
+        }
+        if i == len {
+            return true;
+        }
+        if len < SHORTEST_SHIFTING {
+            return false;
         }
+
+        // Swap the out-of-order pair and move both elements to their place
+        v.swap(i - 1, i);
+        shift_tail(&mut v[..i]);
+        shift_head(&mut v[i..]);
+    }
+    false
+}
+
+/// Partitions `v` around `v[pivot]` into `[< pivot, pivot, >= pivot]`.
+///
+/// Returns the final index of the pivot and whether `v` was already partitioned.
+fn partition<T: PartialOrd>(v: &mut [T], pivot: usize) -> (usize, bool) {
+    v.swap(0, pivot);
+    let (head, rest) = v.split_at_mut(1);
+    let pivot = &head[0];
+
+    // Skip the prefix and suffix that are already on the correct side
+    let (mut l, mut r) = (0, rest.len());
+    while l < r && is_less(&rest[l], pivot) {
+        l += 1;
+    }
+    while l < r && !is_less(&rest[r - 1], pivot) {
+        r -= 1;
+    }
+    let was_partitioned = l >= r;
+
+    loop {
+        while l < r && is_less(&rest[l], pivot) {
+            l += 1;
+        }
+        while l < r && !is_less(&rest[r - 1], pivot) {
+            r -= 1;
+        }
+        if l >= r {
+            break;
+        }
+        r -= 1;
+        rest.swap(l, r);
+        l += 1;
+    }
+
+    // Put the pivot between the two sides
+    v.swap(0, l);
+    (l, was_partitioned)
+}
+
+/// Partitions `v` into elements equal to `v[pivot]` followed by greater elements, assuming no
+/// element is smaller than the pivot. Returns the number of equal elements.
+fn partition_equal<T: PartialOrd>(v: &mut [T], pivot: usize) -> usize {
+    v.swap(0, pivot);
+    let (head, rest) = v.split_at_mut(1);
+    let pivot = &head[0];
+
+    let (mut l, mut r) = (0, rest.len());
+    loop {
+        while l < r && !is_less(pivot, &rest[l]) {
+            l += 1;
+        }
+        while l < r && is_less(pivot, &rest[r - 1]) {
+            r -= 1;
+        }
+        if l >= r {
+            break;
+        }
+        r -= 1;
+        rest.swap(l, r);
+        l += 1;
+    }
+
+    // The pivot itself is equal too
+    l + 1
+}
+
+/// Swaps a few pseudo-random elements around the middle of `v` to break up patterns that
+/// produce repeatedly imbalanced partitions.
+fn break_patterns<T>(v: &mut [T]) {
+    let len = v.len();
+    if len < 8 {
+        return;
+    }
+
+    // Xorshift seeded with the length: deterministic, but unrelated to the data
+    let mut random = len as u32;
+    let mut next = || {
+        random ^= random << 13;
+        random ^= random >> 17;
+        random ^= random << 5;
+        random as usize
+    };
+
+    let mask = len.next_power_of_two() - 1;
+    let pos = len / 4 * 2;
+    for i in 0..3 {
+        let mut other = next() & mask;
+        if other >= len {
+            other -= len;
+        }
+        v.swap(pos - 1 + i, other);
+    }
+}
+
+/// Rearranges `arr` in place so that `arr[n]` is the element that would be there if `arr` were
+/// sorted, every element before it is `<=` and every element after it is `>=`. Neither side is
+/// sorted.
+///
+/// Uses introselect: quickselect with the pivot selection and partitioning of
+/// [`quick_sort_in_place`], falling back to heapsort on the remaining slice after too many
+/// imbalanced partitions. This takes O(n) time on average and O(n log n) in the worst case,
+/// without allocating. As with [`quick_sort_in_place`], incomparable elements (`NaN`) end up in
+/// an unspecified position; see [`nth_element_f64`] for floats.
+///
+/// Returns `Error::InvalidInput` if `n` is not an index of `arr`.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::nth_element;
+///
+/// // 95th percentile (nearest rank) of 1..=100
+/// let mut latencies: Vec<u32> = (1..=100).rev().collect();
+/// nth_element(&mut latencies, 94).unwrap();
+/// assert_eq!(latencies[94], 95);
+/// assert!(latencies[..94].iter().all(|&v| v <= 95));
+/// ```
+pub fn nth_element<T: PartialOrd>(arr: &mut [T], n: usize) -> Result<()> {
+    if n >= arr.len() {
+        return Err(Error::InvalidInput(format!("index {} is out of range for {} elements", n, arr.len())));
+    }
+    introselect(arr, n);
+    Ok(())
+}
+
+/// Rearranges `arr` like [`nth_element`], in the order of [`sort_f64`] with `NaN`s last.
+pub fn nth_element_f64(arr: &mut [f64], n: usize) -> Result<()> {
+    let mut ordered: Vec<TotalF64> = arr.iter().map(|&v| TotalF64(v)).collect();
+    nth_element(&mut ordered, n)?;
+    for (value, ordered) in arr.iter_mut().zip(ordered) {
+        *value = ordered.0;
+    }
+    Ok(())
+}
+
+/// Returns the `k` smallest elements of `arr` in ascending order, or with
+/// `SortOrder::Descending` the `k` largest in descending order. Returns every element if `arr`
+/// is shorter than `k`.
+///
+/// Equal elements are taken in the order of their indices, so the result is the prefix of the
+/// stable sort of `arr` in the same direction. Runs in O(n + k log k) time on average, instead of
+/// the O(n log n) of a full sort.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::{top_k, SortOrder};
+///
+/// assert_eq!(top_k(&[4, 9, 1, 7, 9], 3, SortOrder::Descending), vec![9, 9, 7]);
+/// assert_eq!(top_k(&[4, 9, 1, 7, 9], 2, SortOrder::Ascending), vec![1, 4]);
+/// ```
+pub fn top_k<T: PartialOrd + Clone>(arr: &[T], k: usize, order: SortOrder) -> Vec<T> {
+    top_k_indices(arr, k, order).into_iter().map(|i| arr[i].clone()).collect()
+}
+
+/// Returns the indices of the elements [`top_k`] selects, in the same order.
+pub fn top_k_indices<T: PartialOrd>(arr: &[T], k: usize, order: SortOrder) -> Vec<usize> {
+    match order {
+        SortOrder::Ascending => smallest_k(arr.iter().zip(0..).collect(), k),
+        SortOrder::Descending => smallest_k(arr.iter().map(Reverse).zip(0..).collect(), k),
+    }
+}
+
+/// Returns the `k` smallest or largest numbers of `arr` like [`top_k`], with `-0.0` before
+/// `0.0`. `NaN`s are never selected, so the result is shorter than `k` when `arr` has fewer
+/// numbers.
+pub fn top_k_f64(arr: &[f64], k: usize, order: SortOrder) -> Vec<f64> {
+    top_k_indices_f64(arr, k, order).into_iter().map(|i| arr[i]).collect()
+}
+
+/// Returns the indices of the numbers [`top_k_f64`] selects, in the same order.
+pub fn top_k_indices_f64(arr: &[f64], k: usize, order: SortOrder) -> Vec<usize> {
+    let numbers = arr.iter().zip(0..).filter(|(v, _)| !v.is_nan()).map(|(&v, i)| (TotalF64(v), i));
+    match order {
+        SortOrder::Ascending => smallest_k(numbers.collect(), k),
+        SortOrder::Descending => smallest_k(numbers.map(|(v, i)| (Reverse(v), i)).collect(), k),
+    }
+}
+
+/// Returns the median of `arr`: its middle element once sorted, or the mean of the two middle
+/// elements for an even length. `None` if `arr` is empty.
+///
+/// Runs in O(n) time on average with one copy of the input; see [`nth_element`].
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::median;
+///
+/// assert_eq!(median(&[7, 1, 3]), Some(3.0));
+/// assert_eq!(median(&[7, 1, 3, 4]), Some(3.5));
+/// assert_eq!(median::<i32>(&[]), None);
+/// ```
+pub fn median<T: Ord + Copy + Into<f64>>(arr: &[T]) -> Option<f64> {
+    median_of(arr.to_vec(), |v| v.into())
+}
+
+/// Returns the median of the numbers in `arr` like [`median`], ignoring `NaN`s (like numpy's
+/// `nanmedian`). `None` if `arr` has no numbers.
+pub fn median_f64(arr: &[f64]) -> Option<f64> {
+    median_of(arr.iter().filter(|v| !v.is_nan()).map(|&v| TotalF64(v)).collect(), |v| v.0)
+}
+
+/// Selects the middle of `values` and converts it to `f64`.
+fn median_of<T: Ord + Copy>(mut values: Vec<T>, to_f64: impl Fn(T) -> f64) -> Option<f64> {
+    let mid = values.len() / 2;
+    nth_element(&mut values, mid).ok()?;
+    let upper = to_f64(values[mid]);
+    if !values.len().is_multiple_of(2) {
+        return Some(upper);
+    }
+    // After selection the lower middle is the largest element of the left side
+    let lower = to_f64(*values[..mid].iter().max()?);
+    // Halve first so that the sum cannot overflow
+    Some(lower / 2.0 + upper / 2.0)
+}
+
+/// Returns the indices of the `k` smallest keys in ascending order. Keys are paired with their
+/// index, which breaks ties, so the result matches a stable sort.
+fn smallest_k<K: PartialOrd>(mut keyed: Vec<(K, usize)>, k: usize) -> Vec<usize> {
+    if k < keyed.len() {
+        // Everything before the k-th smallest key is smaller than it
+        introselect(&mut keyed, k);
+        keyed.truncate(k);
+    }
+    quick_sort_in_place(&mut keyed);
+    keyed.into_iter().map(|(_, index)| index).collect()
+}
+
+/// An `f64` ordered like [`sort_f64`] with `NaN`s last, so that it can use the algorithms that
+/// need `Ord` or a consistent `PartialOrd`.
+#[derive(Debug, Clone, Copy)]
+struct TotalF64(f64);
+
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        nan_order(self.0.is_nan(), other.0.is_nan(), NanPlacement::Last).unwrap_or_else(|| self.0.total_cmp(&other.0))
     }
+}
+
+/// Quickselect loop of [`nth_element`], built from the pdqsort pieces: partitions `v` and keeps
+/// only the side that contains index `n`.
+fn introselect<'a, T: PartialOrd>(mut v: &'a mut [T], mut n: usize) {
+    // Slices of up to this length are finished with insertion sort
+    const MAX_INSERTION: usize = 10;
+
+    // Smallest value of the parent partitions, as in `pdq_recurse`
+    let mut pred: Option<&'a T> = None;
+    let mut limit = usize::BITS - v.len().leading_zeros();
+    let mut was_balanced = true;
 
-    // Recursively sort the left and right arrays
-    let mut sorted_left = quick_sort(left);
-    let sorted_right = quick_sort(right);
+    loop {
+        let len = v.len();
+        if len <= MAX_INSERTION {
+            insertion_sort(v);
+            return;
+        }
+        if limit == 0 {
+            heapsort(v);
+            return;
+        }
+        if !was_balanced {
+            break_patterns(v);
+            limit -= 1;
+        }
+
+        let (pivot, _) = choose_pivot(v);
 
-    // Combine sorted left, pivot, and sorted right into a single sorted array
-    sorted_left.push(pivot); // Insert the pivot back in
-    sorted_left.extend(sorted_right); // Append sorted right
+        // Many elements equal to the smallest value: skip all of them at once
+        if let Some(pred) = pred {
+            if !is_less(pred, &v[pivot]) {
+                let mid = partition_equal(v, pivot);
+                if n < mid {
+                    return;
+                }
+                v = &mut std::mem::take(&mut v)[mid..];
+                n -= mid;
+                continue;
+            }
+        }
+
+        let (mid, _) = partition(v, pivot);
+        was_balanced = mid.min(len - mid) >= len / 8;
 
-    sorted_left // Return the fully sorted array
+        let (left, right) = std::mem::take(&mut v).split_at_mut(mid);
+        let (pivot, right) = right.split_at_mut(1);
+        match n.cmp(&mid) {
+            Ordering::Less => v = left,
+            Ordering::Equal => return,
+            Ordering::Greater => {
+                v = right;
+                n -= mid + 1;
+                pred = Some(&pivot[0]);
+            }
+        }
+    }
 }
 
-/// Sorts a vector of integers using the MergeSort algorithm.
+/// Sorts a slice using the MergeSort algorithm.
 ///
 /// This function implements the MergeSort algorithm, a comparison-based sorting algorithm
 /// that follows the divide-and-conquer paradigm. The input array is recursively divided into
@@ -82,17 +589,20 @@ pub fn quick_sort(mut arr: Vec<i32>) -> Vec<i32> {
 ///
 /// # Arguments
 ///
-/// * `arr` - A mutable vector `Vec<i32>` containing the list of integers to be sorted.
+/// * `arr` - A slice (`&[T]`) containing the values to be sorted. Any type implementing
+///   `PartialOrd + Clone` is accepted.
 ///
 /// # Returns
 ///
-/// * `Vec<i32>` - A sorted vector of integers in ascending order.
+/// * `Vec<T>` - A sorted vector in ascending order. Equal elements keep their original order.
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::array::merge_sort;
+///
 /// let arr = vec![3, 1, 4, 1, 5, 9, 2, 6, 5];
-/// let sorted_arr = merge_sort(arr);
-/// println!("{:?}", sorted_arr); // Outputs: [1, 1, 2, 3, 4, 5, 5, 6, 9]
+/// let sorted_arr = merge_sort(&arr);
+/// assert_eq!(sorted_arr, vec![1, 1, 2, 3, 4, 5, 5, 6, 9]);
 /// ```
 ///
 /// # Performance Considerations
@@ -104,80 +614,2828 @@ pub fn quick_sort(mut arr: Vec<i32>) -> Vec<i32> {
 ///
 /// - **Time Complexity**: O(n log n) in all cases (best, worst, average).
 /// - **Space Complexity**: O(n) due to the extra space used for the auxiliary array.
-#[wasm_bindgen]
-pub fn merge_sort(mut arr: Vec<i32>) -> Vec<i32> {
-    let len = arr.len();
-    if len <= 1 {
-        return arr;
-    }
+pub fn merge_sort<T: PartialOrd + Clone>(arr: &[T]) -> Vec<T> {
+    merge_sort_with(arr, &|a: &T, b: &T| a < b)
+}
 
-    let mut aux = arr.clone(); // Temporary auxiliary array for merging
-    merge_sort_recursive(&mut arr, &mut aux, 0, len);
-    arr
+/// Sorts a slice with the stable MergeSort, using a comparator function.
+///
+/// Same algorithm and guarantees as [`merge_sort`], but the order is defined by `compare`, like
+/// `slice::sort_by`. Elements for which `compare` returns `Ordering::Equal` keep their original
+/// order.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::merge_sort_by;
+///
+/// let words = vec!["pear", "fig", "apple", "kiwi"];
+/// let by_length = merge_sort_by(&words, |a, b| a.len().cmp(&b.len()));
+/// assert_eq!(by_length, vec!["fig", "pear", "kiwi", "apple"]);
+/// ```
+pub fn merge_sort_by<T: Clone, F: Fn(&T, &T) -> Ordering>(arr: &[T], compare: F) -> Vec<T> {
+    merge_sort_with(arr, &|a: &T, b: &T| compare(a, b) == Ordering::Less)
 }
-/// Recursively splits and sorts the array using MergeSort.
+
+/// Where `NaN` values go when sorting floats with [`sort_f64`] or [`sort_f32`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPlacement {
+    /// Every `NaN` comes before all numbers.
+    First,
+    /// Every `NaN` comes after all numbers, like `Float64Array.prototype.sort` in JavaScript.
+    #[default]
+    Last,
+}
+
+/// Sorts `f64` values with the stable MergeSort and a total order.
 ///
-/// This function divides the array into smaller subarrays and then merges them back together.
+/// [`merge_sort`] on floats leaves `NaN` wherever the comparisons happen to put it and treats
+/// `-0.0` and `0.0` as equal. This function instead orders:
 ///
-/// # Arguments
+/// * numbers ascending, with `-0.0` before `0.0` (as `Float64Array.prototype.sort` does);
+/// * every `NaN` first or last according to `nans`, keeping their original relative order.
 ///
-/// * `arr` - The array to be sorted.
-/// * `aux` - The auxiliary array used for temporary storage during merging.
-/// * `start` - The starting index of the subarray being processed.
-/// * `end` - The ending index of the subarray being processed.
+/// With `NanPlacement::Last` the result matches sorting a `Float64Array` in JavaScript.
 ///
-/// # Returns
+/// # Example
+/// ```rust
+/// use rust_scripts::array::{sort_f64, NanPlacement};
 ///
-/// This function does not return a value, as it operates in-place on the array.
-fn merge_sort_recursive(arr: &mut [i32], aux: &mut [i32], start: usize, end: usize) {
-    if end - start <= 1 {
-        return;
+/// let sorted = sort_f64(&[1.5, f64::NAN, 0.0, -0.0, -3.0], NanPlacement::First);
+/// assert!(sorted[0].is_nan());
+/// assert_eq!(&sorted[1..], &[-3.0, -0.0, 0.0, 1.5]);
+/// assert!(sorted[2].is_sign_negative() && sorted[3].is_sign_positive());
+/// ```
+pub fn sort_f64(arr: &[f64], nans: NanPlacement) -> Vec<f64> {
+    merge_sort_by(arr, |a, b| nan_order(a.is_nan(), b.is_nan(), nans).unwrap_or_else(|| a.total_cmp(b)))
+}
+
+/// Sorts `f32` values like [`sort_f64`]: numbers ascending with `-0.0` before `0.0`, `NaN`s
+/// first or last according to `nans`. Stable.
+///
+/// Inputs of at least [`RADIX_SORT_THRESHOLD`] elements are sorted with [`radix_sort_f32`],
+/// which produces the same result.
+pub fn sort_f32(arr: &[f32], nans: NanPlacement) -> Vec<f32> {
+    if arr.len() >= RADIX_SORT_THRESHOLD {
+        return radix_sort_f32(arr, nans);
     }
+    merge_sort_by(arr, |a, b| nan_order(a.is_nan(), b.is_nan(), nans).unwrap_or_else(|| a.total_cmp(b)))
+}
 
-    let mid = (start + end) / 2;
+/// Sorts `i32` values, picking the faster algorithm for the input size: [`merge_sort`] below
+/// [`RADIX_SORT_THRESHOLD`] elements, [`radix_sort_i32`] from there on. Both give the same result.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::sort_i32;
+///
+/// let input: Vec<i32> = (0..100_000).map(|i| (i * 7919) % 10_007 - 5_000).collect();
+/// let mut expected = input.clone();
+/// expected.sort();
+/// assert_eq!(sort_i32(&input), expected);
+/// ```
+pub fn sort_i32(arr: &[i32]) -> Vec<i32> {
+    if arr.len() >= RADIX_SORT_THRESHOLD {
+        radix_sort_i32(arr)
+    } else {
+        merge_sort(arr)
+    }
+}
 
-    // Recursively sort both halves
-    merge_sort_recursive(aux, arr, start, mid);
-    merge_sort_recursive(aux, arr, mid, end);
+/// Sorts `u32` values like [`sort_i32`]: [`merge_sort`] for short inputs, [`radix_sort_u32`] from
+/// [`RADIX_SORT_THRESHOLD`] elements on.
+pub fn sort_u32(arr: &[u32]) -> Vec<u32> {
+    if arr.len() >= RADIX_SORT_THRESHOLD {
+        radix_sort_u32(arr)
+    } else {
+        merge_sort(arr)
+    }
+}
 
-    // Merge sorted halves
-    merge(arr, aux, start, mid, end);
+/// Input length from which [`sort_i32`], [`sort_u32`] and [`sort_f32`] switch from MergeSort to
+/// radix sort.
+///
+/// Radix sort does four linear passes (each skipped when all keys share that byte) plus a
+/// histogram pass, so its cost per element is constant, while MergeSort's grows with log n. The
+/// crossover was measured with `cargo bench --bench sort`; below it the fixed cost of the
+/// histograms and the scratch buffer dominates.
+pub const RADIX_SORT_THRESHOLD: usize = 1 << 8;
+
+/// Sorts `u32` values with an LSD radix sort (four passes of 8 bits).
+///
+/// Runs in O(n) time with O(n) extra space, independent of the input order. See [`sort_u32`]
+/// for a version that uses MergeSort on short inputs.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::radix_sort_u32;
+///
+/// assert_eq!(radix_sort_u32(&[300, 7, u32::MAX, 0, 7]), vec![0, 7, 7, 300, u32::MAX]);
+/// ```
+pub fn radix_sort_u32(arr: &[u32]) -> Vec<u32> {
+    radix_sort_keys(arr.to_vec())
 }
 
-/// Merges two sorted subarrays back into a single sorted array.
+/// Sorts `i32` values with an LSD radix sort.
 ///
-/// This helper function takes two sorted subarrays and merges them into a single sorted subarray.
+/// Flipping the sign bit maps `i32` to `u32` keys in the same order (`i32::MIN` becomes 0), which
+/// are sorted with [`radix_sort_u32`] and mapped back.
+pub fn radix_sort_i32(arr: &[i32]) -> Vec<i32> {
+    let keys = arr.iter().map(|&v| v as u32 ^ SIGN_BIT).collect();
+    radix_sort_keys(keys).into_iter().map(|k| (k ^ SIGN_BIT) as i32).collect()
+}
+
+/// Sorts `f32` values with an LSD radix sort, with the same order as [`sort_f32`].
 ///
-/// # Arguments
+/// Uses the float bit-flip trick: for positive floats the sign bit is set, for negative floats
+/// every bit is inverted. The resulting `u32` keys sort in the same order as the numbers,
+/// including `-0.0` before `0.0`. `NaN`s are set aside first (keeping their order) and put back
+/// first or last according to `nans`.
 ///
-/// * `arr` - The array being sorted, which will hold the final merged result.
-/// * `aux` - The auxiliary array containing the sorted subarrays.
-/// * `start` - The starting index of the left subarray.
-/// * `mid` - The ending index of the left subarray, which is the starting index of the right subarray.
-/// * `end` - The ending index of the right subarray.
+/// # Example
+/// ```rust
+/// use rust_scripts::array::{radix_sort_f32, NanPlacement};
 ///
-/// # Returns
+/// let sorted = radix_sort_f32(&[2.5, f32::NAN, -1.0, f32::NEG_INFINITY], NanPlacement::Last);
+/// assert_eq!(&sorted[..3], &[f32::NEG_INFINITY, -1.0, 2.5]);
+/// assert!(sorted[3].is_nan());
+/// ```
+pub fn radix_sort_f32(arr: &[f32], nans: NanPlacement) -> Vec<f32> {
+    let (nan_values, numbers): (Vec<f32>, Vec<f32>) = arr.iter().partition(|v| v.is_nan());
+
+    let keys = numbers
+        .iter()
+        .map(|v| {
+            let bits = v.to_bits();
+            if bits & SIGN_BIT == 0 { bits | SIGN_BIT } else { !bits }
+        })
+        .collect();
+    let sorted = radix_sort_keys(keys).into_iter().map(|k| {
+        f32::from_bits(if k & SIGN_BIT != 0 { k ^ SIGN_BIT } else { !k })
+    });
+
+    match nans {
+        NanPlacement::First => nan_values.into_iter().chain(sorted).collect(),
+        NanPlacement::Last => sorted.chain(nan_values).collect(),
+    }
+}
+
+const SIGN_BIT: u32 = 1 << 31;
+
+/// LSD radix sort of `u32` keys, one byte per pass from the least significant.
 ///
-/// This function does not return a value, as it operates in-place on the array.
-fn merge(arr: &mut [i32], aux: &[i32], start: usize, mid: usize, end: usize) {
-    let (mut left, mut right) = (start, mid);
-    let mut idx = start;
+/// All four histograms are built in a single read of the input. A pass whose byte is the same
+/// for every key would not move anything and is skipped, so e.g. small non-negative values only
+/// cost one or two passes.
+fn radix_sort_keys(mut keys: Vec<u32>) -> Vec<u32> {
+    let len = keys.len();
+    let mut counts = [[0usize; 256]; 4];
+    for &key in &keys {
+        for (pass, count) in counts.iter_mut().enumerate() {
+            count[(key >> (pass * 8)) as usize & 0xff] += 1;
+        }
+    }
 
-    // Merge elements from aux (sorted) back into arr
-    while left < mid && right < end {
-        if aux[left] <= aux[right] {
-            arr[idx] = aux[left];
-            left += 1;
-        } else {
-            arr[idx] = aux[right];
-            right += 1;
+    let mut scratch = vec![0u32; len];
+    for (pass, count) in counts.iter().enumerate() {
+        if count.contains(&len) {
+            continue;
         }
-        idx += 1;
+
+        // Starting position of each bucket in the output
+        let mut offsets = [0usize; 256];
+        let mut total = 0;
+        for (offset, &n) in offsets.iter_mut().zip(count) {
+            *offset = total;
+            total += n;
+        }
+
+        // Scatter in input order, which keeps every pass stable
+        for &key in &keys {
+            let bucket = (key >> (pass * 8)) as usize & 0xff;
+            scratch[offsets[bucket]] = key;
+            offsets[bucket] += 1;
+        }
+        std::mem::swap(&mut keys, &mut scratch);
     }
+    keys
+}
 
-    // Copy any remaining elements from the left half
-    if left < mid {
-        arr[idx..end].copy_from_slice(&aux[left..mid]);
+/// Direction of [`argsort`] and the other argsort functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// Smallest first.
+    #[default]
+    Ascending,
+    /// Largest first. Equal elements still keep their original order.
+    Descending,
+}
+
+impl SortOrder {
+    /// Applies the direction to an ascending comparison.
+    fn apply(self, ordering: Ordering) -> Ordering {
+        match self {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    }
+}
+
+/// Returns the permutation that sorts `arr`: the index of the smallest element first (or of the
+/// largest, with `SortOrder::Descending`).
+///
+/// The sort is stable in both directions: equal elements keep the order of their indices. Pass
+/// the result to [`apply_permutation`] to reorder `arr`, or any other column of the same table.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::{apply_permutation, argsort, SortOrder};
+///
+/// let ages = [31, 25, 31, 19];
+/// let names = ["Ada", "Bob", "Cy", "Dee"];
+/// let order = argsort(&ages, SortOrder::Descending);
+/// assert_eq!(order, vec![0, 2, 1, 3]);
+/// assert_eq!(apply_permutation(&names, &order).unwrap(), vec!["Ada", "Cy", "Bob", "Dee"]);
+/// ```
+pub fn argsort<T: Ord>(arr: &[T], order: SortOrder) -> Vec<usize> {
+    argsort_by(arr, |a, b| order.apply(a.cmp(b)))
+}
+
+/// Returns the permutation that sorts `arr` by `compare`, stably. See [`argsort`].
+pub fn argsort_by<T, F: Fn(&T, &T) -> Ordering>(arr: &[T], compare: F) -> Vec<usize> {
+    let indices: Vec<usize> = (0..arr.len()).collect();
+    merge_sort_by(&indices, |&a, &b| compare(&arr[a], &arr[b]))
+}
+
+/// Returns the permutation that sorts `arr` like [`sort_f64`]: `-0.0` before `0.0` and `NaN`s
+/// first or last according to `nans`, whatever the direction.
+pub fn argsort_f64(arr: &[f64], order: SortOrder, nans: NanPlacement) -> Vec<usize> {
+    argsort_by(arr, |a, b| nan_order(a.is_nan(), b.is_nan(), nans).unwrap_or_else(|| order.apply(a.total_cmp(b))))
+}
+
+/// Returns the permutation that sorts `arr` like [`sort_f32`]. See [`argsort_f64`].
+pub fn argsort_f32(arr: &[f32], order: SortOrder, nans: NanPlacement) -> Vec<usize> {
+    argsort_by(arr, |a, b| nan_order(a.is_nan(), b.is_nan(), nans).unwrap_or_else(|| order.apply(a.total_cmp(b))))
+}
+
+/// Reorders `arr` by a permutation from one of the argsort functions: element `i` of the result
+/// is `arr[permutation[i]]`.
+///
+/// Returns `Error::InvalidInput` unless `permutation` contains every index of `arr` exactly once.
+pub fn apply_permutation<T: Clone>(arr: &[T], permutation: &[usize]) -> Result<Vec<T>> {
+    check_permutation(permutation, arr.len())?;
+    Ok(permutation.iter().map(|&i| arr[i].clone()).collect())
+}
+
+/// Returns `Error::InvalidInput` unless `permutation` is a permutation of `0..len`.
+fn check_permutation(permutation: &[usize], len: usize) -> Result<()> {
+    if permutation.len() != len {
+        return Err(Error::InvalidInput(format!(
+            "permutation has {} indices for {} elements",
+            permutation.len(),
+            len
+        )));
+    }
+    let mut seen = vec![false; len];
+    for &index in permutation {
+        match seen.get_mut(index) {
+            Some(seen @ false) => *seen = true,
+            Some(true) => return Err(Error::InvalidInput(format!("index {} appears twice in the permutation", index))),
+            None => return Err(Error::InvalidInput(format!("index {} is out of range for {} elements", index, len))),
+        }
+    }
+    Ok(())
+}
+
+/// One column of a table, as a key for [`argsort_multi`].
+#[derive(Debug, Clone, Copy)]
+pub enum Column<'a> {
+    I32(&'a [i32]),
+    U32(&'a [u32]),
+    /// Ordered like [`sort_f32`], `NaN`s last in both directions.
+    F32(&'a [f32]),
+    /// Ordered like [`sort_f64`], `NaN`s last in both directions.
+    F64(&'a [f64]),
+    /// Ordered by Unicode code point, like [`merge_sort`] on strings.
+    Strings(&'a [String]),
+}
+
+impl Column<'_> {
+    fn len(&self) -> usize {
+        match self {
+            Column::I32(values) => values.len(),
+            Column::U32(values) => values.len(),
+            Column::F32(values) => values.len(),
+            Column::F64(values) => values.len(),
+            Column::Strings(values) => values.len(),
+        }
+    }
+
+    /// Compares the values at rows `a` and `b` in ascending order.
+    fn compare(&self, a: usize, b: usize) -> Ordering {
+        match self {
+            Column::I32(values) => values[a].cmp(&values[b]),
+            Column::U32(values) => values[a].cmp(&values[b]),
+            Column::F32(values) => {
+                let (a, b) = (values[a], values[b]);
+                nan_order(a.is_nan(), b.is_nan(), NanPlacement::Last).unwrap_or_else(|| a.total_cmp(&b))
+            }
+            Column::F64(values) => {
+                let (a, b) = (values[a], values[b]);
+                nan_order(a.is_nan(), b.is_nan(), NanPlacement::Last).unwrap_or_else(|| a.total_cmp(&b))
+            }
+            Column::Strings(values) => values[a].cmp(&values[b]),
+        }
+    }
+}
+
+/// Returns the permutation that sorts the rows of a table lexicographically: by the first
+/// column, ties broken by the second, and so on. Each key has its own direction; `NaN`s stay last
+/// in both directions. Rows equal on every key keep their original order.
+///
+/// Returns `Error::InvalidInput` if the columns do not all have the same length. Without keys
+/// there is nothing to sort by and the result is empty.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::{argsort_multi, Column, SortOrder};
+///
+/// let team = [2, 1, 2, 1];
+/// let score = [7.5, 9.0, 8.0, 9.0];
+/// let order = argsort_multi(&[
+///     (Column::I32(&team), SortOrder::Ascending),
+///     (Column::F64(&score), SortOrder::Descending),
+/// ])
+/// .unwrap();
+/// assert_eq!(order, vec![1, 3, 2, 0]);
+/// ```
+pub fn argsort_multi(keys: &[(Column<'_>, SortOrder)]) -> Result<Vec<usize>> {
+    let Some((first, _)) = keys.first() else {
+        return Ok(Vec::new());
+    };
+    let len = first.len();
+    if let Some((column, _)) = keys.iter().find(|(column, _)| column.len() != len) {
+        return Err(Error::InvalidInput(format!(
+            "columns have different lengths ({} and {})",
+            len,
+            column.len()
+        )));
+    }
+
+    let indices: Vec<usize> = (0..len).collect();
+    Ok(merge_sort_by(&indices, |&a, &b| {
+        keys.iter()
+            .map(|(column, order)| {
+                let ordering = column.compare(a, b);
+                // Keep NaNs last in descending columns too
+                match column {
+                    Column::F32(values) if values[a].is_nan() || values[b].is_nan() => ordering,
+                    Column::F64(values) if values[a].is_nan() || values[b].is_nan() => ordering,
+                    _ => order.apply(ordering),
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }))
+}
+
+/// The value of one sort key in one record, as passed to [`argsort_records`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortValue {
+    /// A missing value (`null`, `undefined` or an absent field in JavaScript).
+    Null,
+    Number(f64),
+    Text(String),
+}
+
+/// How [`argsort_records`] compares the values of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Collation {
+    /// As numbers. Text is parsed as a decimal number (surrounding whitespace allowed); text that
+    /// is not a number, and `NaN`, count as [`SortValue::Null`].
+    Numeric,
+    /// As strings, by Unicode code point. Numbers are formatted first.
+    #[default]
+    String,
+    /// As strings, with runs of digits compared by their numeric value (see [`natural_cmp`]).
+    Natural,
+}
+
+/// Where [`argsort_records`] puts records whose value is [`SortValue::Null`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullPlacement {
+    /// Before every other value, whatever the direction of the key.
+    First,
+    /// After every other value, whatever the direction of the key.
+    #[default]
+    Last,
+}
+
+/// Direction, collation and placement of missing values of one key of [`argsort_records`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SortKey {
+    pub order: SortOrder,
+    pub collation: Collation,
+    pub nulls: NullPlacement,
+}
+
+/// A [`SortValue`] normalized for its key's collation, so that every comparison is cheap.
+enum Normalized {
+    Null,
+    Number(f64),
+    Text(String),
+}
+
+impl Normalized {
+    fn new(value: &SortValue, collation: Collation) -> Normalized {
+        match (value, collation) {
+            (SortValue::Null, _) => Normalized::Null,
+            (SortValue::Number(n), Collation::Numeric) if n.is_nan() => Normalized::Null,
+            (SortValue::Number(n), Collation::Numeric) => Normalized::Number(*n),
+            (SortValue::Text(text), Collation::Numeric) => match text.trim().parse::<f64>() {
+                Ok(n) if !n.is_nan() => Normalized::Number(n),
+                _ => Normalized::Null,
+            },
+            (SortValue::Number(n), _) => Normalized::Text(n.to_string()),
+            (SortValue::Text(text), _) => Normalized::Text(text.clone()),
+        }
+    }
+}
+
+/// Returns the permutation that sorts records by several keys: the first key, ties broken by the
+/// second, and so on. `columns` holds, for each key, its value in every record and how to compare
+/// them.
+///
+/// This is what sorting an array of objects by `user.lastName`, then `createdAt desc` needs once
+/// the field values have been extracted. The sort is the stable MergeSort of [`merge_sort_by`]:
+/// records equal on every key keep their original order. Missing values go first or last
+/// according to the key's [`NullPlacement`], independently of its direction.
+///
+/// Returns `Error::InvalidInput` if the columns do not all have the same length. Without keys the
+/// result is empty.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::{argsort_records, Collation, SortKey, SortOrder, SortValue};
+///
+/// let files = ["file10.txt", "file9.txt", "file1.txt"].map(|f| SortValue::Text(f.to_string()));
+/// let sizes = ["12", "", "3.5"].map(|s| SortValue::Text(s.to_string()));
+///
+/// let natural = SortKey { collation: Collation::Natural, ..SortKey::default() };
+/// assert_eq!(argsort_records(&[(&files, natural)]).unwrap(), vec![2, 1, 0]);
+///
+/// let by_size = SortKey { order: SortOrder::Descending, collation: Collation::Numeric, ..SortKey::default() };
+/// assert_eq!(argsort_records(&[(&sizes, by_size)]).unwrap(), vec![0, 2, 1]);
+/// ```
+pub fn argsort_records(columns: &[(&[SortValue], SortKey)]) -> Result<Vec<usize>> {
+    let Some((first, _)) = columns.first() else {
+        return Ok(Vec::new());
+    };
+    let len = first.len();
+    if let Some((column, _)) = columns.iter().find(|(column, _)| column.len() != len) {
+        return Err(Error::InvalidInput(format!(
+            "columns have different lengths ({} and {})",
+            len,
+            column.len()
+        )));
+    }
+
+    let normalized: Vec<Vec<Normalized>> = columns
+        .iter()
+        .map(|(column, key)| column.iter().map(|value| Normalized::new(value, key.collation)).collect())
+        .collect();
+    let compare_key = |values: &[Normalized], key: &SortKey, a: usize, b: usize| {
+        let null_vs_value = match key.nulls {
+            NullPlacement::First => Ordering::Less,
+            NullPlacement::Last => Ordering::Greater,
+        };
+        match (&values[a], &values[b]) {
+            (Normalized::Null, Normalized::Null) => Ordering::Equal,
+            (Normalized::Null, _) => null_vs_value,
+            (_, Normalized::Null) => null_vs_value.reverse(),
+            (Normalized::Number(x), Normalized::Number(y)) => key.order.apply(x.total_cmp(y)),
+            (Normalized::Text(x), Normalized::Text(y)) if key.collation == Collation::Natural => {
+                key.order.apply(natural_cmp(x, y))
+            }
+            (Normalized::Text(x), Normalized::Text(y)) => key.order.apply(x.cmp(y)),
+            // Only reachable for the string collations, where numbers were already formatted
+            (Normalized::Number(_), Normalized::Text(_)) => key.order.apply(Ordering::Less),
+            (Normalized::Text(_), Normalized::Number(_)) => key.order.apply(Ordering::Greater),
+        }
+    };
+
+    let indices: Vec<usize> = (0..len).collect();
+    Ok(merge_sort_by(&indices, |&a, &b| {
+        normalized
+            .iter()
+            .zip(columns)
+            .map(|(values, (_, key))| compare_key(values, key, a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }))
+}
+
+/// Compares two strings in natural order: runs of ASCII digits are compared by their numeric
+/// value, everything else by Unicode code point. So `"file9"` comes before `"file10"`.
+///
+/// Numbers of any length are supported (they are compared digit by digit, never parsed). When
+/// two strings differ only in leading zeros, the one with fewer zeros in the first such run
+/// comes first, so the order stays total: `"a1" < "a01" < "a001"`.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::natural_cmp;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(natural_cmp("img12.png", "img2.png"), Ordering::Greater);
+/// assert_eq!(natural_cmp("v1.10", "v1.9"), Ordering::Greater);
+/// ```
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    let mut leading_zeros = Ordering::Equal;
+    loop {
+        let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len()).then(leading_zeros);
+        };
+        if x.is_ascii_digit() && y.is_ascii_digit() {
+            let (digits_a, rest_a) = a.split_at(a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len()));
+            let (digits_b, rest_b) = b.split_at(b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len()));
+            let (value_a, value_b) = (digits_a.trim_start_matches('0'), digits_b.trim_start_matches('0'));
+            // Without leading zeros, a longer run is a larger number
+            let ordering = value_a.len().cmp(&value_b.len()).then_with(|| value_a.cmp(value_b));
+            if ordering.is_ne() {
+                return ordering;
+            }
+            if leading_zeros.is_eq() {
+                leading_zeros = digits_a.len().cmp(&digits_b.len());
+            }
+            (a, b) = (rest_a, rest_b);
+        } else {
+            if x != y {
+                return x.cmp(&y);
+            }
+            (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+        }
+    }
+}
+
+/// How [`sort_strings`] and [`argsort_strings`] compare strings. The default compares by Unicode
+/// code point, like [`merge_sort`] on strings; the options can be combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StringCollation {
+    /// Compare runs of digits by their numeric value, so `"img2"` comes before `"img10"` (see
+    /// [`natural_cmp`]).
+    pub natural: bool,
+    /// Ignore case (`"apple"` equals `"Apple"`).
+    pub case_insensitive: bool,
+    /// Order with the Unicode Collation Algorithm and the root locale instead of code points,
+    /// e.g. `"é"` between `"e"` and `"f"`. Needs the `collation` feature.
+    pub unicode: bool,
+    /// With `unicode`, ignore accents (`"resume"` equals `"résumé"`).
+    pub accent_insensitive: bool,
+}
+
+/// Sorts strings with the stable MergeSort according to `collation`. Strings that compare equal
+/// (e.g. differing only in case with `case_insensitive`) keep their original order.
+///
+/// Returns `Error::InvalidInput` if `collation` asks for the Unicode collation and the crate was
+/// built without the `collation` feature, or for `accent_insensitive` without `unicode`.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::{sort_strings, StringCollation};
+///
+/// let files = ["img10.png", "IMG2.png", "img1.png"];
+/// let collation = StringCollation { natural: true, case_insensitive: true, ..StringCollation::default() };
+/// assert_eq!(sort_strings(&files, collation).unwrap(), vec!["img1.png", "IMG2.png", "img10.png"]);
+/// ```
+pub fn sort_strings<S: AsRef<str> + Clone>(arr: &[S], collation: StringCollation) -> Result<Vec<S>> {
+    let permutation = argsort_strings(arr, SortOrder::Ascending, collation)?;
+    Ok(permutation.into_iter().map(|i| arr[i].clone()).collect())
+}
+
+/// Returns the stable sorting permutation of strings compared according to `collation`. See
+/// [`sort_strings`] and [`argsort`].
+pub fn argsort_strings<S: AsRef<str>>(arr: &[S], order: SortOrder, collation: StringCollation) -> Result<Vec<usize>> {
+    if collation.unicode {
+        #[cfg(feature = "collation")]
+        {
+            let collator = unicode_collator(collation)?;
+            return Ok(argsort_by(arr, |a, b| order.apply(collator.compare(a.as_ref(), b.as_ref()))));
+        }
+        #[cfg(not(feature = "collation"))]
+        return Err(Error::InvalidInput("Unicode collation needs the `collation` feature".to_string()));
+    }
+    if collation.accent_insensitive {
+        return Err(Error::InvalidInput("accent-insensitive comparison needs the Unicode collation".to_string()));
+    }
+
+    // Fold the case once instead of at every comparison
+    let folded: Vec<String>;
+    let keys: Vec<&str> = if collation.case_insensitive {
+        folded = arr.iter().map(|s| s.as_ref().to_lowercase()).collect();
+        folded.iter().map(String::as_str).collect()
+    } else {
+        arr.iter().map(AsRef::as_ref).collect()
+    };
+    Ok(if collation.natural {
+        argsort_by(&keys, |a, b| order.apply(natural_cmp(a, b)))
+    } else {
+        argsort_by(&keys, |a, b| order.apply(a.cmp(b)))
+    })
+}
+
+/// Creates the root-locale collator for the Unicode options of `collation`.
+#[cfg(feature = "collation")]
+fn unicode_collator(collation: StringCollation) -> Result<icu_collator::CollatorBorrowed<'static>> {
+    use icu_collator::options::{CaseLevel, CollatorOptions, Strength};
+    use icu_collator::preferences::CollationNumericOrdering;
+    use icu_collator::{Collator, CollatorPreferences};
+
+    let mut preferences = CollatorPreferences::default();
+    if collation.natural {
+        preferences.numeric_ordering = Some(CollationNumericOrdering::True);
+    }
+    let mut options = CollatorOptions::default();
+    // Primary strength compares base letters only, secondary adds accents, tertiary adds case
+    options.strength = Some(match (collation.accent_insensitive, collation.case_insensitive) {
+        (true, _) => Strength::Primary,
+        (false, true) => Strength::Secondary,
+        (false, false) => Strength::Tertiary,
+    });
+    if collation.accent_insensitive && !collation.case_insensitive {
+        // Ignore accents but still tell "a" from "A"
+        options.case_level = Some(CaseLevel::On);
+    }
+    Collator::try_new(preferences, options).map_err(|e| Error::InvalidInput(format!("Unicode collation unavailable: {}", e)))
+}
+
+/// Returns the index of the first element equal to `value` in the sorted slice `arr`, or `None`
+/// if there is none.
+///
+/// `arr` must be sorted in ascending order, e.g. by [`merge_sort`] or [`sort_f64`]; otherwise the
+/// result is meaningless (but there is no panic). Runs in O(log n). Unlike
+/// `slice::binary_search`, the index is always the first of a run of equal elements.
+///
+/// All the searches compare with `PartialOrd`, and treat values that are not comparable with
+/// themselves (`NaN`) as equal to each other and greater than everything else, which is where
+/// [`sort_f64`] puts them by default. `-0.0` and `0.0` are equal.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::{binary_search, count_in_range, equal_range};
+///
+/// let sorted = [1, 3, 3, 3, 8, 13];
+/// assert_eq!(binary_search(&sorted, &3), Some(1));
+/// assert_eq!(binary_search(&sorted, &4), None);
+/// assert_eq!(equal_range(&sorted, &3), 1..4);
+/// assert_eq!(count_in_range(&sorted, &2, &8), 4);
+/// ```
+pub fn binary_search<T: PartialOrd>(arr: &[T], value: &T) -> Option<usize> {
+    let index = lower_bound(arr, value);
+    (index < arr.len() && search_cmp(&arr[index], value).is_eq()).then_some(index)
+}
+
+/// Returns the index of the first element of the sorted slice `arr` that is not less than
+/// `value`, i.e. where `value` would be inserted before any equal elements. See
+/// [`binary_search`].
+pub fn lower_bound<T: PartialOrd>(arr: &[T], value: &T) -> usize {
+    arr.partition_point(|x| search_cmp(x, value).is_lt())
+}
+
+/// Returns the index of the first element of the sorted slice `arr` that is greater than
+/// `value`, i.e. where `value` would be inserted after any equal elements. See
+/// [`binary_search`].
+pub fn upper_bound<T: PartialOrd>(arr: &[T], value: &T) -> usize {
+    arr.partition_point(|x| search_cmp(x, value).is_le())
+}
+
+/// Returns the range of indices of the sorted slice `arr` whose elements equal `value`, empty
+/// (at the insertion point) if there are none. See [`binary_search`].
+pub fn equal_range<T: PartialOrd>(arr: &[T], value: &T) -> Range<usize> {
+    let start = lower_bound(arr, value);
+    start..start + upper_bound(&arr[start..], value)
+}
+
+/// Counts the elements of the sorted slice `arr` between `low` and `high`, both included. Zero
+/// if `low > high`. See [`binary_search`].
+pub fn count_in_range<T: PartialOrd>(arr: &[T], low: &T, high: &T) -> usize {
+    upper_bound(arr, high).saturating_sub(lower_bound(arr, low))
+}
+
+/// Answers [`equal_range`] for every value of `queries` in one call: the lower and upper bound
+/// of each query. The result has one range per query, in the same order.
+///
+/// Runs in O(q log n) for q queries. When `queries` is sorted too, each search starts from the
+/// previous lower bound instead of the beginning of `arr`.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::equal_range_batch;
+///
+/// let sorted = [10, 20, 20, 30];
+/// assert_eq!(equal_range_batch(&sorted, &[20, 5, 30, 40]), vec![1..3, 0..0, 3..4, 4..4]);
+/// ```
+pub fn equal_range_batch<T: PartialOrd>(arr: &[T], queries: &[T]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::with_capacity(queries.len());
+    let mut previous: Option<(&T, usize)> = None;
+    for query in queries {
+        // Everything before the previous lower bound is less than a smaller-or-equal query
+        let from = match previous {
+            Some((last, start)) if search_cmp(last, query).is_le() => start,
+            _ => 0,
+        };
+        let range = equal_range(&arr[from..], query);
+        let range = from + range.start..from + range.end;
+        previous = Some((query, range.start));
+        ranges.push(range);
+    }
+    ranges
+}
+
+/// Returns the sorted slice `arr` without repeated elements: the first of each run of equal
+/// elements is kept.
+///
+/// This and the set operations below work on inputs sorted in ascending order (e.g. by
+/// [`merge_sort`] or [`sort_f64`]) in a single linear pass, and treat them as sets: duplicates
+/// in the inputs are ignored and every result is sorted and duplicate-free. Equality is the one
+/// of [`binary_search`] (`-0.0 == 0.0`, `NaN`s equal to each other). On unsorted inputs the
+/// results are meaningless, but there is no panic.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::{difference, intersection, is_subset, union, unique};
+///
+/// let a = [1, 2, 2, 3, 5];
+/// let b = [2, 3, 4];
+/// assert_eq!(unique(&a), vec![1, 2, 3, 5]);
+/// assert_eq!(union(&a, &b), vec![1, 2, 3, 4, 5]);
+/// assert_eq!(intersection(&a, &b), vec![2, 3]);
+/// assert_eq!(difference(&a, &b), vec![1, 5]);
+/// assert!(is_subset(&[2, 3], &a));
+/// ```
+pub fn unique<T: PartialOrd + Clone>(arr: &[T]) -> Vec<T> {
+    let mut values = arr.to_vec();
+    dedup(&mut values);
+    values
+}
+
+/// Removes repeated elements from the sorted vector `arr` in place, like [`unique`].
+pub fn dedup<T: PartialOrd>(arr: &mut Vec<T>) {
+    arr.dedup_by(|a, b| search_cmp(a, b).is_eq());
+}
+
+/// Returns the elements that are in `a`, in `b` or in both. See [`unique`].
+pub fn union<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    merge_sets(a, b, SetParts { only_a: true, both: true, only_b: true })
+}
+
+/// Returns the elements that are in both `a` and `b`. See [`unique`].
+pub fn intersection<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    merge_sets(a, b, SetParts { only_a: false, both: true, only_b: false })
+}
+
+/// Returns the elements of `a` that are not in `b`. See [`unique`].
+pub fn difference<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    merge_sets(a, b, SetParts { only_a: true, both: false, only_b: false })
+}
+
+/// Returns the elements that are in exactly one of `a` and `b`. See [`unique`].
+pub fn symmetric_difference<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    merge_sets(a, b, SetParts { only_a: true, both: false, only_b: true })
+}
+
+/// Returns `true` if every element of `a` is also in `b`. Runs in O(|a| log |b|) without
+/// allocating. See [`unique`].
+pub fn is_subset<T: PartialOrd>(a: &[T], b: &[T]) -> bool {
+    let mut rest = b;
+    a.iter().all(|value| {
+        // Later elements of `a` are not smaller, so the search can resume from here
+        rest = &rest[lower_bound(rest, value)..];
+        rest.first().is_some_and(|found| search_cmp(found, value).is_eq())
+    })
+}
+
+/// Which elements [`merge_sets`] keeps.
+struct SetParts {
+    only_a: bool,
+    both: bool,
+    only_b: bool,
+}
+
+/// Walks the sorted slices `a` and `b` together, one distinct value at a time, and keeps the
+/// values selected by `parts`. Values in both are taken from `a`.
+fn merge_sets<T: PartialOrd + Clone>(a: &[T], b: &[T], parts: SetParts) -> Vec<T> {
+    // Index of the next element that differs from `arr[i]`
+    let next_distinct = |arr: &[T], i: usize| i + 1 + arr[i + 1..].iter().take_while(|v| search_cmp(*v, &arr[i]).is_eq()).count();
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        let ordering = match (a.get(i), b.get(j)) {
+            (Some(x), Some(y)) => search_cmp(x, y),
+            (Some(_), None) => Ordering::Less,
+            _ => Ordering::Greater,
+        };
+        match ordering {
+            Ordering::Less => {
+                if parts.only_a {
+                    result.push(a[i].clone());
+                }
+                i = next_distinct(a, i);
+            }
+            Ordering::Greater => {
+                if parts.only_b {
+                    result.push(b[j].clone());
+                }
+                j = next_distinct(b, j);
+            }
+            Ordering::Equal => {
+                if parts.both {
+                    result.push(a[i].clone());
+                }
+                i = next_distinct(a, i);
+                j = next_distinct(b, j);
+            }
+        }
+    }
+    result
+}
+
+/// Merges any number of sorted inputs into one sorted vector, keeping duplicates.
+///
+/// The inputs are copied once into a single buffer, then adjacent runs are merged pairwise with
+/// the merge step of [`merge_sort`], halving the number of runs at every round. For `k` inputs
+/// and `n` elements in total this takes O(n log k) time, against O(n log n) for sorting the
+/// concatenation, and O(n) extra space. The merge is stable: equal elements (including `-0.0`
+/// and `0.0`) come from earlier inputs first. Equality and the place of `NaN` are the ones of
+/// [`binary_search`].
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::merge_sorted;
+///
+/// let merged = merge_sorted(&[vec![1, 4, 9], vec![2, 3], vec![], vec![0, 4]]);
+/// assert_eq!(merged, vec![0, 1, 2, 3, 4, 4, 9]);
+/// ```
+pub fn merge_sorted<T: PartialOrd + Clone, S: AsRef<[T]>>(inputs: &[S]) -> Vec<T> {
+    let mut merged: Vec<T> = inputs.iter().flat_map(|input| input.as_ref().iter().cloned()).collect();
+    // Run `r` is `merged[bounds[r]..bounds[r + 1]]`
+    let mut bounds = vec![0];
+    for input in inputs {
+        bounds.push(bounds[bounds.len() - 1] + input.as_ref().len());
+    }
+
+    let is_less = |a: &T, b: &T| search_cmp(a, b).is_lt();
+    let mut target = merged.clone();
+    while bounds.len() > 2 {
+        let mut next_bounds = Vec::with_capacity(bounds.len() / 2 + 1);
+        for runs in bounds.windows(3).step_by(2) {
+            merge(&mut target, &merged, runs[0], runs[1], runs[2], &is_less);
+            next_bounds.push(runs[0]);
+        }
+        // An odd run out is copied as it is
+        if bounds.len() % 2 == 0 {
+            let (start, end) = (bounds[bounds.len() - 2], bounds[bounds.len() - 1]);
+            target[start..end].clone_from_slice(&merged[start..end]);
+            next_bounds.push(start);
+        }
+        next_bounds.push(bounds[bounds.len() - 1]);
+
+        std::mem::swap(&mut merged, &mut target);
+        bounds = next_bounds;
+    }
+    merged
+}
+
+/// Ordering of the binary searches: `partial_cmp`, with values that are not comparable with
+/// themselves (`NaN`) equal to each other and after everything else.
+fn search_cmp<T: PartialOrd>(x: &T, value: &T) -> Ordering {
+    x.partial_cmp(value).unwrap_or_else(|| {
+        let x_is_nan = x.partial_cmp(x).is_none();
+        let value_is_nan = value.partial_cmp(value).is_none();
+        nan_order(x_is_nan, value_is_nan, NanPlacement::Last).unwrap_or(Ordering::Equal)
+    })
+}
+
+/// Orders two floats when at least one of them is `NaN`; `None` if both are numbers.
+fn nan_order(a_is_nan: bool, b_is_nan: bool, nans: NanPlacement) -> Option<Ordering> {
+    let nan_vs_number = match nans {
+        NanPlacement::First => Ordering::Less,
+        NanPlacement::Last => Ordering::Greater,
+    };
+    match (a_is_nan, b_is_nan) {
+        (false, false) => None,
+        (true, true) => Some(Ordering::Equal),
+        (true, false) => Some(nan_vs_number),
+        (false, true) => Some(nan_vs_number.reverse()),
+    }
+}
+
+/// Shared implementation of the MergeSort variants, ordering by `is_less`.
+fn merge_sort_with<T: Clone, F: Fn(&T, &T) -> bool>(arr: &[T], is_less: &F) -> Vec<T> {
+    let mut arr = arr.to_vec();
+    let len = arr.len();
+    if len <= 1 {
+        return arr;
+    }
+
+    let mut aux = arr.clone(); // Temporary auxiliary array for merging
+    merge_sort_recursive(&mut arr, &mut aux, 0, len, is_less);
+    arr
+}
+/// Sorts a slice using MergeSort, splitting the work across the rayon thread pool.
+///
+/// This is the parallel counterpart of [`merge_sort`]: the two halves at each recursion level are
+/// sorted on different threads with `rayon::join`, and are then merged with the same `merge` helper.
+/// The result is identical to `merge_sort`, including the relative order of equal elements.
+///
+/// If the input is shorter than `parallel::MIN_PARALLEL_ELEMENTS` or no worker threads are available
+/// (e.g. a single-threaded wasm build), the function simply calls `merge_sort`.
+///
+/// # Arguments
+///
+/// * `arr` - A slice (`&[T]`) containing the values to be sorted.
+///
+/// # Returns
+///
+/// * `Vec<T>` - A sorted vector in ascending order.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::merge_sort_parallel;
+///
+/// let arr: Vec<i32> = (0..100_000).rev().collect();
+/// let sorted = merge_sort_parallel(&arr);
+/// assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+///
+/// # Performance Considerations
+///
+/// - **Time Complexity**: O(n log n) work, split over the available threads. The final merge at each
+///   level is sequential, so the speedup flattens out for very high thread counts.
+/// - **Space Complexity**: O(n), the same auxiliary array as `merge_sort`.
+#[cfg(feature = "parallel")]
+pub fn merge_sort_parallel<T: PartialOrd + Clone + Send + Sync>(arr: &[T]) -> Vec<T> {
+    if !parallel::should_parallelize(arr.len(), parallel::MIN_PARALLEL_ELEMENTS) {
+        return merge_sort(arr);
+    }
+
+    let mut arr = arr.to_vec();
+    let mut aux = arr.clone(); // Temporary auxiliary array for merging
+    par_merge_sort_recursive(&mut arr, &mut aux, &|a: &T, b: &T| a < b);
+    arr
+}
+
+/// Parallel version of `merge_sort_recursive` operating on whole sub-slices.
+///
+/// Sub-slices at or below `SEQUENTIAL_CUTOFF` elements are handed to the sequential recursion,
+/// since spawning tasks for them would cost more than sorting them.
+#[cfg(feature = "parallel")]
+fn par_merge_sort_recursive<T, F>(arr: &mut [T], aux: &mut [T], is_less: &F)
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> bool + Sync,
+{
+    const SEQUENTIAL_CUTOFF: usize = 4096;
+
+    let len = arr.len();
+    if len <= SEQUENTIAL_CUTOFF {
+        merge_sort_recursive(arr, aux, 0, len, is_less);
+        return;
+    }
+
+    let mid = len / 2;
+    {
+        let (arr_left, arr_right) = arr.split_at_mut(mid);
+        let (aux_left, aux_right) = aux.split_at_mut(mid);
+
+        // Sort both halves of `aux` in parallel, using `arr` as scratch space
+        rayon::join(
+            || par_merge_sort_recursive(aux_left, arr_left, is_less),
+            || par_merge_sort_recursive(aux_right, arr_right, is_less),
+        );
+    }
+
+    // Merge sorted halves
+    merge(arr, aux, 0, mid, len, is_less);
+}
+
+/// Sorts a slice like [`merge_sort`], reporting progress and allowing cancellation.
+///
+/// The result is identical to `merge_sort`. After roughly every 64K element moves (and once at the
+/// end) `progress` receives the fraction of merge work done and the number of element moves so
+/// far. If it returns `false`, sorting stops and `Error::Cancelled` is returned.
+///
+/// # Example
+/// ```rust
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use rust_scripts::{array::merge_sort_with_progress, Error};
+///
+/// let input: Vec<i32> = (0..100_000).rev().collect();
+/// let sorted = merge_sort_with_progress(&input, &mut |_fraction, _moves| true).unwrap();
+/// assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+///
+/// // e.g. set from another thread
+/// let cancel = AtomicBool::new(true);
+/// let result = merge_sort_with_progress(&input, &mut |_fraction, _moves| !cancel.load(Ordering::Relaxed));
+/// assert_eq!(result.unwrap_err(), Error::Cancelled);
+/// ```
+pub fn merge_sort_with_progress<T: PartialOrd + Clone>(arr: &[T], progress: &mut dyn Progress) -> Result<Vec<T>> {
+    let mut arr = arr.to_vec();
+    let len = arr.len();
+    if len <= 1 {
+        checkpoint(progress, 1.0, 0)?;
+        return Ok(arr);
+    }
+
+    let mut aux = arr.clone(); // Temporary auxiliary array for merging
+    let mut tracker = MergeProgress::new(progress, len);
+    merge_sort_recursive_with_progress(&mut arr, &mut aux, 0, len, &mut tracker)?;
+    checkpoint(tracker.progress, 1.0, tracker.moves)?;
+    Ok(arr)
+}
+
+/// Counts element moves during `merge_sort_with_progress` and reports them in batches.
+struct MergeProgress<'a> {
+    progress: &'a mut dyn Progress,
+    /// Total number of element moves: every recursion level moves each element once.
+    total: f64,
+    moves: usize,
+    next_update: usize,
+}
+
+impl<'a> MergeProgress<'a> {
+    const MOVES_PER_UPDATE: usize = 1 << 16;
+
+    fn new(progress: &'a mut dyn Progress, len: usize) -> Self {
+        let levels = (usize::BITS - (len - 1).leading_zeros()) as usize;
+        MergeProgress { progress, total: (len * levels) as f64, moves: 0, next_update: Self::MOVES_PER_UPDATE }
+    }
+
+    /// Records `moves` more element moves, reporting once a batch is complete.
+    fn advance(&mut self, moves: usize) -> Result<()> {
+        self.moves += moves;
+        if self.moves >= self.next_update {
+            self.next_update = self.moves + Self::MOVES_PER_UPDATE;
+            checkpoint(self.progress, self.moves as f64 / self.total, self.moves)?;
+        }
+        Ok(())
+    }
+}
+
+/// `merge_sort_recursive` with a progress update after every merge.
+fn merge_sort_recursive_with_progress<T: PartialOrd + Clone>(
+    arr: &mut [T],
+    aux: &mut [T],
+    start: usize,
+    end: usize,
+    tracker: &mut MergeProgress,
+) -> Result<()> {
+    if end - start <= 1 {
+        return Ok(());
+    }
+
+    let mid = (start + end) / 2;
+
+    // Recursively sort both halves
+    merge_sort_recursive_with_progress(aux, arr, start, mid, tracker)?;
+    merge_sort_recursive_with_progress(aux, arr, mid, end, tracker)?;
+
+    // Merge sorted halves
+    merge(arr, aux, start, mid, end, &|a: &T, b: &T| a < b);
+    tracker.advance(end - start)
+}
+
+/// Recursively splits and sorts the array using MergeSort.
+///
+/// This function divides the array into smaller subarrays and then merges them back together.
+///
+/// # Arguments
+///
+/// * `arr` - The array to be sorted.
+/// * `aux` - The auxiliary array used for temporary storage during merging.
+/// * `start` - The starting index of the subarray being processed.
+/// * `end` - The ending index of the subarray being processed.
+/// * `is_less` - The strict "less than" used to order the elements.
+///
+/// # Returns
+///
+/// This function does not return a value, as it operates in-place on the array.
+fn merge_sort_recursive<T: Clone, F: Fn(&T, &T) -> bool>(arr: &mut [T], aux: &mut [T], start: usize, end: usize, is_less: &F) {
+    if end - start <= 1 {
+        return;
+    }
+
+    let mid = (start + end) / 2;
+
+    // Recursively sort both halves
+    merge_sort_recursive(aux, arr, start, mid, is_less);
+    merge_sort_recursive(aux, arr, mid, end, is_less);
+
+    // Merge sorted halves
+    merge(arr, aux, start, mid, end, is_less);
+}
+
+/// Merges two sorted subarrays back into a single sorted array.
+///
+/// This helper function takes two sorted subarrays and merges them into a single sorted subarray.
+///
+/// # Arguments
+///
+/// * `arr` - The array being sorted, which will hold the final merged result.
+/// * `aux` - The auxiliary array containing the sorted subarrays.
+/// * `start` - The starting index of the left subarray.
+/// * `mid` - The ending index of the left subarray, which is the starting index of the right subarray.
+/// * `end` - The ending index of the right subarray.
+/// * `is_less` - The strict "less than" used to order the elements. Ties are taken from the left
+///   subarray first, which is what makes the sort stable.
+///
+/// # Returns
+///
+/// This function does not return a value, as it operates in-place on the array.
+fn merge<T: Clone, F: Fn(&T, &T) -> bool>(arr: &mut [T], aux: &[T], start: usize, mid: usize, end: usize, is_less: &F) {
+    let (mut left, mut right) = (start, mid);
+    let mut idx = start;
+
+    // Merge elements from aux (sorted) back into arr
+    while left < mid && right < end {
+        if !is_less(&aux[right], &aux[left]) {
+            arr[idx] = aux[left].clone();
+            left += 1;
+        } else {
+            arr[idx] = aux[right].clone();
+            right += 1;
+        }
+        idx += 1;
+    }
+
+    // Copy any remaining elements from the left half
+    if left < mid {
+        arr[idx..end].clone_from_slice(&aux[left..mid]);
+    }
+    // Copy any remaining elements from the right half (`arr` and `aux` swap roles at every
+    // recursion level, so they are not already in place)
+    if right < end {
+        arr[idx..end].clone_from_slice(&aux[right..end]);
+    }
+}
+
+/// Returns the index of the first element of `arr` that is smaller than the one before it (or
+/// not larger, if `strict`), or `None` if `arr` is sorted in ascending order.
+///
+/// Elements are compared as by the sorts: NaN is larger than every number and equal to NaN.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::first_unsorted_index;
+///
+/// assert_eq!(first_unsorted_index(&[1, 2, 2, 1], false), Some(3));
+/// assert_eq!(first_unsorted_index(&[1, 2, 2, 1], true), Some(2));
+/// assert_eq!(first_unsorted_index(&[1.0, 2.0, f64::NAN], true), None);
+/// ```
+pub fn first_unsorted_index<T: PartialOrd>(arr: &[T], strict: bool) -> Option<usize> {
+    let out_of_order = if strict { Ordering::is_ge } else { Ordering::is_gt };
+    arr.windows(2).position(|pair| out_of_order(search_cmp(&pair[0], &pair[1]))).map(|i| i + 1)
+}
+
+/// Returns `true` if `arr` is sorted in ascending order; with `strict`, also without repeated
+/// elements. See [`first_unsorted_index`].
+pub fn is_sorted<T: PartialOrd>(arr: &[T], strict: bool) -> bool {
+    first_unsorted_index(arr, strict).is_none()
+}
+
+/// Returns the number of inversions of `arr`, the pairs `i < j` with `arr[i] > arr[j]`: 0 for a
+/// sorted array, `n * (n - 1) / 2` for a strictly decreasing one. Equal elements are not
+/// inversions.
+///
+/// Counted during a merge sort of a copy of `arr`, in O(n log n): when an element of the right
+/// half is merged before the rest of the left half, it is smaller than all of them.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::count_inversions;
+///
+/// assert_eq!(count_inversions(&[3, 1, 2]), 2);
+/// assert_eq!(count_inversions(&[1, 1, 2]), 0);
+/// ```
+pub fn count_inversions<T: PartialOrd + Clone>(arr: &[T]) -> u64 {
+    let mut arr = arr.to_vec();
+    let mut aux = arr.clone();
+    count_inversions_recursive(&mut arr, &mut aux)
+}
+
+fn count_inversions_recursive<T: PartialOrd + Clone>(arr: &mut [T], aux: &mut [T]) -> u64 {
+    let len = arr.len();
+    if len <= 1 {
+        return 0;
+    }
+    let mid = len / 2;
+    let mut inversions = count_inversions_recursive(&mut arr[..mid], &mut aux[..mid])
+        + count_inversions_recursive(&mut arr[mid..], &mut aux[mid..]);
+
+    aux.clone_from_slice(arr);
+    let (mut left, mut right) = (0, mid);
+    for slot in arr.iter_mut() {
+        if right < len && (left == mid || search_cmp(&aux[right], &aux[left]).is_lt()) {
+            inversions += (mid - left) as u64;
+            *slot = aux[right].clone();
+            right += 1;
+        } else {
+            *slot = aux[left].clone();
+            left += 1;
+        }
+    }
+    inversions
+}
+
+/// Returns the indices of a longest increasing subsequence of `arr` (strictly increasing if
+/// `strict`, otherwise non-decreasing), in O(n log n).
+///
+/// When there are several, returns the one whose last element is the smallest. Elements are
+/// compared as in [`first_unsorted_index`].
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::longest_increasing_subsequence_indices;
+///
+/// assert_eq!(longest_increasing_subsequence_indices(&[3, 1, 4, 1, 5, 2, 6], true), vec![1, 2, 4, 6]);
+/// assert_eq!(longest_increasing_subsequence_indices(&[2, 2, 1, 2], false), vec![0, 1, 3]);
+/// ```
+pub fn longest_increasing_subsequence_indices<T: PartialOrd>(arr: &[T], strict: bool) -> Vec<usize> {
+    // `tails[l]` is the index of the smallest last element of an increasing subsequence of
+    // length `l + 1` seen so far; `previous[i]` links element `i` to the one before it
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![usize::MAX; arr.len()];
+    for (i, value) in arr.iter().enumerate() {
+        let length = tails.partition_point(|&t| {
+            let order = search_cmp(&arr[t], value);
+            if strict { order.is_lt() } else { order.is_le() }
+        });
+        if length > 0 {
+            previous[i] = tails[length - 1];
+        }
+        if length == tails.len() {
+            tails.push(i);
+        } else {
+            tails[length] = i;
+        }
+    }
+
+    let mut indices = Vec::with_capacity(tails.len());
+    let mut next = tails.last().copied();
+    while let Some(i) = next {
+        indices.push(i);
+        next = Some(previous[i]).filter(|&p| p != usize::MAX);
+    }
+    indices.reverse();
+    indices
+}
+
+/// Returns the elements of a longest increasing subsequence of `arr`. See
+/// [`longest_increasing_subsequence_indices`].
+pub fn longest_increasing_subsequence<T: PartialOrd + Clone>(arr: &[T], strict: bool) -> Vec<T> {
+    longest_increasing_subsequence_indices(arr, strict).into_iter().map(|i| arr[i].clone()).collect()
+}
+
+/// Number of elements per compressed block of a spilled run. While merging, one block per run
+/// is in memory.
+const SPILL_BLOCK_LENGTH: usize = 1 << 16;
+
+/// Storage for the runs an [`ExternalSorter`] moves out of memory.
+///
+/// Runs are written as LZ4-compressed blocks, each identified by an id that one sorter never
+/// reuses. While merging, every block is loaded exactly once, so a store can drop a block as
+/// soon as it has returned it. A `HashMap<u32, Vec<u8>>` is a store that keeps the compressed
+/// blocks in memory.
+pub trait RunStore {
+    /// Saves the compressed block `id`.
+    fn spill(&mut self, id: u32, bytes: Vec<u8>) -> Result<()>;
+
+    /// Returns the bytes previously saved as block `id`.
+    fn load(&mut self, id: u32) -> Result<Vec<u8>>;
+}
+
+impl RunStore for HashMap<u32, Vec<u8>> {
+    fn spill(&mut self, id: u32, bytes: Vec<u8>) -> Result<()> {
+        self.insert(id, bytes);
+        Ok(())
+    }
+
+    fn load(&mut self, id: u32) -> Result<Vec<u8>> {
+        self.remove(&id).ok_or_else(|| Error::InvalidInput(format!("block {} is not in the store", id)))
+    }
+}
+
+/// Fixed-size numbers an [`ExternalSorter`] can spill, stored little-endian.
+pub trait SpillElement: PartialOrd + Copy {
+    /// Size of one element in bytes.
+    const SIZE: usize;
+
+    fn write_le(self, out: &mut Vec<u8>);
+
+    /// Reads an element from exactly `SIZE` bytes.
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+impl SpillElement for i32 {
+    const SIZE: usize = 4;
+
+    fn write_le(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(bytes: &[u8]) -> i32 {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+impl SpillElement for u32 {
+    const SIZE: usize = 4;
+
+    fn write_le(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(bytes: &[u8]) -> u32 {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+impl SpillElement for f32 {
+    const SIZE: usize = 4;
+
+    fn write_le(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(bytes: &[u8]) -> f32 {
+        f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+impl SpillElement for f64 {
+    const SIZE: usize = 8;
+
+    fn write_le(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn read_le(bytes: &[u8]) -> f64 {
+        let mut le = [0; 8];
+        le.copy_from_slice(&bytes[..8]);
+        f64::from_le_bytes(le)
+    }
+}
+
+/// A sorted run: in memory, or the ids of its spilled blocks in order.
+enum Run<T> {
+    Memory(Vec<T>),
+    Spilled(Vec<u32>),
+}
+
+/// Sorts more data than fits in memory at once (an external merge sort).
+///
+/// Chunks are pushed as they arrive and collected into runs of `run_length` elements, each
+/// sorted with [`merge_sort_by`]. With a [`RunStore`] every sorted run is compressed and
+/// handed to the store, so memory holds at most one run while pushing; without one the runs
+/// stay in memory. [`ExternalSorter::finish`] then returns a [`MergedRuns`] that merges the
+/// runs incrementally, one output chunk at a time, keeping a single block of each spilled run
+/// in memory.
+///
+/// The sort is stable and orders NaN after every number.
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use rust_scripts::array::ExternalSorter;
+///
+/// let mut sorter = ExternalSorter::new(3).unwrap().with_store(HashMap::new());
+/// sorter.push(&[5, 1, 4]).unwrap();
+/// sorter.push(&[2, 6, 3, 0]).unwrap();
+///
+/// let mut merged = sorter.finish().unwrap();
+/// assert_eq!(merged.next_chunk(4).unwrap(), Some(vec![0, 1, 2, 3]));
+/// assert_eq!(merged.next_chunk(4).unwrap(), Some(vec![4, 5, 6]));
+/// assert_eq!(merged.next_chunk(4).unwrap(), None);
+/// ```
+pub struct ExternalSorter<T> {
+    run_length: usize,
+    pending: Vec<T>,
+    runs: Vec<Run<T>>,
+    store: Option<Box<dyn RunStore>>,
+    next_block: u32,
+    /// Set when a spill failed: part of the data may be lost, so the sorter refuses more work.
+    failed: bool,
+}
+
+impl<T: SpillElement> ExternalSorter<T> {
+    /// Creates a sorter that sorts runs of `run_length` elements in memory.
+    ///
+    /// Returns `Error::InvalidInput` if `run_length` is 0.
+    pub fn new(run_length: usize) -> Result<ExternalSorter<T>> {
+        if run_length == 0 {
+            return Err(Error::InvalidInput("run length must be at least 1".to_string()));
+        }
+        Ok(ExternalSorter { run_length, pending: Vec::new(), runs: Vec::new(), store: None, next_block: 0, failed: false })
+    }
+
+    /// Spills every sorted run to `store` instead of keeping it in memory.
+    pub fn with_store(mut self, store: impl RunStore + 'static) -> ExternalSorter<T> {
+        self.store = Some(Box::new(store));
+        self
+    }
+
+    /// Adds a chunk of elements, sorting (and spilling) a run whenever `run_length` elements
+    /// have been collected.
+    ///
+    /// Returns the error of the store if spilling fails. The sorter is then unusable: every
+    /// later call returns `Error::InvalidInput`, since the run being spilled may be incomplete.
+    pub fn push(&mut self, chunk: &[T]) -> Result<()> {
+        self.check_not_failed()?;
+        let mut chunk = chunk;
+        while !chunk.is_empty() {
+            let take = (self.run_length - self.pending.len()).min(chunk.len());
+            self.pending.extend_from_slice(&chunk[..take]);
+            chunk = &chunk[take..];
+            if self.pending.len() == self.run_length {
+                self.seal_run()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of sorted runs so far, not counting elements waiting for a full run.
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Sorts the last, partial run and starts merging. Blocks are loaded back from the store by
+    /// [`MergedRuns::next_chunk`].
+    ///
+    /// Returns the error of the store if spilling fails, or `Error::InvalidInput` after a failed
+    /// spill in [`ExternalSorter::push`].
+    pub fn finish(mut self) -> Result<MergedRuns<T>> {
+        self.check_not_failed()?;
+        if !self.pending.is_empty() {
+            self.seal_run()?;
+        }
+        let mut merged = MergedRuns {
+            cursors: Vec::new(),
+            heads: BinaryHeap::new(),
+            store: self.store,
+            taken: Vec::new(),
+            stalled: Vec::new(),
+        };
+        for (run, sorted) in self.runs.into_iter().enumerate() {
+            let (block, blocks) = match sorted {
+                Run::Memory(values) => (values, VecDeque::new()),
+                Run::Spilled(ids) => (Vec::new(), VecDeque::from(ids)),
+            };
+            merged.cursors.push(RunCursor { block, position: 0, blocks });
+            merged.stalled.push(run);
+        }
+        Ok(merged)
+    }
+
+    fn seal_run(&mut self) -> Result<()> {
+        let sorted = merge_sort_by(&self.pending, search_cmp);
+        let Some(store) = self.store.as_mut() else {
+            self.pending.clear();
+            self.runs.push(Run::Memory(sorted));
+            return Ok(());
+        };
+        let mut ids = Vec::new();
+        for block in sorted.chunks(SPILL_BLOCK_LENGTH) {
+            let mut raw = Vec::with_capacity(block.len() * T::SIZE);
+            block.iter().for_each(|value| value.write_le(&mut raw));
+            if let Err(error) = store.spill(self.next_block, lz4_flex::compress_prepend_size(&raw)) {
+                self.failed = true;
+                return Err(error);
+            }
+            ids.push(self.next_block);
+            self.next_block += 1;
+        }
+        self.pending.clear();
+        self.runs.push(Run::Spilled(ids));
+        Ok(())
+    }
+
+    fn check_not_failed(&self) -> Result<()> {
+        if self.failed {
+            return Err(Error::InvalidInput("the sorter cannot be used after a failed spill".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Position in one run while merging; `block` is the part of the run currently in memory.
+struct RunCursor<T> {
+    block: Vec<T>,
+    position: usize,
+    blocks: VecDeque<u32>,
+}
+
+/// The smallest unmerged element of a run. Equal values are ordered by run, which keeps the
+/// merge stable.
+struct Head<T> {
+    value: T,
+    run: usize,
+}
+
+impl<T: PartialOrd> Ord for Head<T> {
+    fn cmp(&self, other: &Head<T>) -> Ordering {
+        // Reversed, so that `BinaryHeap` (a max-heap) pops the smallest head first
+        search_cmp(&other.value, &self.value).then(other.run.cmp(&self.run))
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Head<T> {
+    fn partial_cmp(&self, other: &Head<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd> PartialEq for Head<T> {
+    fn eq(&self, other: &Head<T>) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<T: PartialOrd> Eq for Head<T> {}
+
+/// The sorted output of an [`ExternalSorter`], produced incrementally.
+pub struct MergedRuns<T> {
+    cursors: Vec<RunCursor<T>>,
+    heads: BinaryHeap<Head<T>>,
+    store: Option<Box<dyn RunStore>>,
+    /// Merged elements not returned yet because loading a block failed.
+    taken: Vec<T>,
+    /// Runs whose next element is not on the heap yet: every run before the first call, or
+    /// the run whose block could not be loaded.
+    stalled: Vec<usize>,
+}
+
+impl<T: SpillElement> MergedRuns<T> {
+    /// Returns the next (at most) `max` elements in sorted order, or `None` once every element
+    /// has been returned.
+    ///
+    /// Returns `Error::InvalidInput` if `max` is 0 or the store returns a block of the wrong
+    /// size, `Error::Decompression` if a block is corrupted, or the error of the store. Nothing
+    /// is lost on error: the elements merged so far are returned by the next call, which first
+    /// loads the failed block again, so a transient store error can simply be retried.
+    pub fn next_chunk(&mut self, max: usize) -> Result<Option<Vec<T>>> {
+        if max == 0 {
+            return Err(Error::InvalidInput("chunk size must be at least 1".to_string()));
+        }
+        while let Some(&run) = self.stalled.last() {
+            self.advance(run)?;
+            self.stalled.pop();
+        }
+        if self.heads.is_empty() && self.taken.is_empty() {
+            return Ok(None);
+        }
+        let mut out = std::mem::take(&mut self.taken);
+        while out.len() < max {
+            let Some(head) = self.heads.pop() else {
+                break;
+            };
+            out.push(head.value);
+            if let Err(error) = self.advance(head.run) {
+                // Keep what was merged so far for the next call, which retries this run first
+                self.stalled.push(head.run);
+                self.taken = out;
+                return Err(error);
+            }
+        }
+        if out.len() > max {
+            self.taken = out.split_off(max);
+        }
+        Ok(Some(out))
+    }
+
+    /// Pushes the next element of `run` onto the heap, loading its next block if needed. On
+    /// error the cursor is unchanged, so the call can be retried.
+    fn advance(&mut self, run: usize) -> Result<()> {
+        let cursor = &mut self.cursors[run];
+        while cursor.position == cursor.block.len() {
+            let Some(&id) = cursor.blocks.front() else {
+                cursor.block = Vec::new();
+                return Ok(());
+            };
+            let store = self.store.as_mut().expect("spilled runs always have a store");
+            cursor.block = decode_block(&store.load(id)?)?;
+            cursor.blocks.pop_front();
+            cursor.position = 0;
+        }
+        self.heads.push(Head { value: cursor.block[cursor.position], run });
+        cursor.position += 1;
+        Ok(())
+    }
+}
+
+fn decode_block<T: SpillElement>(bytes: &[u8]) -> Result<Vec<T>> {
+    let raw = lz4_flex::decompress_size_prepended(bytes).map_err(|e| Error::Decompression(e.to_string()))?;
+    if !raw.len().is_multiple_of(T::SIZE) {
+        return Err(Error::InvalidInput(format!("a block of {} bytes does not hold {}-byte elements", raw.len(), T::SIZE)));
+    }
+    Ok(raw.chunks_exact(T::SIZE).map(T::read_le).collect())
+}
+
+/// Returns the running totals of `arr`: element `i` is the sum of `arr[..=i]`.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::cumulative_sum;
+///
+/// assert_eq!(cumulative_sum(&[1.0, 2.0, 3.0]), vec![1.0, 3.0, 6.0]);
+/// ```
+pub fn cumulative_sum<T: Copy + Add<Output = T>>(arr: &[T]) -> Vec<T> {
+    scan(arr, |total, value| total + value)
+}
+
+/// Returns the running products of `arr`: element `i` is the product of `arr[..=i]`.
+pub fn cumulative_product<T: Copy + Mul<Output = T>>(arr: &[T]) -> Vec<T> {
+    scan(arr, |total, value| total * value)
+}
+
+/// Returns the running minimum of `arr`. A value that is not comparable with itself (NaN) is
+/// the minimum from its position on, as it would be in a running sum.
+pub fn cumulative_min<T: PartialOrd + Copy>(arr: &[T]) -> Vec<T> {
+    scan(arr, |low, value| if value < low || is_incomparable(&value) { value } else { low })
+}
+
+/// Returns the running maximum of `arr`. NaN propagates as in [`cumulative_min`].
+pub fn cumulative_max<T: PartialOrd + Copy>(arr: &[T]) -> Vec<T> {
+    scan(arr, |high, value| if value > high || is_incomparable(&value) { value } else { high })
+}
+
+fn scan<T: Copy>(arr: &[T], step: impl Fn(T, T) -> T) -> Vec<T> {
+    let mut out = Vec::with_capacity(arr.len());
+    let mut iter = arr.iter().copied();
+    if let Some(first) = iter.next() {
+        out.push(first);
+        let mut acc = first;
+        for value in iter {
+            acc = step(acc, value);
+            out.push(acc);
+        }
+    }
+    out
+}
+
+/// `true` for values that do not even equal themselves, i.e. NaN.
+fn is_incomparable<T: PartialOrd>(value: &T) -> bool {
+    value.partial_cmp(value).is_none()
+}
+
+fn check_window(window: usize) -> Result<()> {
+    if window == 0 {
+        return Err(Error::InvalidInput("window must be at least 1".to_string()));
+    }
+    Ok(())
+}
+
+/// Sum of a sliding window of floats. Finite values are added and removed with Neumaier's
+/// compensation, so the result does not drift along long series, and non-finite values are
+/// counted instead of added, so a NaN or infinity only affects the windows that contain it.
+#[derive(Default)]
+struct WindowSum {
+    sum: f64,
+    compensation: f64,
+    nans: usize,
+    positive_infinities: usize,
+    negative_infinities: usize,
+}
+
+impl WindowSum {
+    fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            self.nans += 1;
+        } else if value == f64::INFINITY {
+            self.positive_infinities += 1;
+        } else if value == f64::NEG_INFINITY {
+            self.negative_infinities += 1;
+        } else {
+            let sum = self.sum + value;
+            self.compensation += if self.sum.abs() >= value.abs() { (self.sum - sum) + value } else { (value - sum) + self.sum };
+            self.sum = sum;
+        }
+    }
+
+    fn remove(&mut self, value: f64) {
+        match value {
+            v if v.is_nan() => self.nans -= 1,
+            f64::INFINITY => self.positive_infinities -= 1,
+            f64::NEG_INFINITY => self.negative_infinities -= 1,
+            v => self.add(-v),
+        }
+    }
+
+    fn value(&self) -> f64 {
+        match (self.nans, self.positive_infinities, self.negative_infinities) {
+            (0, 0, 0) => self.sum + self.compensation,
+            (0, 0, _) => f64::NEG_INFINITY,
+            (0, _, 0) => f64::INFINITY,
+            _ => f64::NAN,
+        }
+    }
+}
+
+/// Returns the sum of every window of `window` consecutive elements of `arr`, in O(n).
+///
+/// Element `i` of the result covers `arr[i..i + window]`, so there are `arr.len() - window + 1`
+/// of them (none if `arr` is shorter than the window). A NaN or infinity only affects the
+/// windows that contain it. Returns `Error::InvalidInput` if `window` is 0.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::rolling_sum;
+///
+/// assert_eq!(rolling_sum(&[1.0, 2.0, 3.0, 4.0], 2).unwrap(), vec![3.0, 5.0, 7.0]);
+/// ```
+pub fn rolling_sum(arr: &[f64], window: usize) -> Result<Vec<f64>> {
+    check_window(window)?;
+    if arr.len() < window {
+        return Ok(Vec::new());
+    }
+    let mut sum = WindowSum::default();
+    arr[..window - 1].iter().for_each(|&value| sum.add(value));
+    Ok(arr
+        .windows(window)
+        .map(|values| {
+            sum.add(values[window - 1]);
+            let total = sum.value();
+            sum.remove(values[0]);
+            total
+        })
+        .collect())
+}
+
+/// Returns the mean of every window of `window` consecutive elements of `arr`. See
+/// [`rolling_sum`].
+pub fn rolling_mean(arr: &[f64], window: usize) -> Result<Vec<f64>> {
+    Ok(rolling_sum(arr, window)?.into_iter().map(|sum| sum / window as f64).collect())
+}
+
+/// Returns the minimum of every window of `window` consecutive elements of `arr`, in O(n)
+/// with a monotonic queue. Windows containing NaN are NaN. Returns `Error::InvalidInput` if
+/// `window` is 0; see [`rolling_sum`] for the shape of the result.
+pub fn rolling_min<T: PartialOrd + Copy>(arr: &[T], window: usize) -> Result<Vec<T>> {
+    rolling_extreme(arr, window, |a, b| a <= b)
+}
+
+/// Returns the maximum of every window of `window` consecutive elements of `arr`. See
+/// [`rolling_min`].
+pub fn rolling_max<T: PartialOrd + Copy>(arr: &[T], window: usize) -> Result<Vec<T>> {
+    rolling_extreme(arr, window, |a, b| a >= b)
+}
+
+/// Sliding-window extreme: `queue` holds the indices of the candidates, whose values are in
+/// `keeps` order from front to back, so the front is the extreme of the current window.
+fn rolling_extreme<T: PartialOrd + Copy>(arr: &[T], window: usize, keeps: impl Fn(&T, &T) -> bool) -> Result<Vec<T>> {
+    check_window(window)?;
+    let mut out = Vec::with_capacity((arr.len() + 1).saturating_sub(window));
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    let mut last_incomparable = None;
+    for (i, value) in arr.iter().enumerate() {
+        if is_incomparable(value) {
+            last_incomparable = Some(i);
+        } else {
+            while queue.back().is_some_and(|&j| !keeps(&arr[j], value)) {
+                queue.pop_back();
+            }
+            queue.push_back(i);
+        }
+        if i + 1 < window {
+            continue;
+        }
+        let start = i + 1 - window;
+        while queue.front().is_some_and(|&j| j < start) {
+            queue.pop_front();
+        }
+        out.push(match last_incomparable {
+            Some(j) if j >= start => arr[j],
+            _ => arr[queue[0]],
+        });
+    }
+    Ok(out)
+}
+
+/// Splits `arr` into consecutive chunks of `size` elements; the last one may be shorter.
+///
+/// Returns `Error::InvalidInput` if `size` is 0.
+pub fn chunk<T: Clone>(arr: &[T], size: usize) -> Result<Vec<Vec<T>>> {
+    if size == 0 {
+        return Err(Error::InvalidInput("chunk size must be at least 1".to_string()));
+    }
+    Ok(arr.chunks(size).map(<[T]>::to_vec).collect())
+}
+
+/// Concatenates `parts` into one array, the inverse of [`chunk`].
+pub fn flatten<T: Clone, S: AsRef<[T]>>(parts: &[S]) -> Vec<T> {
+    let mut out = Vec::with_capacity(parts.iter().map(|part| part.as_ref().len()).sum());
+    for part in parts {
+        out.extend_from_slice(part.as_ref());
+    }
+    out
+}
+
+/// Pairs up the elements of `columns` by position: row `i` holds element `i` of every column.
+/// Like `Iterator::zip`, stops at the end of the shortest column.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::zip;
+///
+/// let rows = zip(&[vec![1, 2, 3], vec![10, 20]]);
+/// assert_eq!(rows, vec![vec![1, 10], vec![2, 20]]);
+/// ```
+pub fn zip<T: Clone, S: AsRef<[T]>>(columns: &[S]) -> Vec<Vec<T>> {
+    let len = columns.iter().map(|column| column.as_ref().len()).min().unwrap_or(0);
+    (0..len).map(|i| columns.iter().map(|column| column.as_ref()[i].clone()).collect()).collect()
+}
+
+/// Swaps the rows and columns of a rectangular table.
+///
+/// Unlike [`zip`], returns `Error::InvalidInput` if the rows have different lengths.
+pub fn transpose<T: Clone, S: AsRef<[T]>>(rows: &[S]) -> Result<Vec<Vec<T>>> {
+    if let Some(first) = rows.first() {
+        let width = first.as_ref().len();
+        if let Some((i, row)) = rows.iter().enumerate().find(|(_, row)| row.as_ref().len() != width) {
+            return Err(Error::InvalidInput(format!("row {} has {} elements, expected {}", i, row.as_ref().len(), width)));
+        }
+    }
+    Ok(zip(rows))
+}
+
+/// Aggregates of the values that share a key, returned by [`group_by`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group<K> {
+    pub key: K,
+    /// Number of values with this key.
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl<K> Group<K> {
+    /// Arithmetic mean of the values of the group.
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+/// Groups `values` by the key at the same position in `keys` and returns the count, sum,
+/// minimum and maximum of every group, sorted by key.
+///
+/// Groups are found by sorting (stable, O(n log n)), not hashing, so any `PartialOrd` key
+/// works; NaN keys form one group at the end. A NaN value makes the sum, mean, minimum and
+/// maximum of its group NaN. Returns `Error::InvalidInput` if `keys` and `values` differ in
+/// length.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::group_by;
+///
+/// let groups = group_by(&["b", "a", "b"], &[1.0, 2.0, 5.0]).unwrap();
+/// assert_eq!((groups[0].key, groups[0].count), ("a", 1));
+/// assert_eq!((groups[1].key, groups[1].sum, groups[1].max), ("b", 6.0, 5.0));
+/// ```
+pub fn group_by<K: PartialOrd + Clone>(keys: &[K], values: &[f64]) -> Result<Vec<Group<K>>> {
+    if keys.len() != values.len() {
+        return Err(Error::InvalidInput(format!("{} keys for {} values", keys.len(), values.len())));
+    }
+    Ok(group_runs(keys)
+        .into_iter()
+        .map(|(key, indices)| {
+            let mut group = Group { key, count: indices.len(), sum: 0.0, min: f64::INFINITY, max: f64::NEG_INFINITY };
+            for value in indices.iter().map(|&i| values[i]) {
+                group.sum += value;
+                // Once NaN, `value < group.min` is never true again, so NaN sticks
+                group.min = if value < group.min || value.is_nan() { value } else { group.min };
+                group.max = if value > group.max || value.is_nan() { value } else { group.max };
+            }
+            group
+        })
+        .collect())
+}
+
+/// Returns every distinct key of `keys` with the number of times it occurs, sorted by key.
+/// See [`group_by`].
+pub fn group_counts<K: PartialOrd + Clone>(keys: &[K]) -> Vec<(K, usize)> {
+    group_runs(keys).into_iter().map(|(key, indices)| (key, indices.len())).collect()
+}
+
+/// Sorts the indices of `keys` by key and splits them into runs of equal keys.
+fn group_runs<K: PartialOrd + Clone>(keys: &[K]) -> Vec<(K, Vec<usize>)> {
+    let order = argsort_by(keys, search_cmp);
+    let mut runs: Vec<(K, Vec<usize>)> = Vec::new();
+    for i in order {
+        match runs.last_mut() {
+            Some((key, indices)) if search_cmp(key, &keys[i]).is_eq() => indices.push(i),
+            _ => runs.push((keys[i].clone(), vec![i])),
+        }
+    }
+    runs
+}
+
+/// A seeded pseudo-random number generator for reproducible shuffles and samples.
+///
+/// The generator is xoshiro256**, seeded through SplitMix64: fast, with a period of 2^256 - 1
+/// and good statistical quality, but not suitable for cryptography. The same seed always gives
+/// the same sequence, on every platform and in the browser, so an A/B assignment or a sample
+/// can be reproduced from its seed.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::array::Random;
+///
+/// let mut a = Random::new(42);
+/// let mut b = Random::new(42);
+/// let mut cards: Vec<u32> = (1..=52).collect();
+/// let mut same_cards = cards.clone();
+/// a.shuffle(&mut cards);
+/// b.shuffle(&mut same_cards);
+/// assert_eq!(cards, same_cards);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Random {
+    state: [u64; 4],
+}
+
+impl Random {
+    /// Creates a generator from a 64-bit seed.
+    pub fn new(seed: u64) -> Random {
+        // SplitMix64 spreads any seed (including 0) over the whole state, which must not be zero
+        let mut x = seed;
+        let mut next = || {
+            x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Random { state: [next(), next(), next(), next()] }
+    }
+
+    /// Creates a generator from a text seed, e.g. an experiment name. The text is hashed with
+    /// 64-bit FNV-1a into the seed of [`Random::new`].
+    pub fn from_text(seed: &str) -> Random {
+        let hash = seed.bytes().fold(0xCBF2_9CE4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01B3)
+        });
+        Random::new(hash)
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Returns the next 32 random bits (the high half of [`Random::next_u64`]).
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Returns a uniformly distributed `f64` in `[0, 1)`, with 53 random bits.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Returns a uniformly distributed integer in `[0, bound)`, without modulo bias.
+    ///
+    /// Returns `Error::InvalidInput` if `bound` is 0.
+    pub fn below(&mut self, bound: u64) -> Result<u64> {
+        if bound == 0 {
+            return Err(Error::InvalidInput("bound must be greater than 0".to_string()));
+        }
+        Ok(self.below_unchecked(bound))
+    }
+
+    /// Lemire's multiply-and-reject method: the high half of `random * bound` is uniform once
+    /// the few low halves that would favour some results are rejected. `bound` must not be 0.
+    fn below_unchecked(&mut self, bound: u64) -> u64 {
+        let mut product = u128::from(self.next_u64()) * u128::from(bound);
+        if (product as u64) < bound {
+            let threshold = bound.wrapping_neg() % bound;
+            while (product as u64) < threshold {
+                product = u128::from(self.next_u64()) * u128::from(bound);
+            }
+        }
+        (product >> 64) as u64
+    }
+
+    /// Returns a uniformly distributed index in `0..len`. `len` must not be 0.
+    fn index(&mut self, len: usize) -> usize {
+        self.below_unchecked(len as u64) as usize
+    }
+
+    /// Shuffles `arr` in place with the Fisher–Yates algorithm: every permutation is equally
+    /// likely.
+    pub fn shuffle<T>(&mut self, arr: &mut [T]) {
+        for i in (1..arr.len()).rev() {
+            let j = self.index(i + 1);
+            arr.swap(i, j);
+        }
+    }
+
+    /// Returns `k` elements drawn uniformly at random from `arr`, in random order.
+    ///
+    /// Without replacement every element is drawn at most once (a partial Fisher–Yates shuffle
+    /// of the indices) and `k` may not exceed `arr.len()`. With replacement the same element
+    /// can be drawn several times and `arr` may only be empty if `k` is 0. Otherwise returns
+    /// `Error::InvalidInput`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rust_scripts::array::Random;
+    ///
+    /// let mut random = Random::from_text("experiment-7");
+    /// let users = ["ann", "bob", "cyd", "dan", "eve"];
+    /// let picked = random.sample(&users, 3, false).unwrap();
+    /// assert_eq!(picked.len(), 3);
+    /// assert!(picked.iter().all(|user| users.contains(user)));
+    /// ```
+    pub fn sample<T: Clone>(&mut self, arr: &[T], k: usize, with_replacement: bool) -> Result<Vec<T>> {
+        if with_replacement {
+            if arr.is_empty() && k > 0 {
+                return Err(Error::InvalidInput("cannot sample from an empty array".to_string()));
+            }
+            return Ok((0..k).map(|_| arr[self.index(arr.len())].clone()).collect());
+        }
+
+        if k > arr.len() {
+            return Err(Error::InvalidInput(format!("cannot sample {} of {} elements without replacement", k, arr.len())));
+        }
+        let mut indices: Vec<usize> = (0..arr.len()).collect();
+        for i in 0..k {
+            let j = i + self.index(arr.len() - i);
+            indices.swap(i, j);
+        }
+        Ok(indices[..k].iter().map(|&i| arr[i].clone()).collect())
+    }
+
+    /// Returns `k` elements of `arr` drawn with probability proportional to `weights`.
+    ///
+    /// With replacement each draw picks element `i` with probability `weights[i] / sum`
+    /// (binary search over the cumulative weights, O(n + k log n)). Without replacement the
+    /// elements are drawn one after the other from those not drawn yet, which is computed in
+    /// one pass with the Efraimidis–Spirakis keys `u^(1/w)`; then `k` may not exceed the number
+    /// of positive weights. Elements with weight 0 are never drawn.
+    ///
+    /// Returns `Error::InvalidInput` if `weights` and `arr` differ in length, a weight is
+    /// negative or not finite, or no weight is positive while `k > 0`.
+    pub fn weighted_sample<T: Clone>(&mut self, arr: &[T], weights: &[f64], k: usize, with_replacement: bool) -> Result<Vec<T>> {
+        if weights.len() != arr.len() {
+            return Err(Error::InvalidInput(format!("{} weights for {} elements", weights.len(), arr.len())));
+        }
+        if let Some(weight) = weights.iter().find(|w| !w.is_finite() || **w < 0.0) {
+            return Err(Error::InvalidInput(format!("weight {} is not a finite non-negative number", weight)));
+        }
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        let positive = weights.iter().filter(|&&w| w > 0.0).count();
+        if positive == 0 {
+            return Err(Error::InvalidInput("at least one weight must be positive".to_string()));
+        }
+
+        if with_replacement {
+            // Scaled by the largest weight so that the sum of finite weights cannot overflow
+            let largest = weights.iter().fold(0.0, |max: f64, &w| max.max(w));
+            let cumulative: Vec<f64> = weights
+                .iter()
+                .scan(0.0, |sum, w| {
+                    *sum += w / largest;
+                    Some(*sum)
+                })
+                .collect();
+            let total = cumulative[cumulative.len() - 1];
+            return Ok((0..k)
+                .map(|_| {
+                    let target = self.next_f64() * total;
+                    // Zero weights repeat the previous sum, so they are never the first one above
+                    let i = cumulative.partition_point(|&sum| sum <= target).min(arr.len() - 1);
+                    arr[i].clone()
+                })
+                .collect());
+        }
+
+        if k > positive {
+            return Err(Error::InvalidInput(format!(
+                "cannot sample {} elements without replacement, only {} have a positive weight",
+                k, positive
+            )));
+        }
+        // ln(u) / w orders like u^(1/w); `1 - next_f64()` is in (0, 1], so the log is finite
+        let keys: Vec<f64> = weights
+            .iter()
+            .map(|&w| if w > 0.0 { (1.0 - self.next_f64()).ln() / w } else { f64::NAN })
+            .collect();
+        Ok(top_k_indices_f64(&keys, k, SortOrder::Descending).into_iter().map(|i| arr[i].clone()).collect())
+    }
+
+    /// Returns `k` elements drawn uniformly without replacement from `items` in a single pass,
+    /// without knowing the length in advance (reservoir sampling, Algorithm R). Returns every
+    /// item if there are fewer than `k`.
+    ///
+    /// Useful for streams: memory is O(k) whatever the number of items.
+    pub fn reservoir_sample<T, I: IntoIterator<Item = T>>(&mut self, items: I, k: usize) -> Vec<T> {
+        let items = items.into_iter();
+        // `k` may be far larger than the stream (e.g. `usize::MAX` to keep everything)
+        let mut reservoir = Vec::with_capacity(k.min(items.size_hint().0));
+        for (seen, item) in items.enumerate() {
+            if seen < k {
+                reservoir.push(item);
+            } else {
+                // Keep the item with probability k / (seen + 1), replacing a random one
+                let j = self.index(seen + 1);
+                if j < k {
+                    reservoir[j] = item;
+                }
+            }
+        }
+        reservoir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    fn pseudo_random(len: usize) -> Vec<i32> {
+        (0..len as i64).map(|i| ((i * 7919 + 13) % 1009 - 500) as i32).collect()
+    }
+
+    #[test]
+    fn quick_sort_matches_std_sort() {
+        for len in [0, 1, 2, 3, 10, 257] {
+            let input = pseudo_random(len);
+            let mut expected = input.clone();
+            expected.sort();
+            assert_eq!(quick_sort(&input), expected, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn quick_sort_handles_adversarial_patterns() {
+        let len = 100_000;
+        let patterns: Vec<Vec<i32>> = vec![
+            (0..len).collect(),
+            (0..len).rev().collect(),
+            vec![7; len as usize],
+            (0..len).map(|i| i % 17).collect(),
+            (0..len).map(|i| if i % 2 == 0 { i } else { len - i }).collect(),
+            (0..len).map(|i| if i == len / 2 { -1 } else { i }).collect(),
+        ];
+        for input in patterns {
+            let mut expected = input.clone();
+            expected.sort();
+            assert_eq!(quick_sort(&input), expected);
+        }
+    }
+
+    #[test]
+    fn heapsort_fallback_sorts() {
+        let mut input = pseudo_random(1000);
+        let mut expected = input.clone();
+        expected.sort();
+        heapsort(&mut input);
+        assert_eq!(input, expected);
+    }
+
+    #[test]
+    fn quick_sort_in_place_tolerates_nan() {
+        let mut values: Vec<f64> = pseudo_random(500).into_iter().map(f64::from).collect();
+        values[10] = f64::NAN;
+        values[300] = f64::NAN;
+        quick_sort_in_place(&mut values);
+        assert_eq!(values.iter().filter(|v| v.is_nan()).count(), 2);
+    }
+
+    #[test]
+    fn merge_sort_matches_std_sort() {
+        for len in [0, 1, 2, 3, 10, 257] {
+            let input = pseudo_random(len);
+            let mut expected = input.clone();
+            expected.sort();
+            assert_eq!(merge_sort(&input), expected, "length {}", len);
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn merge_sort_parallel_matches_merge_sort() {
+        let input = pseudo_random(3 * parallel::MIN_PARALLEL_ELEMENTS + 17);
+        assert_eq!(merge_sort_parallel(&input), merge_sort(&input));
+        assert_eq!(merge_sort_parallel(&input[..100]), merge_sort(&input[..100]));
+    }
+
+    #[test]
+    fn merge_sort_with_progress_matches_merge_sort() {
+        let input = pseudo_random(200_000);
+        let mut last = (0.0, 0);
+        let sorted = merge_sort_with_progress(&input, &mut |fraction, moves| {
+            assert!(fraction >= last.0);
+            last = (fraction, moves);
+            true
+        })
+        .unwrap();
+        assert_eq!(sorted, merge_sort(&input));
+        assert_eq!(last.0, 1.0);
+
+        let mut calls = 0;
+        let cancelled = merge_sort_with_progress(&input, &mut |_, _| {
+            calls += 1;
+            calls < 3
+        });
+        assert_eq!(cancelled, Err(crate::Error::Cancelled));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn merge_sort_by_is_stable_and_can_reverse() {
+        let input: Vec<Keyed> = pseudo_random(100).into_iter().enumerate().map(|(i, v)| Keyed(v % 5, i)).collect();
+        let sorted = merge_sort_by(&input, |a, b| b.0.cmp(&a.0));
+        assert!(sorted.windows(2).all(|w| w[0].0 > w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1)));
+    }
+
+    #[test]
+    fn float_sorts_place_nan_and_signed_zero() {
+        let input = [0.0, f64::NAN, -1.0, -0.0, f64::INFINITY, -f64::NAN, 2.0];
+        let last = sort_f64(&input, NanPlacement::Last);
+        assert_eq!(&last[..5], &[-1.0, -0.0, 0.0, 2.0, f64::INFINITY]);
+        assert!(last[1].is_sign_negative() && last[2].is_sign_positive());
+        // NaNs keep their input order (the positive one first)
+        assert!(last[5].is_nan() && last[5].is_sign_positive() && last[6].is_sign_negative());
+
+        let first = sort_f32(&[1.0, f32::NAN, 0.0, -0.0], NanPlacement::First);
+        assert!(first[0].is_nan());
+        assert_eq!(first[1..].iter().map(|v| v.to_bits()).collect::<Vec<_>>(), vec![(-0.0f32).to_bits(), 0, 1.0f32.to_bits()]);
+    }
+
+    #[test]
+    fn radix_sorts_match_comparison_sorts() {
+        for len in [0, 1, 2, 255, 256, 5000] {
+            let ints: Vec<i32> = pseudo_random(len).into_iter().map(|v| v.wrapping_mul(4_000_037)).collect();
+            assert_eq!(radix_sort_i32(&ints), merge_sort(&ints), "length {}", len);
+            assert_eq!(sort_i32(&ints), merge_sort(&ints), "length {}", len);
+
+            let uints: Vec<u32> = ints.iter().map(|&v| v as u32).collect();
+            assert_eq!(radix_sort_u32(&uints), merge_sort(&uints), "length {}", len);
+            assert_eq!(sort_u32(&uints), merge_sort(&uints), "length {}", len);
+        }
+        assert_eq!(radix_sort_i32(&[i32::MAX, -1, i32::MIN, 0]), vec![i32::MIN, -1, 0, i32::MAX]);
+    }
+
+    #[test]
+    fn radix_sort_f32_matches_sort_f32() {
+        let mut floats: Vec<f32> = pseudo_random(1000).into_iter().map(|v| v as f32 / 7.0).collect();
+        floats.extend([0.0, -0.0, f32::NAN, -f32::NAN, f32::INFINITY, f32::NEG_INFINITY, f32::MIN_POSITIVE]);
+        for nans in [NanPlacement::First, NanPlacement::Last] {
+            let expected = merge_sort_by(&floats, |a, b| nan_order(a.is_nan(), b.is_nan(), nans).unwrap_or_else(|| a.total_cmp(b)));
+            let bits = |v: Vec<f32>| v.into_iter().map(f32::to_bits).collect::<Vec<_>>();
+            assert_eq!(bits(radix_sort_f32(&floats, nans)), bits(expected.clone()));
+            assert_eq!(bits(sort_f32(&floats, nans)), bits(expected));
+        }
+    }
+
+    #[test]
+    fn argsort_is_stable_in_both_directions() {
+        let values = [3, 1, 3, 2, 1];
+        assert_eq!(argsort(&values, SortOrder::Ascending), vec![1, 4, 3, 0, 2]);
+        assert_eq!(argsort(&values, SortOrder::Descending), vec![0, 2, 3, 1, 4]);
+
+        let floats = [0.5, f64::NAN, -0.0, 0.0, 2.0];
+        assert_eq!(argsort_f64(&floats, SortOrder::Descending, NanPlacement::Last), vec![4, 0, 3, 2, 1]);
+        assert_eq!(argsort_f64(&floats, SortOrder::Ascending, NanPlacement::First), vec![1, 2, 3, 0, 4]);
+
+        let input = pseudo_random(1000);
+        let sorted = apply_permutation(&input, &argsort(&input, SortOrder::Ascending)).unwrap();
+        assert_eq!(sorted, merge_sort(&input));
+    }
+
+    #[test]
+    fn apply_permutation_rejects_invalid_permutations() {
+        let values = ["a", "b", "c"];
+        assert_eq!(apply_permutation(&values, &[2, 0, 1]).unwrap(), vec!["c", "a", "b"]);
+        for permutation in [&[0, 1][..], &[0, 1, 1], &[0, 1, 3]] {
+            assert!(matches!(apply_permutation(&values, permutation), Err(Error::InvalidInput(_))));
+        }
+    }
+
+    #[test]
+    fn argsort_multi_breaks_ties_with_later_keys() {
+        let city = ["Rome", "Milan", "Rome", "Milan", "Rome"].map(String::from);
+        let year = [2020, 2021, 2019, 2021, 2020];
+        let price = [1.0, f64::NAN, 3.0, 2.0, 5.0];
+        let order = argsort_multi(&[
+            (Column::Strings(&city), SortOrder::Ascending),
+            (Column::I32(&year), SortOrder::Descending),
+            (Column::F64(&price), SortOrder::Descending),
+        ])
+        .unwrap();
+        assert_eq!(order, vec![3, 1, 4, 0, 2]);
+
+        assert_eq!(argsort_multi(&[]).unwrap(), Vec::<usize>::new());
+        let short = [1u32];
+        assert!(argsort_multi(&[(Column::I32(&year), SortOrder::Ascending), (Column::U32(&short), SortOrder::Ascending)]).is_err());
+    }
+
+    #[test]
+    fn natural_cmp_compares_digit_runs_by_value() {
+        let mut names = vec!["a10", "a2", "a1", "a01", "b", "a", "a1b", "a1a", "x99999999999999999999999", "x100000000000000000000000"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, vec!["a", "a1", "a01", "a1a", "a1b", "a2", "a10", "b", "x99999999999999999999999", "x100000000000000000000000"]);
+        assert_eq!(natural_cmp("é2", "é10"), Ordering::Less);
+    }
+
+    #[test]
+    fn argsort_records_sorts_by_several_keys() {
+        let text = |values: &[&str]| values.iter().map(|v| SortValue::Text(v.to_string())).collect::<Vec<_>>();
+        let last_names = text(&["Rossi", "Bianchi", "Rossi", "Bianchi"]);
+        let mut created = text(&["2024-01-02", "2023-05-01", "2024-03-01", "2023-05-01"]);
+        created[2] = SortValue::Null;
+        let by_created = SortKey { order: SortOrder::Descending, ..SortKey::default() };
+        let order = argsort_records(&[(&last_names, SortKey::default()), (&created, by_created)]).unwrap();
+        assert_eq!(order, vec![1, 3, 0, 2]);
+
+        let nulls_first = SortKey { nulls: NullPlacement::First, ..by_created };
+        assert_eq!(argsort_records(&[(&created, nulls_first)]).unwrap(), vec![2, 0, 1, 3]);
+
+        let amounts = vec![SortValue::Number(10.0), SortValue::Text(" 9.5 ".to_string()), SortValue::Number(f64::NAN), SortValue::Text("n/a".to_string())];
+        let numeric = SortKey { collation: Collation::Numeric, nulls: NullPlacement::First, ..SortKey::default() };
+        assert_eq!(argsort_records(&[(&amounts, numeric)]).unwrap(), vec![2, 3, 1, 0]);
+        let as_strings = SortKey::default();
+        assert_eq!(argsort_records(&[(&amounts[..2], as_strings)]).unwrap(), vec![1, 0]);
+
+        assert!(argsort_records(&[(&amounts, numeric), (&created, numeric)]).is_ok());
+        assert!(argsort_records(&[(&amounts, numeric), (&last_names[..3], numeric)]).is_err());
+    }
+
+    #[test]
+    fn nth_element_matches_sorted_position() {
+        let patterns: Vec<Vec<i32>> = vec![
+            pseudo_random(5000),
+            (0..5000).collect(),
+            (0..5000).rev().collect(),
+            vec![7; 5000],
+            (0..5000).map(|i| i % 3).collect(),
+        ];
+        for input in patterns {
+            let sorted = merge_sort(&input);
+            for n in [0, 1, 17, 2500, 4999] {
+                let mut values = input.clone();
+                nth_element(&mut values, n).unwrap();
+                assert_eq!(values[n], sorted[n]);
+                assert!(values[..n].iter().all(|v| *v <= values[n]));
+                assert!(values[n + 1..].iter().all(|v| *v >= values[n]));
+            }
+        }
+        assert!(nth_element(&mut [1, 2], 2).is_err());
+
+        let mut floats = [f64::NAN, 2.0, -0.0, 0.0, 1.0];
+        nth_element_f64(&mut floats, 4).unwrap();
+        assert!(floats[4].is_nan());
+        nth_element_f64(&mut floats, 0).unwrap();
+        assert!(floats[0] == 0.0 && floats[0].is_sign_negative());
+    }
+
+    #[test]
+    fn top_k_is_a_prefix_of_the_stable_sort() {
+        let input: Vec<i32> = pseudo_random(3000).into_iter().map(|v| v % 100).collect();
+        let ascending = argsort(&input, SortOrder::Ascending);
+        let descending = argsort(&input, SortOrder::Descending);
+        for k in [0, 1, 50, 2999, 3000, 4000] {
+            let take = k.min(input.len());
+            assert_eq!(top_k_indices(&input, k, SortOrder::Ascending), ascending[..take]);
+            assert_eq!(top_k_indices(&input, k, SortOrder::Descending), descending[..take]);
+        }
+
+        let floats = [1.5, f64::NAN, 3.0, -2.0, 3.0];
+        assert_eq!(top_k_f64(&floats, 2, SortOrder::Descending), vec![3.0, 3.0]);
+        assert_eq!(top_k_indices_f64(&floats, 10, SortOrder::Ascending), vec![3, 0, 2, 4]);
+    }
+
+    #[test]
+    fn median_handles_even_lengths_and_nan() {
+        assert_eq!(median(&[5]), Some(5.0));
+        assert_eq!(median(&[i32::MAX, i32::MAX - 2]), Some(i32::MAX as f64 - 1.0));
+        assert_eq!(median(&pseudo_random(1001)), Some(merge_sort(&pseudo_random(1001))[500] as f64));
+        assert_eq!(median_f64(&[f64::NAN, 4.0, 1.0, f64::NAN, 2.0, 10.0]), Some(3.0));
+        assert_eq!(median_f64(&[f64::NAN]), None);
+        assert_eq!(median_f64(&[f64::MAX, f64::MAX]), Some(f64::MAX));
+    }
+
+    #[test]
+    fn searches_match_linear_scans() {
+        let sorted = merge_sort(&pseudo_random(2000).into_iter().map(|v| v % 50).collect::<Vec<_>>());
+        let queries: Vec<i32> = (-60..60).collect();
+        for value in &queries {
+            let less = sorted.iter().filter(|v| *v < value).count();
+            let not_greater = sorted.iter().filter(|v| *v <= value).count();
+            assert_eq!(lower_bound(&sorted, value), less);
+            assert_eq!(upper_bound(&sorted, value), not_greater);
+            assert_eq!(equal_range(&sorted, value), less..not_greater);
+            assert_eq!(binary_search(&sorted, value), (less < not_greater).then_some(less));
+            assert_eq!(count_in_range(&sorted, value, &(value + 10)), sorted.iter().filter(|v| (*value..=value + 10).contains(v)).count());
+        }
+        assert_eq!(count_in_range(&sorted, &10, &-10), 0);
+
+        let batch = equal_range_batch(&sorted, &queries);
+        let mut shuffled = queries.clone();
+        shuffled.reverse();
+        let shuffled_batch = equal_range_batch(&sorted, &shuffled);
+        for (i, value) in queries.iter().enumerate() {
+            assert_eq!(batch[i], equal_range(&sorted, value));
+            assert_eq!(shuffled_batch[queries.len() - 1 - i], batch[i]);
+        }
+    }
+
+    #[test]
+    fn searches_place_nan_last() {
+        let sorted = sort_f64(&[2.0, f64::NAN, -0.0, 0.0, f64::NAN, 1.0], NanPlacement::Last);
+        assert_eq!(equal_range(&sorted, &0.0), 0..2);
+        assert_eq!(equal_range(&sorted, &f64::NAN), 4..6);
+        assert_eq!(binary_search(&sorted, &f64::INFINITY), None);
+        assert_eq!(lower_bound(&sorted, &f64::INFINITY), 4);
+        assert_eq!(count_in_range(&sorted, &f64::NEG_INFINITY, &f64::INFINITY), 4);
+
+        let names = merge_sort(&["pear", "apple", "fig", "apple"].map(String::from));
+        assert_eq!(equal_range(&names, &"apple".to_string()), 0..2);
+        assert_eq!(binary_search(&names, &"kiwi".to_string()), None);
+    }
+
+    #[test]
+    fn set_operations_match_btreeset() {
+        use std::collections::BTreeSet;
+
+        let a = merge_sort(&pseudo_random(500).into_iter().map(|v| v % 40).collect::<Vec<_>>());
+        let b = merge_sort(&pseudo_random(300).into_iter().map(|v| v % 60 + 10).collect::<Vec<_>>());
+        let (set_a, set_b): (BTreeSet<i32>, BTreeSet<i32>) = (a.iter().copied().collect(), b.iter().copied().collect());
+
+        assert_eq!(unique(&a), set_a.iter().copied().collect::<Vec<_>>());
+        assert_eq!(union(&a, &b), set_a.union(&set_b).copied().collect::<Vec<_>>());
+        assert_eq!(intersection(&a, &b), set_a.intersection(&set_b).copied().collect::<Vec<_>>());
+        assert_eq!(difference(&a, &b), set_a.difference(&set_b).copied().collect::<Vec<_>>());
+        assert_eq!(symmetric_difference(&a, &b), set_a.symmetric_difference(&set_b).copied().collect::<Vec<_>>());
+        assert_eq!(is_subset(&a, &b), set_a.is_subset(&set_b));
+        assert!(is_subset(&intersection(&a, &b), &b));
+        assert!(is_subset::<i32>(&[], &[]));
+        assert!(!is_subset(&[1], &[]));
+
+        let floats = [-0.0, 0.0, 1.0, f64::NAN, f64::NAN];
+        assert_eq!(unique(&floats).len(), 3);
+        assert_eq!(intersection(&floats, &[0.0, f64::NAN]).len(), 2);
+    }
+
+    #[test]
+    fn merge_sorted_matches_sorting_the_concatenation() {
+        for k in [0, 1, 2, 3, 7, 16] {
+            let inputs: Vec<Vec<i32>> = (0..k).map(|i| merge_sort(&pseudo_random(i * 37 % 100))).collect();
+            let all: Vec<i32> = inputs.concat();
+            assert_eq!(merge_sorted(&inputs), merge_sort(&all), "{} inputs", k);
+        }
+
+        // Ties come from the earlier input
+        let merged = merge_sorted(&[vec![Keyed(1, 0), Keyed(2, 0)], vec![Keyed(1, 1)], vec![Keyed(1, 2)]]);
+        assert_eq!(merged.iter().map(|k| k.1).collect::<Vec<_>>(), vec![0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn string_sorts_follow_the_collation() {
+        let words = ["b10", "B2", "a", "b2", "A"];
+        let sorted = |collation| sort_strings(&words, collation).unwrap();
+        assert_eq!(sorted(StringCollation::default()), vec!["A", "B2", "a", "b10", "b2"]);
+        let natural = StringCollation { natural: true, ..StringCollation::default() };
+        assert_eq!(sorted(natural), vec!["A", "B2", "a", "b2", "b10"]);
+        let case_insensitive = StringCollation { case_insensitive: true, ..StringCollation::default() };
+        assert_eq!(sorted(case_insensitive), vec!["a", "A", "b10", "B2", "b2"]);
+        let both = StringCollation { natural: true, case_insensitive: true, ..StringCollation::default() };
+        assert_eq!(sorted(both), vec!["a", "A", "B2", "b2", "b10"]);
+        assert_eq!(argsort_strings(&words, SortOrder::Descending, both).unwrap(), vec![0, 1, 3, 2, 4]);
+
+        let accents = StringCollation { accent_insensitive: true, ..StringCollation::default() };
+        assert!(sort_strings(&words, accents).is_err());
+    }
+
+    #[cfg(feature = "collation")]
+    #[test]
+    fn unicode_collation_uses_the_root_locale() {
+        let words = ["zebra", "Éclair", "eclair", "apple", "ecl\u{e1}ir", "Apple"];
+        let unicode = StringCollation { unicode: true, ..StringCollation::default() };
+        assert_eq!(sort_strings(&words, unicode).unwrap(), vec!["apple", "Apple", "eclair", "ecl\u{e1}ir", "Éclair", "zebra"]);
+
+        let base_letters = StringCollation { unicode: true, accent_insensitive: true, case_insensitive: true, ..StringCollation::default() };
+        assert_eq!(argsort_strings(&words, SortOrder::Ascending, base_letters).unwrap(), vec![3, 5, 1, 2, 4, 0]);
+    }
+
+    #[cfg(not(feature = "collation"))]
+    #[test]
+    fn unicode_collation_needs_the_feature() {
+        let unicode = StringCollation { unicode: true, ..StringCollation::default() };
+        assert!(matches!(sort_strings(&["a"], unicode), Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn sortedness_diagnostics_match_brute_force() {
+        let values: Vec<i32> = pseudo_random(600).into_iter().map(|v| v % 50).collect();
+        let brute_force = (0..values.len())
+            .flat_map(|i| (i + 1..values.len()).map(move |j| (i, j)))
+            .filter(|&(i, j)| values[i] > values[j])
+            .count() as u64;
+        assert_eq!(count_inversions(&values), brute_force);
+        assert_eq!(count_inversions(&merge_sort(&values)), 0);
+        let decreasing: Vec<i32> = (0..100).rev().collect();
+        assert_eq!(count_inversions(&decreasing), 99 * 100 / 2);
+        assert_eq!(count_inversions(&[2.0, f64::NAN, 1.0]), 2);
+
+        assert!(is_sorted(&[1, 1, 2], false) && !is_sorted(&[1, 1, 2], true));
+        assert!(is_sorted::<i32>(&[], true));
+        assert_eq!(first_unsorted_index(&[f64::NAN, 1.0], false), Some(1));
+        assert_eq!(first_unsorted_index(&[f64::NAN, f64::NAN], true), Some(1));
+
+        // Longest increasing subsequence against an O(n^2) dynamic program
+        for strict in [true, false] {
+            let lis = longest_increasing_subsequence_indices(&values, strict);
+            assert!(lis.windows(2).all(|w| w[0] < w[1]));
+            assert!(is_sorted(&lis.iter().map(|&i| values[i]).collect::<Vec<_>>(), strict));
+            let mut best = vec![1; values.len()];
+            for j in 0..values.len() {
+                for i in 0..j {
+                    if values[i] < values[j] || (!strict && values[i] == values[j]) {
+                        best[j] = best[j].max(best[i] + 1);
+                    }
+                }
+            }
+            assert_eq!(lis.len(), *best.iter().max().unwrap());
+        }
+        assert_eq!(longest_increasing_subsequence(&[5.0, 1.0, f64::NAN, 2.0], true)[..2], [1.0, 2.0]);
+        assert!(longest_increasing_subsequence::<i32>(&[], true).is_empty());
+    }
+
+    /// A store that the test can still inspect after handing it to a sorter.
+    #[derive(Clone, Default)]
+    struct SharedStore(std::rc::Rc<std::cell::RefCell<HashMap<u32, Vec<u8>>>>);
+
+    impl RunStore for SharedStore {
+        fn spill(&mut self, id: u32, bytes: Vec<u8>) -> Result<()> {
+            self.0.borrow_mut().spill(id, bytes)
+        }
+
+        fn load(&mut self, id: u32) -> Result<Vec<u8>> {
+            self.0.borrow_mut().load(id)
+        }
+    }
+
+    #[test]
+    fn external_sort_matches_merge_sort() {
+        let values: Vec<f64> = pseudo_random(150_000).into_iter().map(|v| v as f64 / 7.0).collect();
+        let store = SharedStore::default();
+        let mut sorter = ExternalSorter::new(70_000).unwrap().with_store(store.clone());
+        for chunk in values.chunks(9_999) {
+            sorter.push(chunk).unwrap();
+        }
+        assert_eq!(sorter.run_count(), 2);
+        // Runs of 70 000 elements are spilled as two blocks of at most `SPILL_BLOCK_LENGTH`
+        assert_eq!(store.0.borrow().len(), 4);
+
+        // Blocks are loaded lazily: the first chunk needs the first block of each of the three runs
+        let mut merged = sorter.finish().unwrap();
+        assert_eq!(store.0.borrow().len(), 5);
+        let mut sorted = merged.next_chunk(4096).unwrap().unwrap();
+        assert_eq!(store.0.borrow().len(), 2);
+        while let Some(chunk) = merged.next_chunk(4096).unwrap() {
+            assert!(chunk.len() <= 4096);
+            sorted.extend(chunk);
+        }
+        assert_eq!(sorted, merge_sort(&values));
+        assert!(store.0.borrow().is_empty());
+
+        let mut in_memory = ExternalSorter::new(2).unwrap();
+        in_memory.push(&[3.0, f64::NAN, 1.0, 2.0, -0.5]).unwrap();
+        let merged = in_memory.finish().unwrap().next_chunk(10).unwrap().unwrap();
+        assert_eq!(&merged[..4], &[-0.5, 1.0, 2.0, 3.0]);
+        assert!(merged[4].is_nan());
+
+        assert!(ExternalSorter::<i32>::new(0).is_err());
+        assert_eq!(ExternalSorter::<i32>::new(4).unwrap().finish().unwrap().next_chunk(1).unwrap(), None);
+    }
+
+    #[test]
+    fn external_sort_reports_store_errors() {
+        let store = SharedStore::default();
+        let mut sorter = ExternalSorter::new(70_000).unwrap().with_store(store.clone());
+        sorter.push(&(0..70_000u32).rev().collect::<Vec<_>>()).unwrap();
+        let mut merged = sorter.finish().unwrap();
+        assert!(merged.next_chunk(0).is_err());
+        store.0.borrow_mut().insert(1, vec![0xFF; 3]);
+        assert!(matches!(merged.next_chunk(70_000), Err(Error::Decompression(_))));
+
+        let store = SharedStore::default();
+        let mut sorter = ExternalSorter::new(2).unwrap().with_store(store.clone());
+        sorter.push(&[1i32, 2]).unwrap();
+        store.0.borrow_mut().clear();
+        assert!(matches!(sorter.finish().unwrap().next_chunk(2), Err(Error::InvalidInput(_))));
+
+        // A failed spill poisons the sorter instead of silently dropping the run
+        struct FailingStore;
+        impl RunStore for FailingStore {
+            fn spill(&mut self, _: u32, _: Vec<u8>) -> Result<()> {
+                Err(Error::InvalidInput("full".to_string()))
+            }
+
+            fn load(&mut self, _: u32) -> Result<Vec<u8>> {
+                unreachable!()
+            }
+        }
+        let mut sorter = ExternalSorter::new(2).unwrap().with_store(FailingStore);
+        assert!(sorter.push(&[5, 4]).is_err());
+        assert!(sorter.push(&[3, 2]).is_err());
+        assert!(sorter.finish().is_err());
+    }
+
+    #[test]
+    fn external_sort_retries_after_a_failed_load() {
+        let store = SharedStore::default();
+        let mut sorter = ExternalSorter::new(70_000).unwrap().with_store(store.clone());
+        sorter.push(&(0..140_000u32).rev().collect::<Vec<_>>()).unwrap();
+        let mut merged = sorter.finish().unwrap();
+        let mut sorted = merged.next_chunk(60_000).unwrap().unwrap();
+
+        // Block 3, the second block of the run holding 0..70 000, is needed within the next chunk
+        let block = store.0.borrow_mut().remove(&3).unwrap();
+        assert!(merged.next_chunk(60_000).is_err());
+        assert!(merged.next_chunk(60_000).is_err());
+        store.0.borrow_mut().insert(3, block);
+        while let Some(chunk) = merged.next_chunk(60_000).unwrap() {
+            sorted.extend(chunk);
+        }
+        assert_eq!(sorted, (0..140_000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn scans_and_rolling_windows() {
+        assert_eq!(cumulative_sum(&[1, 2, 3, 4]), vec![1, 3, 6, 10]);
+        assert_eq!(cumulative_product(&[1.0, 2.0, 3.0]), vec![1.0, 2.0, 6.0]);
+        assert_eq!(cumulative_min(&[3, 1, 2, 0]), vec![3, 1, 1, 0]);
+        assert_eq!(cumulative_max(&[3, 1, 4, 2]), vec![3, 3, 4, 4]);
+        assert!(cumulative_max(&[1.0, f64::NAN, 5.0])[2].is_nan());
+        assert_eq!(cumulative_sum::<i32>(&[]), Vec::<i32>::new());
+
+        let values = [4.0, 1.0, 3.0, 2.0, 5.0];
+        assert_eq!(rolling_sum(&values, 3).unwrap(), vec![8.0, 6.0, 10.0]);
+        assert_eq!(rolling_mean(&values, 5).unwrap(), vec![3.0]);
+        assert_eq!(rolling_min(&values, 2).unwrap(), vec![1.0, 1.0, 2.0, 2.0]);
+        assert_eq!(rolling_max(&[5, 4, 3, 6, 1], 3).unwrap(), vec![5, 6, 6]);
+        assert_eq!(rolling_sum(&values, 6).unwrap(), Vec::<f64>::new());
+        assert!(rolling_sum(&values, 0).is_err());
+
+        // Non-finite values only affect the windows that contain them
+        let gaps = [1.0, f64::NAN, 2.0, 3.0, f64::INFINITY, 4.0];
+        let sums = rolling_sum(&gaps, 2).unwrap();
+        assert!(sums[0].is_nan() && sums[1].is_nan());
+        assert_eq!(&sums[2..], &[5.0, f64::INFINITY, f64::INFINITY]);
+        let mins = rolling_min(&gaps, 2).unwrap();
+        assert!(mins[1].is_nan());
+        assert_eq!(&mins[2..], &[2.0, 3.0, 4.0]);
+
+        // Compensated: no drift after large values leave the window
+        let mut series = vec![1e16, 1.0, -1e16];
+        series.extend([0.1; 10]);
+        assert!((rolling_sum(&series, 3).unwrap().last().unwrap() - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn chunk_zip_and_group_by() {
+        let chunks = chunk(&[1, 2, 3, 4, 5], 2).unwrap();
+        assert_eq!(chunks, vec![vec![1, 2], vec![3, 4], vec![5]]);
+        assert_eq!(flatten(&chunks), vec![1, 2, 3, 4, 5]);
+        assert!(chunk(&[1], 0).is_err());
+
+        assert_eq!(zip(&[vec![1, 2], vec![3, 4, 5]]), vec![vec![1, 3], vec![2, 4]]);
+        assert_eq!(transpose(&[vec![1, 2, 3], vec![4, 5, 6]]).unwrap(), vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert!(transpose(&[vec![1, 2], vec![3]]).is_err());
+        assert_eq!(transpose::<i32, Vec<i32>>(&[]).unwrap(), Vec::<Vec<i32>>::new());
+
+        let groups = group_by(&[2.0, 1.0, f64::NAN, 2.0], &[3.0, 1.0, 7.0, -1.0]).unwrap();
+        assert_eq!(groups.len(), 3);
+        assert_eq!((groups[1].key, groups[1].count, groups[1].sum, groups[1].min, groups[1].max), (2.0, 2, 2.0, -1.0, 3.0));
+        assert_eq!(groups[1].mean(), 1.0);
+        assert!(groups[2].key.is_nan());
+        let with_nan = group_by(&[1, 1, 1], &[2.0, f64::NAN, 1.0]).unwrap();
+        assert!(with_nan[0].min.is_nan() && with_nan[0].max.is_nan() && with_nan[0].sum.is_nan());
+        assert_eq!(group_counts(&["b", "a", "b"]), vec![("a", 1), ("b", 2)]);
+        assert!(group_by(&[1], &[]).is_err());
+    }
+
+    #[test]
+    fn random_matches_the_xoshiro_reference() {
+        // Reference outputs of xoshiro256** for the state [1, 2, 3, 4]
+        let mut random = Random { state: [1, 2, 3, 4] };
+        let outputs: Vec<u64> = (0..4).map(|_| random.next_u64()).collect();
+        assert_eq!(outputs, vec![11520, 0, 1509978240, 1215971899390074240]);
+
+        assert_eq!(Random::new(7), Random::new(7));
+        assert_ne!(Random::new(7), Random::new(8));
+        assert_eq!(Random::from_text("a/b"), Random::from_text("a/b"));
+        assert_ne!(Random::new(0).state, [0; 4]);
+
+        let mut random = Random::new(1);
+        assert!((0..1000).map(|_| random.next_f64()).all(|v| (0.0..1.0).contains(&v)));
+        assert!((0..1000).map(|_| random.below(3).unwrap()).all(|v| v < 3));
+        assert!(random.below(0).is_err());
+    }
+
+    #[test]
+    fn shuffle_and_sample_are_uniform() {
+        let mut random = Random::new(2024);
+        // Position of element 0 after 6000 shuffles of 6 elements: about 1000 per position
+        let mut counts = [0; 6];
+        for _ in 0..6000 {
+            let mut values = [0, 1, 2, 3, 4, 5];
+            random.shuffle(&mut values);
+            counts[values.iter().position(|&v| v == 0).unwrap()] += 1;
+        }
+        assert!(counts.iter().all(|&c| (850..1150).contains(&c)), "{:?}", counts);
+
+        let values: Vec<i32> = (0..100).collect();
+        let sample = random.sample(&values, 100, false).unwrap();
+        assert_eq!(merge_sort(&sample), values);
+        assert_eq!(random.sample(&values, 500, true).unwrap().len(), 500);
+        assert!(random.sample(&values, 101, false).is_err());
+        assert!(random.sample::<i32>(&[], 1, true).is_err());
+        assert_eq!(random.sample::<i32>(&[], 0, true).unwrap(), Vec::<i32>::new());
+
+        let reservoir = random.reservoir_sample(0..1000, 10);
+        assert_eq!(unique(&merge_sort(&reservoir)).len(), 10);
+        assert_eq!(random.reservoir_sample(0..3, 10), vec![0, 1, 2]);
+        assert_eq!(random.reservoir_sample(0..3, usize::MAX), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn weighted_sample_follows_the_weights() {
+        let mut random = Random::new(99);
+        let items = ['a', 'b', 'c', 'd'];
+        let weights = [1.0, 0.0, 3.0, 6.0];
+        let draws = random.weighted_sample(&items, &weights, 10_000, true).unwrap();
+        let count = |c| draws.iter().filter(|&&d| d == c).count();
+        assert_eq!(count('b'), 0);
+        assert!((800..1200).contains(&count('a')) && (5700..6300).contains(&count('d')));
+
+        let mut first = [0; 4];
+        for _ in 0..4000 {
+            let drawn = random.weighted_sample(&items, &weights, 3, false).unwrap();
+            assert_eq!(merge_sort(&drawn), vec!['a', 'c', 'd']);
+            first[items.iter().position(|&c| c == drawn[0]).unwrap()] += 1;
+        }
+        assert!((2200..2600).contains(&first[3]), "{:?}", first);
+
+        assert!(random.weighted_sample(&items, &weights, 4, false).is_err());
+        assert!(random.weighted_sample(&items, &[1.0, -1.0, 1.0, 1.0], 1, true).is_err());
+        assert!(random.weighted_sample(&items, &[1.0], 1, true).is_err());
+        assert!(random.weighted_sample(&items, &[0.0; 4], 1, true).is_err());
+        assert!(random.weighted_sample::<char>(&[], &[], 0, true).unwrap().is_empty());
+
+        // The total of these weights overflows to infinity unless they are scaled first
+        let draws = random.weighted_sample(&[0, 1], &[f64::MAX, f64::MAX], 1000, true).unwrap();
+        assert!((400..600).contains(&draws.iter().filter(|&&d| d == 0).count()));
+        assert!(random.weighted_sample(&items, &[0.0; 4], 0, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn merge_sort_is_stable() {
+        let input: Vec<Keyed> = pseudo_random(100).into_iter().enumerate().map(|(i, v)| Keyed(v % 5, i)).collect();
+        let sorted = merge_sort(&input);
+        assert!(sorted.windows(2).all(|w| w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1)));
+    }
+
+    /// Compares only by key so that stability is observable through the payload.
+    #[derive(Clone, Debug)]
+    struct Keyed(i32, usize);
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
     }
-    // If there are remaining elements in the right half, they are already in place
 }
\ No newline at end of file
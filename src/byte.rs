@@ -1,5 +1,6 @@
+use std::ops::Range;
 
-use wasm_bindgen::prelude::*;
+use crate::error::{Error, Result};
 
 
 /// Counts the total number of zero bits in the provided data.
@@ -21,9 +22,11 @@ use wasm_bindgen::prelude::*;
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::byte::count_zero_bits;
+///
 /// let input_data = [0b11001100, 0b10101010, 0b11110000];
 /// let zero_bits_count = count_zero_bits(&input_data);
-/// println!("Zero bits count: {}", zero_bits_count); // Outputs the total zero bits in the byte slice
+/// assert_eq!(zero_bits_count, 12);
 /// ```
 ///
 /// # Performance Considerations
@@ -38,7 +41,6 @@ use wasm_bindgen::prelude::*;
 ///   
 /// - **Memory Usage**: The memory usage is minimal and proportional to the size of the input slice. No
 ///   additional significant memory is allocated beyond the input data.
-#[wasm_bindgen]
 pub fn count_zero_bits(data: &[u8]) -> u32 {
     data.iter().map(|&byte| byte.count_zeros()).sum()
 }
@@ -66,10 +68,12 @@ pub fn count_zero_bits(data: &[u8]) -> u32 {
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::byte::xor_bytes;
+///
 /// let data1 = [0b11010101, 0b10101010];
 /// let data2 = [0b01100011, 0b11110000];
 /// let result = xor_bytes(&data1, &data2);
-/// println!("{:?}", result); // Outputs: [0b10110110, 0b11011010]
+/// assert_eq!(result, vec![0b10110110, 0b01011010]);
 /// ```
 ///
 /// # Performance Considerations
@@ -82,9 +86,886 @@ pub fn count_zero_bits(data: &[u8]) -> u32 {
 /// - **Iteration and XOR**: The `iter()` and `zip()` methods are used to pair corresponding bytes from the two
 ///   slices, and `map()` applies the XOR operation on each pair. Both operations are O(n), where `n` is the length
 ///   of the shorter slice.
-#[wasm_bindgen]
 pub fn xor_bytes(data1: &[u8], data2: &[u8]) -> Vec<u8> {
     data1.iter().zip(data2.iter()).map(|(&x1, &x2)| x1 ^ x2).collect()
 }
 
+/// Performs a bitwise XOR of `key` into `data` in place.
+///
+/// This is the in-place counterpart of [`xor_bytes`]: `data[i] ^= key[i]` for every index present in
+/// both slices. Bytes of `data` beyond the length of `key` are left unchanged, mirroring the way
+/// `xor_bytes` stops at the shorter input.
+///
+/// # Arguments
+///
+/// * `data` - The bytes to modify.
+/// * `key` - The bytes to XOR into `data`.
+///
+/// # Returns
+///
+/// * `usize` - The number of bytes that were modified (the length of the shorter slice).
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::xor_bytes_in_place;
+///
+/// let mut data = [0b11010101, 0b10101010, 0xff];
+/// let processed = xor_bytes_in_place(&mut data, &[0b01100011, 0b11110000]);
+/// assert_eq!(processed, 2);
+/// assert_eq!(data, [0b10110110, 0b01011010, 0xff]);
+/// ```
+pub fn xor_bytes_in_place(data: &mut [u8], key: &[u8]) -> usize {
+    for (x, &k) in data.iter_mut().zip(key) {
+        *x ^= k;
+    }
+    data.len().min(key.len())
+}
+
+/// Counts the total number of one bits (the population count) in the provided data.
+///
+/// The complement of [`count_zero_bits`]: the two always add up to `8 * data.len()`.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::count_one_bits;
+///
+/// assert_eq!(count_one_bits(&[0b11001100, 0b10101010, 0b11110000]), 12);
+/// ```
+pub fn count_one_bits(data: &[u8]) -> u32 {
+    data.iter().map(|&byte| byte.count_ones()).sum()
+}
+
+/// Counts the one bits of `data` in the bit range `bits`.
+///
+/// Bits are numbered as in one big-endian number, like [`shift_left`]: bit 0 is the most
+/// significant bit of `data[0]` and bit 8 the most significant bit of `data[1]`. Whole bytes
+/// inside the range are counted a byte at a time.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if the range is reversed or ends after `8 * data.len()`.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::count_one_bits_in_range;
+///
+/// // Bits 4..12 are the low nibble of 0xF0 and the high nibble of 0x0F
+/// assert_eq!(count_one_bits_in_range(&[0xF0, 0x0F], 4..12).unwrap(), 0);
+/// assert_eq!(count_one_bits_in_range(&[0xF0, 0x0F], 0..16).unwrap(), 8);
+/// ```
+pub fn count_one_bits_in_range(data: &[u8], bits: Range<usize>) -> Result<usize> {
+    let Range { start, end } = bits;
+    if start > end || end > data.len() * 8 {
+        return Err(Error::InvalidInput(format!("bit range {}..{} is outside 0..{}", start, end, data.len() * 8)));
+    }
+    if start == end {
+        return Ok(0);
+    }
+    // `0xFF >> n` keeps the bits from position `n` of a byte on
+    let (first, last) = (start / 8, end / 8);
+    let head = 0xFFu8 >> (start % 8);
+    let tail = !(0xFFu8 >> (end % 8));
+    if first == last {
+        return Ok((data[first] & head & tail).count_ones() as usize);
+    }
+    let middle: u32 = data[first + 1..last].iter().map(|byte| byte.count_ones()).sum();
+    let tail_ones = if end % 8 == 0 { 0 } else { (data[last] & tail).count_ones() };
+    Ok(((data[first] & head).count_ones() + middle + tail_ones) as usize)
+}
+
+/// Counts the zero bits of `data` in the bit range `bits`. See [`count_one_bits_in_range`].
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if the range is reversed or ends after `8 * data.len()`.
+pub fn count_zero_bits_in_range(data: &[u8], bits: Range<usize>) -> Result<usize> {
+    let len = bits.len();
+    Ok(len - count_one_bits_in_range(data, bits)?)
+}
+
+/// A bitwise operation of [`bitwise`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOp {
+    And,
+    Or,
+    Xor,
+    /// `a & !b`: the bits of `a` that are not set in `b`.
+    AndNot,
+}
+
+impl BitOp {
+    fn apply(self, a: u8, b: u8) -> u8 {
+        match self {
+            BitOp::And => a & b,
+            BitOp::Or => a | b,
+            BitOp::Xor => a ^ b,
+            BitOp::AndNot => a & !b,
+        }
+    }
+}
+
+/// What [`bitwise`] does when its two inputs have different lengths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LengthPolicy {
+    /// Fail with `Error::InvalidInput`.
+    #[default]
+    Error,
+    /// Stop at the end of the shorter input, like [`xor_bytes`].
+    Truncate,
+    /// Repeat the second input (a key) over the whole first input, e.g. a repeating XOR key.
+    CycleKey,
+}
+
+/// Applies `op` to every byte of `a` and the byte at the same position of `b`.
+///
+/// Unlike [`xor_bytes`], inputs of different lengths are handled according to `policy`; the
+/// result is as long as `a`, or as the shorter input with `LengthPolicy::Truncate`.
+///
+/// # Arguments
+///
+/// * `a` - The first operand.
+/// * `b` - The second operand, or the key repeated over `a` with `LengthPolicy::CycleKey`.
+/// * `op` - The operation: AND, OR, XOR or AND-NOT.
+/// * `policy` - What to do if `a` and `b` have different lengths.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if the lengths differ with `LengthPolicy::Error`, or `b` is
+/// empty while `a` is not with `LengthPolicy::CycleKey`.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::{bitwise, BitOp, LengthPolicy};
+///
+/// assert_eq!(bitwise(&[0b1100], &[0b1010], BitOp::AndNot, LengthPolicy::Error).unwrap(), vec![0b0100]);
+/// assert_eq!(bitwise(b"abc", &[0x20], BitOp::Xor, LengthPolicy::CycleKey).unwrap(), b"ABC");
+/// assert!(bitwise(b"abc", b"ab", BitOp::Or, LengthPolicy::Error).is_err());
+/// ```
+pub fn bitwise(a: &[u8], b: &[u8], op: BitOp, policy: LengthPolicy) -> Result<Vec<u8>> {
+    let len = if policy == LengthPolicy::Truncate { a.len().min(b.len()) } else { a.len() };
+    let mut out = a[..len].to_vec();
+    bitwise_in_place(&mut out, b, op, policy)?;
+    Ok(out)
+}
+
+/// Applies `op` to `data` in place with the bytes of `key`. See [`bitwise`].
+///
+/// With `LengthPolicy::Truncate`, bytes of `data` past the end of `key` are left unchanged,
+/// as in [`xor_bytes_in_place`].
+///
+/// # Returns
+///
+/// * `usize` - The number of bytes that were modified.
+///
+/// # Errors
+///
+/// Same as [`bitwise`]; `data` is unchanged on error.
+pub fn bitwise_in_place(data: &mut [u8], key: &[u8], op: BitOp, policy: LengthPolicy) -> Result<usize> {
+    match policy {
+        LengthPolicy::Error if data.len() != key.len() => {
+            Err(Error::InvalidInput(format!("operands have different lengths ({} and {})", data.len(), key.len())))
+        }
+        LengthPolicy::CycleKey if key.is_empty() && !data.is_empty() => {
+            Err(Error::InvalidInput("the key to cycle is empty".to_string()))
+        }
+        LengthPolicy::CycleKey => {
+            for (x, &k) in data.iter_mut().zip(key.iter().cycle()) {
+                *x = op.apply(*x, k);
+            }
+            Ok(data.len())
+        }
+        LengthPolicy::Error | LengthPolicy::Truncate => {
+            for (x, &k) in data.iter_mut().zip(key) {
+                *x = op.apply(*x, k);
+            }
+            Ok(data.len().min(key.len()))
+        }
+    }
+}
+
+/// Inverts every bit of `data` (bitwise NOT).
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::not_bytes;
+///
+/// assert_eq!(not_bytes(&[0x0F, 0xFF]), vec![0xF0, 0x00]);
+/// ```
+pub fn not_bytes(data: &[u8]) -> Vec<u8> {
+    data.iter().map(|&byte| !byte).collect()
+}
+
+/// Shifts `data`, read as one big-endian number, left by `bits` bits.
+///
+/// Bits cross byte boundaries: the most significant bits of `data[i + 1]` move into `data[i]`.
+/// The result has the same length; bits shifted out of `data[0]` are dropped and zeros are
+/// shifted in at the end.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::shift_left;
+///
+/// assert_eq!(shift_left(&[0x01, 0x80], 1), vec![0x03, 0x00]);
+/// assert_eq!(shift_left(&[0x12, 0x34], 12), vec![0x40, 0x00]);
+/// ```
+pub fn shift_left(data: &[u8], bits: usize) -> Vec<u8> {
+    let (bytes, offset) = (bits / 8, (bits % 8) as u32);
+    (0..data.len())
+        .map(|i| {
+            let high = data.get(i + bytes).copied().unwrap_or(0);
+            let low = data.get(i + bytes + 1).copied().unwrap_or(0);
+            if offset == 0 { high } else { high << offset | low >> (8 - offset) }
+        })
+        .collect()
+}
 
+/// Shifts `data`, read as one big-endian number, right by `bits` bits, shifting in zeros at
+/// the start. See [`shift_left`].
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::shift_right;
+///
+/// assert_eq!(shift_right(&[0x01, 0x80], 1), vec![0x00, 0xC0]);
+/// ```
+pub fn shift_right(data: &[u8], bits: usize) -> Vec<u8> {
+    let (bytes, offset) = (bits / 8, (bits % 8) as u32);
+    let at = |i: Option<usize>| i.and_then(|i| data.get(i)).copied().unwrap_or(0);
+    (0..data.len())
+        .map(|i| {
+            let low = at(i.checked_sub(bytes));
+            let high = at(i.checked_sub(bytes + 1));
+            if offset == 0 { low } else { low >> offset | high << (8 - offset) }
+        })
+        .collect()
+}
+
+/// A fixed-size set of bits, numbered from 0, packed into 64-bit words.
+///
+/// Supports the succinct-data-structure queries `rank` (how many bits are set before an
+/// index) and `select` (where the k-th set bit is). Both scan the words, a popcount per 64 bits.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::Bitset;
+///
+/// let mut bits = Bitset::new(100);
+/// for i in [3, 64, 70] {
+///     bits.set(i).unwrap();
+/// }
+/// assert_eq!(bits.rank(65).unwrap(), 2);
+/// assert_eq!(bits.select(2), Some(70));
+/// assert_eq!(bits.ones().collect::<Vec<_>>(), vec![3, 64, 70]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitset {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bitset {
+    /// Creates a set of `len` bits, all clear.
+    pub fn new(len: usize) -> Bitset {
+        Bitset { words: vec![0; len.div_ceil(64)], len }
+    }
+
+    /// Number of bits in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set has no bits at all.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn check(&self, index: usize) -> Result<()> {
+        if index >= self.len {
+            return Err(Error::InvalidInput(format!("bit {} is out of range for a bitset of {} bits", index, self.len)));
+        }
+        Ok(())
+    }
+
+    /// Sets bit `index`.
+    ///
+    /// Returns `Error::InvalidInput` if `index >= len`, here and in `clear` and `test`.
+    pub fn set(&mut self, index: usize) -> Result<()> {
+        self.check(index)?;
+        self.words[index / 64] |= 1 << (index % 64);
+        Ok(())
+    }
+
+    /// Clears bit `index`.
+    pub fn clear(&mut self, index: usize) -> Result<()> {
+        self.check(index)?;
+        self.words[index / 64] &= !(1 << (index % 64));
+        Ok(())
+    }
+
+    /// Returns `true` if bit `index` is set.
+    pub fn test(&self, index: usize) -> Result<bool> {
+        self.check(index)?;
+        Ok(self.words[index / 64] >> (index % 64) & 1 == 1)
+    }
+
+    /// Number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Number of set bits before `index`, i.e. in `0..index`.
+    ///
+    /// Returns `Error::InvalidInput` if `index > len`.
+    pub fn rank(&self, index: usize) -> Result<usize> {
+        if index > self.len {
+            return Err(Error::InvalidInput(format!("rank {} is out of range for a bitset of {} bits", index, self.len)));
+        }
+        let whole: usize = self.words[..index / 64].iter().map(|word| word.count_ones() as usize).sum();
+        let partial = match index % 64 {
+            0 => 0,
+            bits => (self.words[index / 64] & ((1 << bits) - 1)).count_ones() as usize,
+        };
+        Ok(whole + partial)
+    }
+
+    /// Index of the set bit with rank `k` (the first set bit for `k = 0`), or `None` if fewer
+    /// than `k + 1` bits are set. The inverse of [`Bitset::rank`].
+    pub fn select(&self, k: usize) -> Option<usize> {
+        let mut remaining = k;
+        for (w, &word) in self.words.iter().enumerate() {
+            let ones = word.count_ones() as usize;
+            if remaining < ones {
+                let mut word = word;
+                for _ in 0..remaining {
+                    word &= word - 1;
+                }
+                return Some(w * 64 + word.trailing_zeros() as usize);
+            }
+            remaining -= ones;
+        }
+        None
+    }
+
+    /// Iterates over the indices of the set bits in increasing order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    w * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+/// Letter case of the digits `a`-`f` produced by [`hex_encode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HexCase {
+    #[default]
+    Lower,
+    Upper,
+}
+
+/// Encodes `data` as hexadecimal text, two digits per byte.
+///
+/// # Arguments
+///
+/// * `data` - The bytes to encode.
+/// * `case` - Whether the digits `a`-`f` are lowercase or uppercase.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::{hex_encode, HexCase};
+///
+/// assert_eq!(hex_encode(&[0xde, 0xad, 0x01], HexCase::Lower), "dead01");
+/// assert_eq!(hex_encode(&[0xde, 0xad, 0x01], HexCase::Upper), "DEAD01");
+/// ```
+pub fn hex_encode(data: &[u8], case: HexCase) -> String {
+    let digits: &[u8; 16] = match case {
+        HexCase::Lower => b"0123456789abcdef",
+        HexCase::Upper => b"0123456789ABCDEF",
+    };
+    let mut out = String::with_capacity(data.len() * 2);
+    for &byte in data {
+        out.push(digits[usize::from(byte >> 4)] as char);
+        out.push(digits[usize::from(byte & 0x0F)] as char);
+    }
+    out
+}
+
+/// Decodes hexadecimal text into bytes. Both letter cases are accepted, nothing else: no
+/// `0x` prefix, whitespace or separators.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if `text` has an odd length or contains a character that is
+/// not a hexadecimal digit (the message gives its position).
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::hex_decode;
+///
+/// assert_eq!(hex_decode("DEad01").unwrap(), vec![0xde, 0xad, 0x01]);
+/// assert!(hex_decode("abc").is_err());
+/// ```
+pub fn hex_decode(text: &str) -> Result<Vec<u8>> {
+    let bytes = text.as_bytes();
+    if !bytes.len().is_multiple_of(2) {
+        return Err(Error::InvalidInput(format!("hex text has an odd length ({})", bytes.len())));
+    }
+    let digit = |i: usize| -> Result<u8> {
+        match bytes[i] {
+            c @ b'0'..=b'9' => Ok(c - b'0'),
+            c @ b'a'..=b'f' => Ok(c - b'a' + 10),
+            c @ b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(invalid_character("hex", text, i)),
+        }
+    };
+    (0..bytes.len()).step_by(2).map(|i| Ok(digit(i)? << 4 | digit(i + 1)?)).collect()
+}
+
+/// Alphabet of [`base64_encode`] and [`base64_decode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Base64Alphabet {
+    /// RFC 4648 section 4: `A`-`Z`, `a`-`z`, `0`-`9`, `+` and `/`.
+    #[default]
+    Standard,
+    /// RFC 4648 section 5, safe in URLs and file names: `-` and `_` instead of `+` and `/`.
+    UrlSafe,
+}
+
+const BASE64_STANDARD: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL_SAFE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const BASE32_RFC4648: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const BASE32_CROCKFORD: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const BASE58_BITCOIN: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+fn base64_alphabet(alphabet: Base64Alphabet) -> &'static [u8] {
+    match alphabet {
+        Base64Alphabet::Standard => BASE64_STANDARD,
+        Base64Alphabet::UrlSafe => BASE64_URL_SAFE,
+    }
+}
+
+/// Encodes `data` as Base64 (RFC 4648).
+///
+/// # Arguments
+///
+/// * `data` - The bytes to encode.
+/// * `alphabet` - The standard alphabet, or the URL-safe one (Base64URL).
+/// * `padding` - Whether to pad the output with `=` to a multiple of 4 characters.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::{base64_encode, Base64Alphabet};
+///
+/// assert_eq!(base64_encode(b"foob", Base64Alphabet::Standard, true), "Zm9vYg==");
+/// assert_eq!(base64_encode(&[0xfb, 0xff], Base64Alphabet::UrlSafe, false), "-_8");
+/// ```
+pub fn base64_encode(data: &[u8], alphabet: Base64Alphabet, padding: bool) -> String {
+    encode_bits(data, base64_alphabet(alphabet), 6, padding.then_some(4))
+}
+
+/// Decodes Base64 text (RFC 4648).
+///
+/// Decoding is strict, so that every byte string has exactly one accepted encoding: only the
+/// characters of `alphabet` are accepted (no whitespace, and no mixing of the two alphabets),
+/// padding must be present if and only if `padding` is `true`, and the unused bits of the last
+/// character must be zero.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` describing the first problem, with its position for an
+/// invalid character.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::{base64_decode, Base64Alphabet};
+///
+/// assert_eq!(base64_decode("Zm9vYg==", Base64Alphabet::Standard, true).unwrap(), b"foob");
+/// assert!(base64_decode("Zm9vYg", Base64Alphabet::Standard, true).is_err());
+/// assert!(base64_decode("Zm9vYh==", Base64Alphabet::Standard, true).is_err());
+/// ```
+pub fn base64_decode(text: &str, alphabet: Base64Alphabet, padding: bool) -> Result<Vec<u8>> {
+    let table = decode_table(base64_alphabet(alphabet));
+    decode_bits(text, "Base64", 6, padding.then_some(4), |c| table[usize::from(c)])
+}
+
+/// Encodes `data` as Base32 with the RFC 4648 alphabet (`A`-`Z`, `2`-`7`), optionally padded
+/// with `=` to a multiple of 8 characters.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::base32_encode;
+///
+/// assert_eq!(base32_encode(b"foob", true), "MZXW6YQ=");
+/// assert_eq!(base32_encode(b"foob", false), "MZXW6YQ");
+/// ```
+pub fn base32_encode(data: &[u8], padding: bool) -> String {
+    encode_bits(data, BASE32_RFC4648, 5, padding.then_some(8))
+}
+
+/// Decodes RFC 4648 Base32 text. Strict in the same way as [`base64_decode`]; lowercase
+/// letters are not accepted.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` describing the first problem.
+pub fn base32_decode(text: &str, padding: bool) -> Result<Vec<u8>> {
+    let table = decode_table(BASE32_RFC4648);
+    decode_bits(text, "Base32", 5, padding.then_some(8), |c| table[usize::from(c)])
+}
+
+/// Encodes `data` as Crockford's Base32: digits and uppercase letters without `I`, `L`, `O`
+/// and `U`, never padded.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::crockford_base32_encode;
+///
+/// assert_eq!(crockford_base32_encode(b"foob"), "CSQPYRG");
+/// ```
+pub fn crockford_base32_encode(data: &[u8]) -> String {
+    encode_bits(data, BASE32_CROCKFORD, 5, None)
+}
+
+/// Decodes Crockford's Base32.
+///
+/// As the encoding specifies, letters are case-insensitive, `I` and `L` read as `1`, `O` reads
+/// as `0` and hyphens are ignored, so that codes typed by people decode. Anything else, and
+/// non-zero unused bits in the last character, is an error.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` describing the first problem.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::crockford_base32_decode;
+///
+/// assert_eq!(crockford_base32_decode("csqp-yrg").unwrap(), b"foob");
+/// assert!(crockford_base32_decode("CSQPYRU").is_err());
+/// ```
+pub fn crockford_base32_decode(text: &str) -> Result<Vec<u8>> {
+    let mut table = decode_table(BASE32_CROCKFORD);
+    for (i, &c) in BASE32_CROCKFORD.iter().enumerate() {
+        table[usize::from(c.to_ascii_lowercase())] = i as u8;
+    }
+    for (alias, value) in [(b'I', 1), (b'L', 1), (b'O', 0)] {
+        table[usize::from(alias)] = value;
+        table[usize::from(alias.to_ascii_lowercase())] = value;
+    }
+    table[usize::from(b'-')] = SKIP;
+    decode_bits(text, "Crockford Base32", 5, None, |c| table[usize::from(c)])
+}
+
+/// Encodes `data` as Base58 with the Bitcoin alphabet (no `0`, `O`, `I` or `l`). Every leading
+/// zero byte becomes a leading `1`.
+///
+/// Base58 is not a bit-group encoding, so encoding and decoding are O(n²); it is meant for
+/// short values such as keys and hashes.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::base58_encode;
+///
+/// assert_eq!(base58_encode(b"hello world"), "StV1DL6CwTryKyV");
+/// assert_eq!(base58_encode(&[0, 0, 1]), "112");
+/// ```
+pub fn base58_encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&byte| byte == 0).count();
+    // Little-endian base-58 digits of the number `data[zeros..]`
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for &byte in &data[zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut out = String::with_capacity(zeros + digits.len());
+    out.extend(std::iter::repeat_n('1', zeros));
+    out.extend(digits.iter().rev().map(|&digit| BASE58_BITCOIN[usize::from(digit)] as char));
+    out
+}
+
+/// Decodes Base58 text with the Bitcoin alphabet. Every leading `1` becomes a leading zero
+/// byte.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if `text` contains a character outside the alphabet (the
+/// message gives its position).
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::byte::base58_decode;
+///
+/// assert_eq!(base58_decode("StV1DL6CwTryKyV").unwrap(), b"hello world");
+/// assert!(base58_decode("0OIl").is_err());
+/// ```
+pub fn base58_decode(text: &str) -> Result<Vec<u8>> {
+    let table = decode_table(BASE58_BITCOIN);
+    let bytes = text.as_bytes();
+    let zeros = bytes.iter().take_while(|&&c| c == b'1').count();
+    // Little-endian bytes of the number
+    let mut number: Vec<u8> = Vec::with_capacity(bytes.len() * 733 / 1000 + 1);
+    for (i, &c) in bytes.iter().enumerate().skip(zeros) {
+        let value = table[usize::from(c)];
+        if value == INVALID {
+            return Err(invalid_character("Base58", text, i));
+        }
+        let mut carry = u32::from(value);
+        for byte in number.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            number.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut out = vec![0; zeros];
+    out.extend(number.iter().rev());
+    Ok(out)
+}
+
+/// Marks a byte that is not part of an alphabet in a decode table.
+const INVALID: u8 = 0xFF;
+/// Marks a separator that decoding ignores.
+const SKIP: u8 = 0xFE;
+
+/// Maps every byte to its value in `alphabet`, or `INVALID`.
+fn decode_table(alphabet: &[u8]) -> [u8; 256] {
+    let mut table = [INVALID; 256];
+    for (i, &c) in alphabet.iter().enumerate() {
+        table[usize::from(c)] = i as u8;
+    }
+    table
+}
+
+fn invalid_character(encoding: &str, text: &str, position: usize) -> Error {
+    // `position` is a byte offset; report the whole (possibly multi-byte) character there
+    let character = text[position..].chars().next().unwrap_or_default();
+    Error::InvalidInput(format!("invalid {} character {:?} at position {}", encoding, character, position))
+}
+
+/// Encodes `data` with an alphabet of `2^bits` characters, most significant bits first, padding
+/// the output with `=` to a multiple of `block` characters if given (Base64 and Base32).
+fn encode_bits(data: &[u8], alphabet: &[u8], bits: u32, block: Option<usize>) -> String {
+    let mask = (1u32 << bits) - 1;
+    let mut out = String::with_capacity((data.len() * 8).div_ceil(bits as usize) + 8);
+    let (mut buffer, mut buffered) = (0u32, 0);
+    for &byte in data {
+        buffer = buffer << 8 | u32::from(byte);
+        buffered += 8;
+        while buffered >= bits {
+            buffered -= bits;
+            out.push(alphabet[((buffer >> buffered) & mask) as usize] as char);
+        }
+    }
+    if buffered > 0 {
+        out.push(alphabet[((buffer << (bits - buffered)) & mask) as usize] as char);
+    }
+    if let Some(block) = block {
+        while !out.len().is_multiple_of(block) {
+            out.push('=');
+        }
+    }
+    out
+}
+
+/// Strict inverse of [`encode_bits`]: `value` maps a byte to its value, `INVALID` or `SKIP`.
+fn decode_bits(text: &str, encoding: &str, bits: u32, block: Option<usize>, value: impl Fn(u8) -> u8) -> Result<Vec<u8>> {
+    let mut bytes = text.as_bytes();
+    if let Some(block) = block {
+        if !bytes.len().is_multiple_of(block) {
+            return Err(Error::InvalidInput(format!(
+                "padded {} length must be a multiple of {}, got {}",
+                encoding,
+                block,
+                bytes.len()
+            )));
+        }
+        let unpadded = bytes.iter().rposition(|&c| c != b'=').map_or(0, |last| last + 1);
+        if bytes.len() - unpadded >= block {
+            return Err(Error::InvalidInput(format!("too much {} padding", encoding)));
+        }
+        bytes = &bytes[..unpadded];
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() * bits as usize / 8);
+    let (mut buffer, mut buffered, mut characters) = (0u32, 0, 0usize);
+    for (i, &c) in bytes.iter().enumerate() {
+        let v = value(c);
+        if v == SKIP {
+            continue;
+        }
+        if v == INVALID {
+            return Err(invalid_character(encoding, text, i));
+        }
+        characters += 1;
+        buffer = buffer << bits | u32::from(v);
+        buffered += bits;
+        if buffered >= 8 {
+            buffered -= 8;
+            out.push((buffer >> buffered) as u8);
+        }
+    }
+    // A final group of characters that cannot come from whole bytes, e.g. one Base64 character
+    if (characters * bits as usize) % 8 >= bits as usize {
+        return Err(Error::InvalidInput(format!("{} text is truncated ({} characters)", encoding, characters)));
+    }
+    if buffer & ((1 << buffered) - 1) != 0 {
+        return Err(Error::InvalidInput(format!("{} text has non-zero unused bits at the end", encoding)));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn bitwise_follows_the_length_policy() {
+        let (a, b) = ([0b1100u8, 0xFF, 0x0F], [0b1010u8, 0x0F]);
+        assert_eq!(bitwise(&a, &b, BitOp::And, LengthPolicy::Truncate).unwrap(), vec![0b1000, 0x0F]);
+        assert_eq!(bitwise(&a, &b, BitOp::Or, LengthPolicy::CycleKey).unwrap(), vec![0b1110, 0xFF, 0x0F | 0b1010]);
+        assert_eq!(bitwise(&a, &b, BitOp::Xor, LengthPolicy::Truncate).unwrap(), xor_bytes(&a, &b));
+        assert_eq!(
+            bitwise(&a, &b, BitOp::And, LengthPolicy::Error).unwrap_err(),
+            Error::InvalidInput("operands have different lengths (3 and 2)".to_string())
+        );
+        assert!(bitwise(&a, &[], BitOp::Xor, LengthPolicy::CycleKey).is_err());
+        assert_eq!(bitwise(&[], &[], BitOp::Xor, LengthPolicy::CycleKey).unwrap(), Vec::<u8>::new());
+
+        let mut data = a;
+        assert_eq!(bitwise_in_place(&mut data, &b, BitOp::AndNot, LengthPolicy::Truncate).unwrap(), 2);
+        assert_eq!(data, [0b0100, 0xF0, 0x0F]);
+        assert_eq!(not_bytes(&not_bytes(&a)), a);
+    }
+
+    #[test]
+    fn shifts_and_ranges_use_big_endian_bit_order() {
+        let data = [0b1011_0001u8, 0b0110_1100, 0b1111_0000];
+        let number = u32::from_be_bytes([0, data[0], data[1], data[2]]);
+        for bits in 0..30 {
+            let expected = (number << bits) & 0xFF_FFFF;
+            assert_eq!(shift_left(&data, bits), expected.to_be_bytes()[1..], "<< {}", bits);
+            assert_eq!(shift_right(&data, bits), (number >> bits.min(31)).to_be_bytes()[1..], ">> {}", bits);
+        }
+
+        for start in 0..=24 {
+            for end in start..=24 {
+                let ones = (start..end).filter(|&i| number >> (23 - i) & 1 == 1).count();
+                assert_eq!(count_one_bits_in_range(&data, start..end).unwrap(), ones);
+                assert_eq!(count_zero_bits_in_range(&data, start..end).unwrap(), end - start - ones);
+            }
+        }
+        assert_eq!(count_one_bits(&data) + count_zero_bits(&data), 24);
+        assert!(count_one_bits_in_range(&data, 0..25).is_err());
+        assert!(count_one_bits_in_range(&data, Range { start: 3, end: 2 }).is_err());
+    }
+
+    #[test]
+    fn bitset_rank_and_select_are_inverses() {
+        let mut bits = Bitset::new(300);
+        let set: Vec<usize> = (0..300).filter(|i| i % 7 == 0 || i % 61 == 3).collect();
+        for &i in &set {
+            bits.set(i).unwrap();
+        }
+        bits.set(5).unwrap();
+        bits.clear(5).unwrap();
+        assert!(!bits.test(5).unwrap() && bits.test(64).unwrap());
+        assert_eq!(bits.count_ones(), set.len());
+        assert_eq!(bits.ones().collect::<Vec<_>>(), set);
+        for (k, &i) in set.iter().enumerate() {
+            assert_eq!(bits.select(k), Some(i));
+            assert_eq!(bits.rank(i).unwrap(), k);
+        }
+        assert_eq!(bits.select(set.len()), None);
+        assert_eq!(bits.rank(300).unwrap(), set.len());
+        assert!(bits.rank(301).is_err() && bits.set(300).is_err() && bits.test(300).is_err());
+        assert!(Bitset::new(0).is_empty());
+    }
+
+    /// The test vectors of RFC 4648 section 10.
+    const RFC4648: [(&str, &str, &str, &str); 7] = [
+        ("", "", "", ""),
+        ("f", "Zg==", "MY======", "66"),
+        ("fo", "Zm8=", "MZXQ====", "666F"),
+        ("foo", "Zm9v", "MZXW6===", "666F6F"),
+        ("foob", "Zm9vYg==", "MZXW6YQ=", "666F6F62"),
+        ("fooba", "Zm9vYmE=", "MZXW6YTB", "666F6F6261"),
+        ("foobar", "Zm9vYmFy", "MZXW6YTBOI======", "666F6F626172"),
+    ];
+
+    #[test]
+    fn codecs_match_the_rfc_4648_vectors() {
+        for (data, base64, base32, hex) in RFC4648 {
+            let data = data.as_bytes();
+            assert_eq!(base64_encode(data, Base64Alphabet::Standard, true), base64);
+            assert_eq!(base64_decode(base64, Base64Alphabet::Standard, true).unwrap(), data);
+            let unpadded = base64.trim_end_matches('=');
+            assert_eq!(base64_encode(data, Base64Alphabet::UrlSafe, false), unpadded);
+            assert_eq!(base64_decode(unpadded, Base64Alphabet::UrlSafe, false).unwrap(), data);
+            assert_eq!(base32_encode(data, true), base32);
+            assert_eq!(base32_decode(base32, true).unwrap(), data);
+            assert_eq!(base32_decode(base32.trim_end_matches('='), false).unwrap(), data);
+            assert_eq!(hex_encode(data, HexCase::Upper), hex);
+            assert_eq!(hex_decode(&hex.to_lowercase()).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn decoders_are_strict() {
+        let standard = |text| base64_decode(text, Base64Alphabet::Standard, true);
+        assert!(standard("Zm9v\n").is_err());
+        assert!(standard("Zg=").is_err());
+        assert!(standard("Zg==Zg==").is_err());
+        assert!(standard("Zh==").is_err());
+        assert!(standard("Z===").is_err());
+        assert!(standard("====").is_err());
+        assert!(standard("-_8=").is_err());
+        assert!(base64_decode("Zg==", Base64Alphabet::Standard, false).is_err());
+        assert_eq!(base64_decode("+/8=", Base64Alphabet::Standard, true).unwrap(), vec![0xfb, 0xff]);
+        assert!(base32_decode("MZXW6YQ", true).is_err());
+        assert!(base32_decode("mzxw6yq=", true).is_err());
+        assert!(base32_decode("MZX", false).is_err());
+        assert_eq!(
+            hex_decode("0g").unwrap_err(),
+            Error::InvalidInput("invalid hex character 'g' at position 1".to_string())
+        );
+        assert_eq!(
+            base58_decode("1é").unwrap_err(),
+            Error::InvalidInput("invalid Base58 character 'é' at position 1".to_string())
+        );
+    }
+
+    #[test]
+    fn crockford_and_base58_round_trip() {
+        let data: Vec<u8> = (0..=255).rev().collect();
+        assert_eq!(crockford_base32_decode(&crockford_base32_encode(&data)).unwrap(), data);
+        assert_eq!(crockford_base32_decode("1o-Il-0000").unwrap(), crockford_base32_decode("10110000").unwrap());
+        assert!(crockford_base32_decode("U").is_err());
+
+        for data in [&data[..], &[0, 0, 0, 1], &[0], &[], b"\xff\x00"] {
+            assert_eq!(base58_decode(&base58_encode(data)).unwrap(), data);
+        }
+        // Bitcoin address of the well-known test vector (version byte, hash and checksum)
+        let address = hex_decode("00f54a5851e9372b87810a8e60cdd2e7cfd80b6e31c7f18fe8").unwrap();
+        assert_eq!(base58_encode(&address), "1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs");
+    }
+}
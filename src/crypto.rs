@@ -1,10 +1,19 @@
-use wasm_bindgen::prelude::*;
 use lz4_flex::{compress_prepend_size, decompress_size_prepended};
 use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::deflate::core::{compress, create_comp_flags_from_zip_params, CompressorOxide, TDEFLFlush, TDEFLStatus};
 use miniz_oxide::inflate::decompress_to_vec;
+use miniz_oxide::inflate::stream::{inflate, InflateState};
+use miniz_oxide::{DataFormat, MZError, MZFlush, MZStatus};
 use crc32fast::Hasher;
 use sha2::{Sha256, Digest};
 
+use crate::error::{Error, Result};
+use crate::progress::{checkpoint, Progress};
+#[cfg(feature = "parallel")]
+use crate::parallel;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 
 /// Calculates the CRC32 checksum of the provided data.
 ///
@@ -37,11 +46,12 @@ use sha2::{Sha256, Digest};
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::crypto::calculate_crc32;
+///
 /// let data = b"Hello, world!";
 /// let checksum = calculate_crc32(data);
-/// println!("CRC32 checksum: {}", checksum);
+/// assert_eq!(checksum, 0xEBE6C6E6);
 /// ```
-#[wasm_bindgen]
 pub fn calculate_crc32(data: &[u8]) -> u32 {
     let mut hasher = Hasher::new();
     hasher.update(data);
@@ -82,11 +92,12 @@ pub fn calculate_crc32(data: &[u8]) -> u32 {
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::crypto::compress_data;
+///
 /// let data = b"Hello, world!";
 /// let compressed = compress_data(data);
 /// println!("Compressed data: {:?}", compressed);
 /// ```
-#[wasm_bindgen]
 pub fn compress_data(data: &[u8]) -> Vec<u8> {
     compress_prepend_size(data)
 }
@@ -106,13 +117,12 @@ pub fn compress_data(data: &[u8]) -> Vec<u8> {
 ///
 /// # Returns
 ///
-/// * `Vec<u8>` - A vector of bytes representing the decompressed (original) data.
+/// * `Result<Vec<u8>>` - A vector of bytes representing the decompressed (original) data.
 ///
 /// # Errors
 ///
-/// This function will panic if the decompression process fails. This can happen if the input data is not
-/// in the expected format (e.g., corrupted data or invalid compression format). The panic message will be:
-/// `"Decompression failed"`.
+/// Returns `Error::Decompression` if the decompression process fails. This can happen if the input data is not
+/// in the expected format (e.g., corrupted data or invalid compression format).
 ///
 /// # Performance Considerations
 ///
@@ -126,13 +136,14 @@ pub fn compress_data(data: &[u8]) -> Vec<u8> {
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::crypto::{compress_data, decompress_data};
+///
 /// let compressed = compress_data(b"Hello, world!");
-/// let decompressed = decompress_data(&compressed);
+/// let decompressed = decompress_data(&compressed).unwrap();
 /// assert_eq!(decompressed, b"Hello, world!");  // The decompressed data should match the original
 /// ```
-#[wasm_bindgen]
-pub fn decompress_data(data: &[u8]) -> Vec<u8> {
-    decompress_size_prepended(data).expect("Decompression failed")
+pub fn decompress_data(data: &[u8]) -> Result<Vec<u8>> {
+    decompress_size_prepended(data).map_err(|e| Error::Decompression(e.to_string()))
 }
 
 /// Compresses the provided data using the Deflate algorithm with a specified compression level.
@@ -177,11 +188,12 @@ pub fn decompress_data(data: &[u8]) -> Vec<u8> {
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::crypto::deflate_compress;
+///
 /// let data = b"Hello, world!";
 /// let compressed = deflate_compress(data);
 /// println!("Compressed data: {:?}", compressed);
 /// ```
-#[wasm_bindgen]
 pub fn deflate_compress(data: &[u8]) -> Vec<u8> {
     compress_to_vec(data, 6) // 6 is the compression level (1-9 range)
 }
@@ -191,7 +203,7 @@ pub fn deflate_compress(data: &[u8]) -> Vec<u8> {
 /// This function decompresses the input data that has been compressed using the Deflate algorithm.
 /// It assumes that the data is in the format generated by `deflate_compress` or any other Deflate-based
 /// compression scheme. If the input data is not valid or the decompression process fails, the function
-/// will panic with the message `"Decompression failed"`.
+/// returns `Error::Decompression`.
 ///
 /// # Arguments
 ///
@@ -200,13 +212,12 @@ pub fn deflate_compress(data: &[u8]) -> Vec<u8> {
 ///
 /// # Returns
 ///
-/// * `Vec<u8>` - A vector of bytes representing the decompressed (original) data.
+/// * `Result<Vec<u8>>` - A vector of bytes representing the decompressed (original) data.
 ///
 /// # Errors
 ///
-/// This function will panic if the decompression fails. A failure can occur if the input data is corrupted,
-/// improperly formatted, or if the Deflate decompression algorithm cannot process the data. The panic message
-/// will be: `"Decompression failed"`.
+/// Returns `Error::Decompression` if the decompression fails. A failure can occur if the input data is corrupted,
+/// improperly formatted, or if the Deflate decompression algorithm cannot process the data.
 ///
 /// # Performance Considerations
 ///
@@ -222,20 +233,119 @@ pub fn deflate_compress(data: &[u8]) -> Vec<u8> {
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::crypto::{deflate_compress, deflate_decompress};
+///
 /// let compressed = deflate_compress(b"Hello, world!");
-/// let decompressed = deflate_decompress(&compressed);
+/// let decompressed = deflate_decompress(&compressed).unwrap();
 /// assert_eq!(decompressed, b"Hello, world!");  // The decompressed data should match the original
 /// ```
-#[wasm_bindgen]
-pub fn deflate_decompress(data: &[u8]) -> Vec<u8> {
-    decompress_to_vec(data).expect("Decompression failed")
+pub fn deflate_decompress(data: &[u8]) -> Result<Vec<u8>> {
+    decompress_to_vec(data).map_err(|e| Error::Decompression(e.to_string()))
+}
+
+/// Output produced between two progress updates of the streaming Deflate functions.
+const DEFLATE_CHUNK_BYTES: usize = 1 << 16;
+
+/// Compresses data like [`deflate_compress`], reporting progress and allowing cancellation.
+///
+/// The output is byte-for-byte identical to `deflate_compress`. The input is fed to the compressor
+/// in 64 KiB slices; after every slice, and whenever 64 KiB of compressed output are ready (and
+/// once at the end), `progress` receives the fraction of `data` consumed and the number of input
+/// bytes consumed. If it returns `false`, compression stops with `Error::Cancelled`.
+///
+/// # Errors
+///
+/// Returns `Error::Cancelled` if `progress` cancelled the operation, and `Error::Compression` if
+/// the compressor fails internally.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::crypto::{deflate_compress, deflate_compress_with_progress};
+///
+/// let data = b"Hello, world!".repeat(1000);
+/// let compressed = deflate_compress_with_progress(&data, &mut |_fraction, _bytes| true).unwrap();
+/// assert_eq!(compressed, deflate_compress(&data));
+/// ```
+pub fn deflate_compress_with_progress(data: &[u8], progress: &mut dyn Progress) -> Result<Vec<u8>> {
+    // Same parameters as `compress_to_vec(data, 6)`: level 6, raw Deflate, default strategy
+    let mut compressor = CompressorOxide::new(create_comp_flags_from_zip_params(6, 0, 0));
+    let total = data.len().max(1) as f64;
+    let mut output = Vec::new();
+    let mut chunk = vec![0u8; DEFLATE_CHUNK_BYTES];
+    let mut in_pos = 0;
+
+    loop {
+        // Bounded input and output windows make `compress` return regularly, even for input that
+        // compresses to almost nothing, so progress can be reported
+        let end = data.len().min(in_pos + DEFLATE_CHUNK_BYTES);
+        let flush = if end == data.len() { TDEFLFlush::Finish } else { TDEFLFlush::None };
+        let (status, bytes_in, bytes_out) = compress(&mut compressor, &data[in_pos..end], &mut chunk, flush);
+        in_pos += bytes_in;
+        output.extend_from_slice(&chunk[..bytes_out]);
+
+        match status {
+            TDEFLStatus::Done => break,
+            TDEFLStatus::Okay => checkpoint(progress, in_pos as f64 / total, in_pos)?,
+            status => return Err(Error::Compression(format!("Deflate compressor returned {:?}", status))),
+        }
+    }
+    checkpoint(progress, 1.0, in_pos)?;
+
+    Ok(output)
+}
+
+/// Decompresses a raw Deflate stream like [`deflate_decompress`], reporting progress and allowing
+/// cancellation.
+///
+/// After every 64 KiB of decompressed output (and once at the end) `progress` receives the fraction
+/// of `data` consumed and the number of bytes decompressed so far. If it returns `false`,
+/// decompression stops with `Error::Cancelled`.
+///
+/// # Errors
+///
+/// Returns `Error::Decompression` if the data is corrupted or truncated, and `Error::Cancelled` if
+/// `progress` cancelled the operation.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::crypto::{deflate_compress, deflate_decompress_with_progress};
+///
+/// let compressed = deflate_compress(b"Hello, world!");
+/// let decompressed = deflate_decompress_with_progress(&compressed, &mut |_fraction, _bytes| true).unwrap();
+/// assert_eq!(decompressed, b"Hello, world!");
+/// ```
+pub fn deflate_decompress_with_progress(data: &[u8], progress: &mut dyn Progress) -> Result<Vec<u8>> {
+    let mut state = InflateState::new_boxed(DataFormat::Raw);
+    let total = data.len().max(1) as f64;
+    let mut output = Vec::new();
+    let mut chunk = vec![0u8; DEFLATE_CHUNK_BYTES];
+    let mut in_pos = 0;
+
+    loop {
+        let result = inflate(&mut state, &data[in_pos..], &mut chunk, MZFlush::None);
+        in_pos += result.bytes_consumed;
+        output.extend_from_slice(&chunk[..result.bytes_written]);
+
+        match result.status {
+            Ok(MZStatus::StreamEnd) => break,
+            Ok(_) => {}
+            // `Buf` only means "no progress possible": an error once the input is exhausted
+            Err(MZError::Buf) if result.bytes_consumed > 0 || result.bytes_written > 0 => {}
+            Err(MZError::Buf) => return Err(Error::Decompression("Truncated input stream".to_string())),
+            Err(_) => return Err(Error::Decompression("Invalid input data".to_string())),
+        }
+        checkpoint(progress, in_pos as f64 / total, output.len())?;
+    }
+    checkpoint(progress, 1.0, output.len())?;
+
+    Ok(output)
 }
 
 /// Computes the SHA-256 hash of the given input data.
 ///
 /// This function takes a byte slice (`&[u8]`) as input and computes its SHA-256 hash using the
-/// SHA-256 algorithm from the `sha2` crate. The result is returned as a `Vec<u8>`, which contains
-/// the 256-bit hash in a byte vector form. SHA-256 produces a fixed-size output (32 bytes) regardless
+/// SHA-256 algorithm from the `sha2` crate. The result is returned as a `[u8; 32]` array, which contains
+/// the 256-bit hash. SHA-256 produces a fixed-size output (32 bytes) regardless
 /// of the input size.
 ///
 /// SHA-256 is a cryptographic hash function that is commonly used for integrity checks, digital
@@ -248,10 +358,12 @@ pub fn deflate_decompress(data: &[u8]) -> Vec<u8> {
 ///
 /// # Returns
 ///
-/// * `Vec<u8>` - A vector of 32 bytes representing the SHA-256 hash of the input data.
+/// * `[u8; 32]` - The 32 bytes of the SHA-256 hash of the input data.
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::crypto::sha256_hash;
+///
 /// let input_data = b"Hello, world!";
 /// let hash = sha256_hash(input_data);
 /// println!("SHA-256 hash: {:?}", hash);
@@ -266,9 +378,130 @@ pub fn deflate_decompress(data: &[u8]) -> Vec<u8> {
 /// - **Hash Computation Speed**: SHA-256 is designed to be fast on modern processors, but the time
 ///   required will increase with larger input data. However, for most typical use cases (small to
 ///   medium-sized data), SHA-256 is efficient and performs well.
-#[wasm_bindgen]
-pub fn sha256_hash(input: &[u8]) -> Vec<u8> {
+pub fn sha256_hash(input: &[u8]) -> [u8; 32] {
     let mut hasher = Sha256::new();
     hasher.update(input);
-    hasher.finalize().to_vec()
-}
\ No newline at end of file
+    hasher.finalize().into()
+}
+/// Computes the SHA-256 hash of many independent buffers, hashing them in parallel.
+///
+/// Each buffer is hashed with [`sha256_hash`] on the rayon thread pool; the digests are returned in
+/// the same order as `inputs`. When there is a single buffer, the total size is below
+/// `parallel::MIN_PARALLEL_BYTES`, or no worker threads are available, the buffers are hashed one
+/// after the other on the calling thread.
+///
+/// # Arguments
+///
+/// * `inputs` - The buffers to hash. Anything that can be viewed as `&[u8]` is accepted
+///   (`Vec<u8>`, `&[u8]`, `String`, ...).
+///
+/// # Returns
+///
+/// * `Vec<[u8; 32]>` - One digest per input buffer.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::crypto::{sha256_hash, sha256_hash_many};
+///
+/// let files = vec![b"first".to_vec(), b"second".to_vec()];
+/// let digests = sha256_hash_many(&files);
+/// assert_eq!(digests[1], sha256_hash(b"second"));
+/// ```
+#[cfg(feature = "parallel")]
+pub fn sha256_hash_many<B: AsRef<[u8]> + Sync>(inputs: &[B]) -> Vec<[u8; 32]> {
+    let total_len: usize = inputs.iter().map(|input| input.as_ref().len()).sum();
+    if inputs.len() < 2 || !parallel::should_parallelize(total_len, parallel::MIN_PARALLEL_BYTES) {
+        return inputs.iter().map(|input| sha256_hash(input.as_ref())).collect();
+    }
+
+    inputs.par_iter().map(|input| sha256_hash(input.as_ref())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn lz4_round_trip() {
+        let data = b"hello hello hello hello hello";
+        assert_eq!(decompress_data(&compress_data(data)).unwrap(), data);
+    }
+
+    #[test]
+    fn deflate_round_trip() {
+        let data = b"hello hello hello hello hello";
+        assert_eq!(deflate_decompress(&deflate_compress(data)).unwrap(), data);
+    }
+
+    #[test]
+    fn corrupted_input_is_an_error_not_a_panic() {
+        assert_eq!(decompress_data(&[1, 2, 3]).unwrap_err().code(), "DECOMPRESSION_FAILED");
+        assert_eq!(deflate_decompress(&[0xff; 8]).unwrap_err().code(), "DECOMPRESSION_FAILED");
+    }
+
+    #[test]
+    fn deflate_with_progress_matches_plain_functions() {
+        // Random nibbles: compressible, but still well over one 64 KiB output chunk
+        let mut state = 0x2545_f491u32;
+        let data: Vec<u8> = (0..300_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 28) as u8
+            })
+            .collect();
+        let mut updates = 0;
+        let compressed = deflate_compress_with_progress(&data, &mut |_, _| {
+            updates += 1;
+            true
+        })
+        .unwrap();
+        assert_eq!(compressed, deflate_compress(&data));
+        assert!(updates > 1);
+
+        let mut last = (0.0, 0);
+        let restored = deflate_decompress_with_progress(&compressed, &mut |fraction, bytes| {
+            last = (fraction, bytes);
+            true
+        })
+        .unwrap();
+        assert_eq!(restored, data);
+        assert_eq!(last, (1.0, data.len()));
+
+        let truncated = &compressed[..compressed.len() / 2];
+        let err = deflate_decompress_with_progress(truncated, &mut |_, _| true).unwrap_err();
+        assert_eq!(err.code(), "DECOMPRESSION_FAILED");
+    }
+
+    #[test]
+    fn deflate_with_progress_can_be_cancelled() {
+        let data = vec![7u8; 1 << 20];
+        let compressed = deflate_compress(&data);
+        assert_eq!(deflate_compress_with_progress(&data, &mut |_, _| false), Err(Error::Cancelled));
+
+        // Constant input compresses to a few bytes, yet progress is reported while it is consumed
+        let mut consumed = Vec::new();
+        let result = deflate_compress_with_progress(&data, &mut |_, bytes| {
+            consumed.push(bytes);
+            consumed.len() < 4
+        });
+        assert_eq!(result, Err(Error::Cancelled));
+        assert_eq!(consumed, vec![1 << 16, 2 << 16, 3 << 16, 4 << 16]);
+        assert_eq!(deflate_decompress_with_progress(&compressed, &mut |_, _| false), Err(Error::Cancelled));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn sha256_hash_many_matches_single_hashes() {
+        let inputs: Vec<Vec<u8>> = (0..64).map(|i| vec![i as u8; 4096 + i]).collect();
+        let digests = sha256_hash_many(&inputs);
+        assert_eq!(digests.len(), inputs.len());
+        for (input, digest) in inputs.iter().zip(&digests) {
+            assert_eq!(*digest, sha256_hash(input));
+        }
+    }
+}
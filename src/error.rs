@@ -0,0 +1,91 @@
+use std::fmt;
+
+/// Errors returned by the native Rust API.
+///
+/// Every fallible function in the library returns `Result<_, Error>`. The WebAssembly
+/// wrappers in the `wasm` module convert these errors into JavaScript `Error` objects
+/// carrying the stable [`Error::code`] and the human readable message, so the same failure
+/// is reported consistently whether the code runs natively or in the browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The input could not be decompressed (corrupted data or wrong format).
+    Decompression(String),
+    /// The compressor failed internally; this does not depend on the input.
+    Compression(String),
+    /// The CSV input could not be parsed.
+    Csv(String),
+    /// A value could not be serialized.
+    Serialization(String),
+    /// The input does not have the shape the function expects (e.g. a truncated RGBA buffer).
+    InvalidInput(String),
+    /// The operation was cancelled through its progress callback.
+    Cancelled,
+}
+
+impl Error {
+    /// Returns a stable, machine readable code for this error.
+    ///
+    /// Codes never change between releases, so callers can match on them instead of parsing
+    /// the message.
+    ///
+    /// | Variant         | Code                   |
+    /// |-----------------|------------------------|
+    /// | `Decompression` | `DECOMPRESSION_FAILED` |
+    /// | `Compression`   | `COMPRESSION_FAILED`   |
+    /// | `Csv`           | `CSV_PARSE_FAILED`     |
+    /// | `Serialization` | `SERIALIZATION_FAILED` |
+    /// | `InvalidInput`  | `INVALID_INPUT`        |
+    /// | `Cancelled`     | `CANCELLED`            |
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Decompression(_) => "DECOMPRESSION_FAILED",
+            Error::Compression(_) => "COMPRESSION_FAILED",
+            Error::Csv(_) => "CSV_PARSE_FAILED",
+            Error::Serialization(_) => "SERIALIZATION_FAILED",
+            Error::InvalidInput(_) => "INVALID_INPUT",
+            Error::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Decompression(msg) => write!(f, "Decompression failed: {}", msg),
+            Error::Compression(msg) => write!(f, "Compression failed: {}", msg),
+            Error::Csv(msg) => write!(f, "CSV parsing failed: {}", msg),
+            Error::Serialization(msg) => write!(f, "Serialization failed: {}", msg),
+            Error::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            Error::Cancelled => write!(f, "Operation cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Convenience alias used throughout the native API.
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn codes_are_stable() {
+        assert_eq!(Error::Decompression(String::new()).code(), "DECOMPRESSION_FAILED");
+        assert_eq!(Error::Compression(String::new()).code(), "COMPRESSION_FAILED");
+        assert_eq!(Error::Csv(String::new()).code(), "CSV_PARSE_FAILED");
+        assert_eq!(Error::Serialization(String::new()).code(), "SERIALIZATION_FAILED");
+        assert_eq!(Error::InvalidInput(String::new()).code(), "INVALID_INPUT");
+        assert_eq!(Error::Cancelled.code(), "CANCELLED");
+    }
+
+    #[test]
+    fn display_includes_message() {
+        let err = Error::InvalidInput("length 7 is not a multiple of 4".to_string());
+        assert_eq!(err.to_string(), "Invalid input: length 7 is not a multiple of 4");
+    }
+}
@@ -0,0 +1,77 @@
+use super::{input_mut, status};
+use crate::array;
+
+/// Sorts `len` integers in place with pdqsort. See `array::quick_sort_in_place`.
+///
+/// # Safety
+///
+/// `values` must be valid for reads and writes of `len` elements.
+#[no_mangle]
+pub unsafe extern "C" fn rs_quick_sort_i32(values: *mut i32, len: usize) -> i32 {
+    status(|| {
+        array::quick_sort_in_place(input_mut(values, len)?);
+        Ok(())
+    })
+}
+
+/// Sorts `len` integers in place with the stable MergeSort. See `array::merge_sort`.
+///
+/// # Safety
+///
+/// `values` must be valid for reads and writes of `len` elements.
+#[no_mangle]
+pub unsafe extern "C" fn rs_merge_sort_i32(values: *mut i32, len: usize) -> i32 {
+    status(|| {
+        let values = input_mut(values, len)?;
+        values.copy_from_slice(&array::merge_sort(values));
+        Ok(())
+    })
+}
+
+/// Sorts `len` doubles in place with pdqsort. See `array::quick_sort_in_place`.
+///
+/// # Safety
+///
+/// `values` must be valid for reads and writes of `len` elements.
+#[no_mangle]
+pub unsafe extern "C" fn rs_quick_sort_f64(values: *mut f64, len: usize) -> i32 {
+    status(|| {
+        array::quick_sort_in_place(input_mut(values, len)?);
+        Ok(())
+    })
+}
+
+/// Sorts `len` doubles in place with the stable MergeSort. See `array::merge_sort`.
+///
+/// # Safety
+///
+/// `values` must be valid for reads and writes of `len` elements.
+#[no_mangle]
+pub unsafe extern "C" fn rs_merge_sort_f64(values: *mut f64, len: usize) -> i32 {
+    status(|| {
+        let values = input_mut(values, len)?;
+        values.copy_from_slice(&array::merge_sort(values));
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::{RS_ERR_INVALID_INPUT, RS_OK};
+    use std::ptr;
+
+    #[test]
+    fn sorts_in_place() {
+        let mut ints = [5, -2, 9, 0];
+        let mut floats = [2.5, -1.0, 0.5];
+        unsafe {
+            assert_eq!(rs_merge_sort_i32(ints.as_mut_ptr(), ints.len()), RS_OK);
+            assert_eq!(rs_quick_sort_f64(floats.as_mut_ptr(), floats.len()), RS_OK);
+            assert_eq!(rs_quick_sort_i32(ptr::null_mut(), 0), RS_OK);
+            assert_eq!(rs_merge_sort_f64(ptr::null_mut(), 2), RS_ERR_INVALID_INPUT);
+        }
+        assert_eq!(ints, [-2, 0, 5, 9]);
+        assert_eq!(floats, [-1.0, 0.5, 2.5]);
+    }
+}
@@ -0,0 +1,130 @@
+use super::{input, input_mut, output, status, RsBuffer};
+use crate::crypto;
+
+/// Computes the CRC32 checksum of `data` into `*out`. See `crypto::calculate_crc32`.
+///
+/// # Safety
+///
+/// `data` must be valid for reads of `len` bytes and `out` valid for a `uint32_t` write.
+#[no_mangle]
+pub unsafe extern "C" fn rs_calculate_crc32(data: *const u8, len: usize, out: *mut u32) -> i32 {
+    status(|| {
+        *output(out)? = crypto::calculate_crc32(input(data, len)?);
+        Ok(())
+    })
+}
+
+/// Writes the 32-byte SHA-256 digest of `data` to `out`. See `crypto::sha256_hash`.
+///
+/// # Safety
+///
+/// `data` must be valid for reads of `len` bytes and `out` valid for writes of 32 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rs_sha256_hash(data: *const u8, len: usize, out: *mut u8) -> i32 {
+    status(|| {
+        input_mut(out, 32)?.copy_from_slice(&crypto::sha256_hash(input(data, len)?));
+        Ok(())
+    })
+}
+
+/// LZ4-compresses `data` with the original size prepended into a new buffer. See
+/// `crypto::compress_data`.
+///
+/// # Safety
+///
+/// `data` must be valid for reads of `len` bytes and `out` valid for an `RsBuffer` write. A
+/// previous buffer in `*out` is overwritten, not freed.
+#[no_mangle]
+pub unsafe extern "C" fn rs_compress_data(data: *const u8, len: usize, out: *mut RsBuffer) -> i32 {
+    status(|| {
+        let out = output(out)?;
+        *out = RsBuffer::from_vec(crypto::compress_data(input(data, len)?));
+        Ok(())
+    })
+}
+
+/// Decompresses the output of `rs_compress_data` into a new buffer. See `crypto::decompress_data`.
+///
+/// Returns `RS_ERR_DECOMPRESSION` on invalid input.
+///
+/// # Safety
+///
+/// Same as `rs_compress_data`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_decompress_data(data: *const u8, len: usize, out: *mut RsBuffer) -> i32 {
+    status(|| {
+        let out = output(out)?;
+        *out = RsBuffer::from_vec(crypto::decompress_data(input(data, len)?)?);
+        Ok(())
+    })
+}
+
+/// Deflate-compresses `data` into a new buffer. See `crypto::deflate_compress`.
+///
+/// # Safety
+///
+/// Same as `rs_compress_data`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_deflate_compress(data: *const u8, len: usize, out: *mut RsBuffer) -> i32 {
+    status(|| {
+        let out = output(out)?;
+        *out = RsBuffer::from_vec(crypto::deflate_compress(input(data, len)?));
+        Ok(())
+    })
+}
+
+/// Decompresses a raw Deflate stream into a new buffer. See `crypto::deflate_decompress`.
+///
+/// Returns `RS_ERR_DECOMPRESSION` on invalid input.
+///
+/// # Safety
+///
+/// Same as `rs_compress_data`.
+#[no_mangle]
+pub unsafe extern "C" fn rs_deflate_decompress(data: *const u8, len: usize, out: *mut RsBuffer) -> i32 {
+    status(|| {
+        let out = output(out)?;
+        *out = RsBuffer::from_vec(crypto::deflate_decompress(input(data, len)?)?);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::{rs_buffer_free, RS_ERR_DECOMPRESSION, RS_ERR_INVALID_INPUT, RS_OK};
+    use std::ptr;
+
+    #[test]
+    fn hashes_match_native_api() {
+        let data = b"abc";
+        let mut crc = 0;
+        let mut digest = [0u8; 32];
+        unsafe {
+            assert_eq!(rs_calculate_crc32(data.as_ptr(), data.len(), &mut crc), RS_OK);
+            assert_eq!(rs_sha256_hash(data.as_ptr(), data.len(), digest.as_mut_ptr()), RS_OK);
+            assert_eq!(rs_sha256_hash(ptr::null(), 1, digest.as_mut_ptr()), RS_ERR_INVALID_INPUT);
+            assert_eq!(rs_calculate_crc32(data.as_ptr(), data.len(), ptr::null_mut()), RS_ERR_INVALID_INPUT);
+        }
+        assert_eq!(crc, crypto::calculate_crc32(data));
+        assert_eq!(digest, crypto::sha256_hash(data));
+    }
+
+    #[test]
+    fn compression_round_trip_through_buffers() {
+        let data = b"round trip round trip round trip";
+        let mut compressed = RsBuffer { data: ptr::null_mut(), len: 0 };
+        let mut restored = RsBuffer { data: ptr::null_mut(), len: 0 };
+        unsafe {
+            assert_eq!(rs_deflate_compress(data.as_ptr(), data.len(), &mut compressed), RS_OK);
+            assert_eq!(rs_deflate_decompress(compressed.data, compressed.len, &mut restored), RS_OK);
+            assert_eq!(std::slice::from_raw_parts(restored.data, restored.len), data);
+            rs_buffer_free(&mut compressed);
+            rs_buffer_free(&mut restored);
+
+            assert_eq!(rs_decompress_data([1, 2, 3].as_ptr(), 3, &mut restored), RS_ERR_DECOMPRESSION);
+            assert!(restored.data.is_null());
+        }
+        assert_eq!((compressed.len, restored.len), (0, 0));
+    }
+}
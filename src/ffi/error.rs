@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::ptr;
+
+#[cfg(any(feature = "array", feature = "crypto", feature = "images", feature = "json"))]
+use crate::error::{Error, Result};
+
+/// The call succeeded.
+pub const RS_OK: i32 = 0;
+/// The input could not be decompressed (`DECOMPRESSION_FAILED`).
+pub const RS_ERR_DECOMPRESSION: i32 = 1;
+/// The CSV input could not be parsed (`CSV_PARSE_FAILED`).
+pub const RS_ERR_CSV: i32 = 2;
+/// A value could not be serialized (`SERIALIZATION_FAILED`).
+pub const RS_ERR_SERIALIZATION: i32 = 3;
+/// The input does not have the expected shape, or a required pointer is `NULL` (`INVALID_INPUT`).
+pub const RS_ERR_INVALID_INPUT: i32 = 4;
+/// The operation was cancelled (`CANCELLED`).
+pub const RS_ERR_CANCELLED: i32 = 5;
+/// The compressor failed internally (`COMPRESSION_FAILED`).
+pub const RS_ERR_COMPRESSION: i32 = 6;
+
+thread_local! {
+    /// Message of the last error returned on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Returns the integer status for `error`. Like [`Error::code`], the numbers never change
+/// between releases.
+#[cfg(any(feature = "array", feature = "crypto", feature = "images", feature = "json"))]
+fn status_code(error: &Error) -> i32 {
+    match error {
+        Error::Decompression(_) => RS_ERR_DECOMPRESSION,
+        Error::Csv(_) => RS_ERR_CSV,
+        Error::Serialization(_) => RS_ERR_SERIALIZATION,
+        Error::InvalidInput(_) => RS_ERR_INVALID_INPUT,
+        Error::Cancelled => RS_ERR_CANCELLED,
+        Error::Compression(_) => RS_ERR_COMPRESSION,
+    }
+}
+
+/// Runs the body of an `rs_*` function and converts its result into a status, recording the
+/// message of a failure for `rs_last_error_message`.
+#[cfg(any(feature = "array", feature = "crypto", feature = "images", feature = "json"))]
+pub(crate) fn status(body: impl FnOnce() -> Result<()>) -> i32 {
+    match body() {
+        Ok(()) => RS_OK,
+        Err(error) => {
+            // `Display` output never contains NUL bytes unless the input did; drop them
+            let message = CString::new(error.to_string().replace('\0', "")).unwrap_or_default();
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+            status_code(&error)
+        }
+    }
+}
+
+/// Returns the message of the last error returned on the calling thread, or `NULL` if no call
+/// has failed yet.
+///
+/// The string is NUL-terminated UTF-8 owned by the library. It stays valid until the next
+/// failing call on the same thread; copy it to keep it longer.
+#[no_mangle]
+pub extern "C" fn rs_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+#[cfg(all(test, any(feature = "array", feature = "crypto", feature = "images", feature = "json")))]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn status_records_the_message() {
+        assert_eq!(status(|| Ok(())), RS_OK);
+        assert_eq!(status(|| Err(Error::Csv("bad quote".to_string()))), RS_ERR_CSV);
+        let message = unsafe { CStr::from_ptr(rs_last_error_message()) };
+        assert_eq!(message.to_str().unwrap(), "CSV parsing failed: bad quote");
+        assert_eq!(status(|| Err(Error::Cancelled)), RS_ERR_CANCELLED);
+    }
+}
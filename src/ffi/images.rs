@@ -0,0 +1,45 @@
+use super::{input_mut, status};
+use crate::images;
+
+/// Inverts every byte of an RGBA buffer in place. See `images::invert_colors`.
+///
+/// # Safety
+///
+/// `pixels` must be valid for reads and writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rs_invert_colors(pixels: *mut u8, len: usize) -> i32 {
+    status(|| {
+        images::invert_colors(input_mut(pixels, len)?);
+        Ok(())
+    })
+}
+
+/// Converts an RGBA buffer to grayscale in place. See `images::grayscale_in_place`.
+///
+/// Returns `RS_ERR_INVALID_INPUT` if `len` is not a multiple of 4.
+///
+/// # Safety
+///
+/// `pixels` must be valid for reads and writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rs_grayscale(pixels: *mut u8, len: usize) -> i32 {
+    status(|| images::grayscale_in_place(input_mut(pixels, len)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::{RS_ERR_INVALID_INPUT, RS_OK};
+
+    #[test]
+    fn filters_work_in_place() {
+        let original = [10, 200, 30, 255, 0, 0, 0, 128];
+        let mut pixels = original;
+        unsafe {
+            assert_eq!(rs_grayscale(pixels.as_mut_ptr(), pixels.len()), RS_OK);
+            assert_eq!(pixels.to_vec(), images::grayscale(&original).unwrap());
+            assert_eq!(rs_invert_colors(pixels.as_mut_ptr(), 3), RS_OK);
+            assert_eq!(rs_grayscale(pixels.as_mut_ptr(), 7), RS_ERR_INVALID_INPUT);
+        }
+    }
+}
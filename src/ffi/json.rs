@@ -0,0 +1,48 @@
+use super::{input, output, status, RsBuffer};
+use crate::error::Error;
+use crate::json;
+
+/// Parses UTF-8 CSV text into JSON Lines: one JSON array per record, each followed by `\n`, in a
+/// new buffer (the same output as `rust-scripts csv2json`). See `json::parse_csv_to_json`.
+///
+/// Returns `RS_ERR_INVALID_INPUT` if `content` is not UTF-8, or `RS_ERR_CSV` /
+/// `RS_ERR_SERIALIZATION`.
+///
+/// # Safety
+///
+/// `content` must be valid for reads of `len` bytes and `out` valid for an `RsBuffer` write. A
+/// previous buffer in `*out` is overwritten, not freed.
+#[no_mangle]
+pub unsafe extern "C" fn rs_parse_csv_to_json(content: *const u8, len: usize, out: *mut RsBuffer) -> i32 {
+    status(|| {
+        let out = output(out)?;
+        let content = std::str::from_utf8(input(content, len)?).map_err(|e| Error::InvalidInput(e.to_string()))?;
+        let mut lines = String::new();
+        for record in json::parse_csv_to_json(content)? {
+            lines.push_str(&record);
+            lines.push('\n');
+        }
+        *out = RsBuffer::from_vec(lines.into_bytes());
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::{rs_buffer_free, RS_ERR_INVALID_INPUT, RS_OK};
+    use std::ptr;
+
+    #[test]
+    fn writes_one_record_per_line() {
+        let csv = b"name,age\nJohn,30\nAlice,25\n";
+        let mut out = RsBuffer { data: ptr::null_mut(), len: 0 };
+        unsafe {
+            assert_eq!(rs_parse_csv_to_json(csv.as_ptr(), csv.len(), &mut out), RS_OK);
+            assert_eq!(std::slice::from_raw_parts(out.data, out.len), b"[\"John\",\"30\"]\n[\"Alice\",\"25\"]\n");
+            rs_buffer_free(&mut out);
+
+            assert_eq!(rs_parse_csv_to_json([0xff, 0xfe].as_ptr(), 2, &mut out), RS_ERR_INVALID_INPUT);
+        }
+    }
+}
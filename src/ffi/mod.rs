@@ -0,0 +1,135 @@
+//! C ABI bindings.
+//!
+//! Every function in these modules is a thin `extern "C"` wrapper over the native API of the
+//! module with the same name (e.g. `ffi::crypto::rs_sha256_hash` wraps `crypto::sha256_hash`),
+//! so C and C++ callers get exactly the same results as the WebAssembly build. The header
+//! `include/rust_scripts.h` is generated from these declarations by `build.rs` whenever the
+//! crate is built with the `ffi` feature.
+//!
+//! Conventions shared by all functions:
+//!
+//! * Every function is prefixed with `rs_` and returns an `int32_t` status: `RS_OK` (0) on
+//!   success or one of the `RS_ERR_*` codes (see `error.rs`). The message of the last failure on
+//!   the calling thread is available from `rs_last_error_message`.
+//! * Inputs are `(pointer, length)` pairs owned by the caller. A null pointer is accepted only
+//!   together with a length of 0.
+//! * Results of known size are written into caller-owned memory (in place for images and sorts,
+//!   a 32-byte array for SHA-256). Results of variable size are returned in an [`RsBuffer`]
+//!   allocated by the library, which the caller must release with [`rs_buffer_free`].
+//!
+//! ```c
+//! RsBuffer compressed = {0};
+//! if (rs_deflate_compress(data, len, &compressed) != RS_OK) {
+//!     fprintf(stderr, "%s\n", rs_last_error_message());
+//! }
+//! fwrite(compressed.data, 1, compressed.len, out);
+//! rs_buffer_free(&compressed);
+//! ```
+
+mod error;
+
+pub use error::*;
+
+#[cfg(feature = "array")]
+pub mod array;
+#[cfg(feature = "crypto")]
+pub mod crypto;
+#[cfg(feature = "images")]
+pub mod images;
+#[cfg(feature = "json")]
+pub mod json;
+
+use std::ptr;
+#[cfg(any(feature = "array", feature = "crypto", feature = "images", feature = "json"))]
+use std::slice;
+
+#[cfg(any(feature = "array", feature = "crypto", feature = "images", feature = "json"))]
+use crate::error::{Error, Result};
+
+/// A byte buffer allocated by the library.
+///
+/// Returned through an out-parameter by the functions whose output size is only known once it
+/// has been computed. The caller owns it and must release it with `rs_buffer_free`, exactly once.
+/// A zero-initialized `RsBuffer` (`data == NULL`, `len == 0`) is valid and empty.
+#[repr(C)]
+pub struct RsBuffer {
+    /// Start of the bytes, or `NULL` for an empty buffer.
+    pub data: *mut u8,
+    /// Number of bytes at `data`.
+    pub len: usize,
+}
+
+impl RsBuffer {
+    /// Hands `bytes` over to the caller.
+    #[cfg(any(feature = "crypto", feature = "json"))]
+    fn from_vec(bytes: Vec<u8>) -> RsBuffer {
+        if bytes.is_empty() {
+            return RsBuffer { data: ptr::null_mut(), len: 0 };
+        }
+        let len = bytes.len();
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        RsBuffer { data, len }
+    }
+}
+
+/// Releases the memory of a buffer returned by the library and resets it to empty.
+///
+/// Passing `NULL` or an already empty buffer does nothing.
+///
+/// # Safety
+///
+/// `buffer` must be `NULL` or point to an `RsBuffer` that is empty or was filled by this library
+/// and not modified since.
+#[no_mangle]
+pub unsafe extern "C" fn rs_buffer_free(buffer: *mut RsBuffer) {
+    let Some(buffer) = buffer.as_mut() else {
+        return;
+    };
+    if !buffer.data.is_null() {
+        // Safety: `data`/`len` come from `Box<[u8]>::into_raw` in `RsBuffer::from_vec`
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)));
+    }
+    buffer.data = ptr::null_mut();
+    buffer.len = 0;
+}
+
+/// Borrows a caller-owned input array.
+///
+/// # Safety
+///
+/// `data` must be null or valid for reads of `len` elements for the returned lifetime.
+#[cfg(any(feature = "crypto", feature = "json"))]
+unsafe fn input<'a, T>(data: *const T, len: usize) -> Result<&'a [T]> {
+    if data.is_null() {
+        return if len == 0 { Ok(&[]) } else { Err(null_pointer()) };
+    }
+    Ok(slice::from_raw_parts(data, len))
+}
+
+/// Borrows a caller-owned array that is modified in place.
+///
+/// # Safety
+///
+/// `data` must be null or valid for reads and writes of `len` elements for the returned lifetime.
+#[cfg(any(feature = "array", feature = "crypto", feature = "images"))]
+unsafe fn input_mut<'a, T>(data: *mut T, len: usize) -> Result<&'a mut [T]> {
+    if data.is_null() {
+        return if len == 0 { Ok(&mut []) } else { Err(null_pointer()) };
+    }
+    Ok(slice::from_raw_parts_mut(data, len))
+}
+
+/// Borrows a caller-owned out-parameter.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes for the returned lifetime.
+#[cfg(any(feature = "crypto", feature = "json"))]
+unsafe fn output<'a, T>(out: *mut T) -> Result<&'a mut T> {
+    out.as_mut().ok_or_else(null_pointer)
+}
+
+#[cfg(any(feature = "array", feature = "crypto", feature = "images", feature = "json"))]
+fn null_pointer() -> Error {
+    Error::InvalidInput("unexpected NULL pointer".to_string())
+}
@@ -1,7 +1,12 @@
-use wasm_bindgen::prelude::*;
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+use std::arch::wasm32::*;
 
+use crate::error::{Error, Result};
+#[cfg(feature = "parallel")]
+use crate::parallel;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-use std::arch::wasm32::*;
 /// Inverts the color values of a given array of bytes.
 ///
 /// This function performs an in-place inversion of each byte in the input array `bytes`.
@@ -20,16 +25,14 @@ use std::arch::wasm32::*;
 /// (e.g., modern web browsers with WebAssembly SIMD support).
 ///
 /// # Safety
-/// The function uses `unsafe` blocks to bypass bounds checks in two cases:
-/// - When processing data in 16-byte chunks with SIMD, where it is ensured that `i + 16 <= len`.
-/// - When handling any remaining bytes individually after the main SIMD loop, using `get_unchecked_mut`
-///   to maximize speed by eliminating bounds checks.
+/// The SIMD path uses an `unsafe` block to load and store 16-byte chunks, where it is ensured that
+/// `i + 16 <= len`. The remaining bytes are handled by the safe scalar loop.
 ///
 /// Although `unsafe` is used, this function is safe to call as it performs internal bounds management.
 ///
 /// # Examples
 /// ```rust
-/// use your_module::invert_colors;
+/// use rust_scripts::images::invert_colors;
 ///
 /// let mut colors = vec![0, 128, 255, 100, 50];
 /// invert_colors(&mut colors);
@@ -44,10 +47,15 @@ use std::arch::wasm32::*;
 /// This function does not panic, as it manages empty input by exiting early.
 ///
 /// # Compatibility
-/// This function requires a WebAssembly environment with SIMD enabled.
-/// Older environments or those without SIMD support may experience reduced performance.
-#[wasm_bindgen]
+/// The SIMD path is only compiled for `wasm32` with `simd128` enabled. Every other target
+/// (including native builds) uses `invert_colors_scalar`, which produces identical bytes.
 pub fn invert_colors(bytes: &mut [u8]) {
+    invert_colors_impl(bytes);
+}
+
+/// WebAssembly SIMD implementation of `invert_colors`.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+fn invert_colors_impl(bytes: &mut [u8]) {
     let len = bytes.len();
     let mut i = 0;
 
@@ -64,11 +72,23 @@ pub fn invert_colors(bytes: &mut [u8]) {
         }
     }
 
-    // Invert remaining bytes individually without bounds checks for extra performance
-    unsafe {
-        for j in i..len {
-            *bytes.get_unchecked_mut(j) = 255 - *bytes.get_unchecked(j);
-        }
+    // Invert remaining bytes individually
+    invert_colors_scalar(&mut bytes[i..]);
+}
+
+/// Portable fallback for targets without WebAssembly SIMD.
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+fn invert_colors_impl(bytes: &mut [u8]) {
+    invert_colors_scalar(bytes);
+}
+
+/// Inverts each byte individually (`255 - value`).
+///
+/// Written as a plain loop over the slice so that the compiler can auto-vectorize it on
+/// native targets (SSE2/NEON), while staying bit-identical to the SIMD path.
+fn invert_colors_scalar(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        *byte = 255 - *byte;
     }
 }
 
@@ -83,37 +103,198 @@ pub fn invert_colors(bytes: &mut [u8]) {
 ///   bytes corresponds to a single pixel in the format `[R, G, B, A]`. `R` is the red 
 ///   channel, `G` is the green channel, `B` is the blue channel, and `A` is the alpha channel.
 ///
-///   The input data should be a flat array where the number of elements is a multiple of 4,
+///   The input data must be a flat array where the number of elements is a multiple of 4,
 ///   and each pixel is represented by 4 bytes: `[R, G, B, A]`.
 ///
 /// # Returns
 ///
-/// * A `Vec<u8>` representing the grayscale image data in the same RGBA format. The grayscale 
+/// * A `Result<Vec<u8>>` representing the grayscale image data in the same RGBA format. The grayscale 
 ///   value for the red, green, and blue channels is calculated using the luminance formula:
-///   ```
+///   ```text
 ///   Gray = (0.299 * R) + (0.587 * G) + (0.114 * B)
 ///   ```
 ///   The alpha channel remains unchanged.
 ///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if the length of `input` is not a multiple of 4, instead of
+/// reading past the end of a truncated last pixel.
+///
 /// # Example
 /// ```rust
+/// use rust_scripts::images::grayscale;
+///
 /// let input_image: Vec<u8> = vec![
 ///     255, 0, 0, 255,  // Red pixel (opaque)
 ///     0, 255, 0, 255,  // Green pixel (opaque)
 ///     0, 0, 255, 255,  // Blue pixel (opaque)
 ///     255, 255, 255, 255, // White pixel (opaque)
 /// ];
-/// let output_image = grayscale(&input_image);
+/// let output_image = grayscale(&input_image).unwrap();
+/// assert_eq!(&output_image[..4], &[76, 76, 76, 255]);
 /// ```
 ///
 /// After calling `grayscale`, the `output_image` will contain the grayscale equivalents of
 /// the input pixels, with their alpha channel intact.
 ///
-#[wasm_bindgen]
-pub fn grayscale(input: &[u8]) -> Vec<u8> {
-    input.chunks(4).flat_map(|pixel| {
-        let gray = (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) as u8;
-        vec![gray, gray, gray, pixel[3]]
-    }).collect()
+pub fn grayscale(input: &[u8]) -> Result<Vec<u8>> {
+    check_rgba_len(input)?;
+
+    Ok(input.chunks_exact(4).flat_map(grayscale_pixel).collect())
+}
+
+/// Converts an image of RGBA pixels to grayscale in place.
+///
+/// Applies the same conversion as [`grayscale`], but overwrites `pixels` instead of allocating a new
+/// vector. This is what the zero-copy wasm exports use to work directly on a buffer that lives in
+/// WebAssembly memory.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if the length of `pixels` is not a multiple of 4. The buffer is left
+/// untouched in that case.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::images::grayscale_in_place;
+///
+/// let mut pixels = vec![255, 0, 0, 255, 0, 0, 255, 128];
+/// grayscale_in_place(&mut pixels).unwrap();
+/// assert_eq!(pixels, vec![76, 76, 76, 255, 29, 29, 29, 128]);
+/// ```
+pub fn grayscale_in_place(pixels: &mut [u8]) -> Result<()> {
+    check_rgba_len(pixels)?;
+
+    for pixel in pixels.chunks_exact_mut(4) {
+        let gray = grayscale_pixel(pixel);
+        pixel.copy_from_slice(&gray);
+    }
+    Ok(())
+}
+
+/// Converts RGBA pixels to grayscale like [`grayscale`], splitting the image across the rayon
+/// thread pool.
+///
+/// The image is cut into chunks of whole pixels that are converted independently, so the output is
+/// byte-for-byte identical to `grayscale`. Images smaller than `parallel::MIN_PARALLEL_BYTES`, or
+/// environments without worker threads, use `grayscale` directly.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidInput` if the length of `input` is not a multiple of 4.
+#[cfg(feature = "parallel")]
+pub fn grayscale_parallel(input: &[u8]) -> Result<Vec<u8>> {
+    check_rgba_len(input)?;
+    if !parallel::should_parallelize(input.len(), parallel::MIN_PARALLEL_BYTES) {
+        return grayscale(input);
+    }
+
+    let mut output = vec![0u8; input.len()];
+    output
+        .par_chunks_mut(parallel::PARALLEL_CHUNK_BYTES)
+        .zip(input.par_chunks(parallel::PARALLEL_CHUNK_BYTES))
+        .for_each(|(out, pixels)| {
+            for (dst, pixel) in out.chunks_exact_mut(4).zip(pixels.chunks_exact(4)) {
+                dst.copy_from_slice(&grayscale_pixel(pixel));
+            }
+        });
+    Ok(output)
+}
+
+/// Inverts the color values of a byte array like [`invert_colors`], splitting the buffer across the
+/// rayon thread pool.
+///
+/// Each chunk is inverted with `invert_colors`, so the SIMD path is still used inside every chunk.
+/// Buffers smaller than `parallel::MIN_PARALLEL_BYTES`, or environments without worker threads, are
+/// inverted on the calling thread.
+#[cfg(feature = "parallel")]
+pub fn invert_colors_parallel(bytes: &mut [u8]) {
+    if !parallel::should_parallelize(bytes.len(), parallel::MIN_PARALLEL_BYTES) {
+        invert_colors(bytes);
+        return;
+    }
+
+    bytes.par_chunks_mut(parallel::PARALLEL_CHUNK_BYTES).for_each(invert_colors);
 }
 
+/// Returns `Error::InvalidInput` unless `input` holds a whole number of RGBA pixels.
+fn check_rgba_len(input: &[u8]) -> Result<()> {
+    if !input.len().is_multiple_of(4) {
+        return Err(Error::InvalidInput(format!(
+            "RGBA buffer length {} is not a multiple of 4",
+            input.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Converts a single `[R, G, B, A]` pixel with the luminance formula used by [`grayscale`].
+fn grayscale_pixel(pixel: &[u8]) -> [u8; 4] {
+    let gray = (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) as u8;
+    [gray, gray, gray, pixel[3]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn invert_colors_matches_scalar_reference() {
+        // Lengths around the 16-byte SIMD lane width exercise both the vector loop and the tail
+        for len in [0, 1, 15, 16, 17, 31, 32, 33, 1000] {
+            let original: Vec<u8> = (0..len).map(|i| (i * 37 % 256) as u8).collect();
+
+            let mut dispatched = original.clone();
+            invert_colors(&mut dispatched);
+
+            let mut scalar = original.clone();
+            invert_colors_scalar(&mut scalar);
+
+            assert_eq!(dispatched, scalar, "length {}", len);
+            assert!(dispatched.iter().zip(&original).all(|(&a, &b)| a == 255 - b));
+        }
+    }
+
+    #[test]
+    fn invert_colors_is_an_involution() {
+        let original: Vec<u8> = (0..=255).collect();
+        let mut bytes = original.clone();
+        invert_colors(&mut bytes);
+        invert_colors(&mut bytes);
+        assert_eq!(bytes, original);
+    }
+
+    #[test]
+    fn grayscale_preserves_alpha() {
+        let input = [255, 0, 0, 10, 0, 255, 0, 20, 0, 0, 255, 30, 255, 255, 255, 40];
+        assert_eq!(
+            grayscale(&input).unwrap(),
+            vec![76, 76, 76, 10, 149, 149, 149, 20, 29, 29, 29, 30, 255, 255, 255, 40]
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_variants_match_sequential() {
+        let image: Vec<u8> = (0..parallel::MIN_PARALLEL_BYTES * 3 + 64).map(|i| (i * 31 % 251) as u8).collect();
+
+        assert_eq!(grayscale_parallel(&image).unwrap(), grayscale(&image).unwrap());
+
+        let mut parallel_inverted = image.clone();
+        invert_colors_parallel(&mut parallel_inverted);
+        let mut inverted = image.clone();
+        invert_colors(&mut inverted);
+        assert_eq!(parallel_inverted, inverted);
+
+        assert!(grayscale_parallel(&image[..7]).is_err());
+    }
+
+    #[test]
+    fn grayscale_rejects_truncated_pixels() {
+        let err = grayscale(&[255, 0, 0, 255, 10, 20, 30]).unwrap_err();
+        assert_eq!(err.code(), "INVALID_INPUT");
+    }
+}
@@ -1,16 +1,16 @@
-
-
-
-use wasm_bindgen::prelude::*;
 use csv::{ReaderBuilder, StringRecord};
 
 use serde_json;
-/// Parses a CSV string into a vector of JSON objects (JsValue).
+
+use crate::error::{Error, Result};
+use crate::progress::{checkpoint, NoProgress, Progress};
+
+/// Parses a CSV string into a vector of JSON strings.
 ///
-/// This function takes a string containing CSV data and converts each record into a JSON object. It processes
-/// the CSV content by reading it line by line, converting each record into a `JsValue` object, and then returning
-/// the collection of these objects as a `Result<Vec<JsValue>, JsValue>`. If any errors occur during the CSV parsing
-/// or JSON conversion, they are propagated as `JsValue` errors.
+/// This function takes a string containing CSV data and converts each record into a JSON string. It processes
+/// the CSV content by reading it line by line, converting each record into a JSON array string, and then returning
+/// the collection of these strings as a `Result<Vec<String>>`. If any errors occur during the CSV parsing
+/// or JSON conversion, they are propagated as `Error` values.
 ///
 /// The CSV format is expected to have rows of data separated by newlines, and each field in a row is separated
 /// by commas. Each row is converted into a JSON array, with each field of the row becoming an element in the array.
@@ -22,24 +22,20 @@ use serde_json;
 ///
 /// # Returns
 ///
-/// * `Result<Vec<JsValue>, JsValue>` - A result containing either:
-///     - `Ok(Vec<JsValue>)`: A vector of `JsValue` objects representing the parsed CSV records.
-///     - `Err(JsValue)`: A `JsValue` error, which contains a string representation of the error message.
+/// * `Result<Vec<String>>` - A result containing either:
+///     - `Ok(Vec<String>)`: A vector of JSON strings representing the parsed CSV records.
+///     - `Err(Error)`: An `Error::Csv` or `Error::Serialization` describing the failure.
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::json::parse_csv_to_json;
+///
 /// let csv_content = "name,age,city\nJohn,30,New York\nAlice,25,Los Angeles";
-/// let json_result = parse_csv_to_json(csv_content.to_string());
-/// match json_result {
-///     Ok(json_records) => {
-///         for record in json_records {
-///             println!("{}", record.as_string().unwrap());
-///         }
-///     }
-///     Err(e) => {
-///         console_error!("{}", e.as_string().unwrap());
-///     }
-/// }
+/// let json_records = parse_csv_to_json(csv_content).unwrap();
+/// assert_eq!(json_records, vec![
+///     r#"["John","30","New York"]"#,
+///     r#"["Alice","25","Los Angeles"]"#,
+/// ]);
 /// ```
 ///
 /// # Performance Considerations
@@ -49,36 +45,63 @@ use serde_json;
 /// in the CSV. The function also performs serialization into JSON format, which adds some overhead.
 ///
 /// - **Memory Usage**: The memory usage grows with the size of the CSV content and the number of rows and fields.
-///   Each row is converted into a JSON object, and the resulting `JsValue` objects are stored in a vector, which may
+///   Each row is converted into a JSON string, and the resulting strings are stored in a vector, which may
 ///   be memory-intensive for large CSV files.
-#[wasm_bindgen]
-pub fn parse_csv_to_json(content: String) -> Result<Vec<JsValue>, JsValue> {
+pub fn parse_csv_to_json(content: &str) -> Result<Vec<String>> {
+    parse_csv_to_json_with_progress(content, &mut NoProgress)
+}
+
+/// Parses a CSV string like [`parse_csv_to_json`], reporting progress and allowing cancellation.
+///
+/// Every `RECORDS_PER_UPDATE` records (and once at the end) `progress` receives the fraction of
+/// `content` consumed so far and the number of records converted. If it returns `false` the parse
+/// stops and `Error::Cancelled` is returned.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::json::parse_csv_to_json_with_progress;
+///
+/// let csv_content = "name,age\nJohn,30\nAlice,25";
+/// let mut last = (0.0, 0);
+/// let records = parse_csv_to_json_with_progress(csv_content, &mut |fraction, processed| {
+///     last = (fraction, processed);
+///     true
+/// })
+/// .unwrap();
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(last, (1.0, 2));
+/// ```
+pub fn parse_csv_to_json_with_progress(content: &str, progress: &mut dyn Progress) -> Result<Vec<String>> {
+    const RECORDS_PER_UPDATE: usize = 1024;
+
     // Create a CSV reader from the content string
     let mut rdr = ReaderBuilder::new().from_reader(content.as_bytes());
+    let total = content.len().max(1) as f64;
 
     // Create a vector to store the JSON objects
-    let mut records: Vec<JsValue> = Vec::new();
+    let mut records: Vec<String> = Vec::new();
+    let mut record = StringRecord::new();
 
     // Process the records
-    for result in rdr.records() {
-        let record = result.map_err(|e| JsValue::from_str(&e.to_string()))?;
-        
-        // Convert the record to a JSON object (JsValue)
-        let json = record_to_json(&record)?;
-        
-        // Add the JSON object to the results
-        records.push(json);
+    while rdr.read_record(&mut record).map_err(|e| Error::Csv(e.to_string()))? {
+        // Convert the record to a JSON string and add it to the results
+        records.push(record_to_json(&record)?);
+
+        if records.len().is_multiple_of(RECORDS_PER_UPDATE) {
+            checkpoint(progress, rdr.position().byte() as f64 / total, records.len())?;
+        }
     }
+    checkpoint(progress, 1.0, records.len())?;
 
-    // Return the vector of records as JsValue
+    // Return the vector of records as JSON strings
     Ok(records)
 }
 
-/// Converts a single CSV record to a JSON object (JsValue).
+/// Converts a single CSV record to a JSON string.
 ///
 /// This helper function takes a CSV record represented as a `StringRecord`, converts each field in the record
-/// into a string, and then serializes the record as a JSON array. The resulting JSON array is returned as a `JsValue`.
-/// The function returns an error as `JsValue` if the conversion or serialization fails.
+/// into a string, and then serializes the record as a JSON array. The resulting JSON array is returned as a `String`.
+/// The function returns `Error::Serialization` if the conversion or serialization fails.
 ///
 /// # Arguments
 ///
@@ -87,18 +110,15 @@ pub fn parse_csv_to_json(content: String) -> Result<Vec<JsValue>, JsValue> {
 ///
 /// # Returns
 ///
-/// * `Result<JsValue, JsValue>` - A result containing either:
-///     - `Ok(JsValue)`: The serialized JSON representation of the CSV record as a `JsValue`.
-///     - `Err(JsValue)`: A `JsValue` error if serialization fails.
+/// * `Result<String>` - A result containing either:
+///     - `Ok(String)`: The serialized JSON representation of the CSV record.
+///     - `Err(Error)`: An `Error::Serialization` if serialization fails.
 ///
 /// # Example
-/// ```rust
+/// ```rust,ignore
 /// let record = StringRecord::from(vec!["John", "30", "New York"]);
-/// let json_result = record_to_json(&record);
-/// match json_result {
-///     Ok(json) => println!("{}", json.as_string().unwrap()),
-///     Err(e) => console_error!("{}", e.as_string().unwrap()),
-/// }
+/// let json = record_to_json(&record)?;
+/// assert_eq!(json, r#"["John","30","New York"]"#);
 /// ```
 ///
 /// # Performance Considerations
@@ -108,14 +128,42 @@ pub fn parse_csv_to_json(content: String) -> Result<Vec<JsValue>, JsValue> {
 /// usage is proportional to the number of fields in the record since each field is copied into a vector and serialized.
 /// 
 /// The function may incur additional overhead due to serialization, particularly for large records or complex data.
-fn record_to_json(record: &StringRecord) -> Result<JsValue, JsValue> {
+fn record_to_json(record: &StringRecord) -> Result<String> {
     // Convert the record to a vector of strings
     let values: Vec<String> = record.iter().map(|s| s.to_string()).collect();
     
     // Convert the vector to a JSON value
     let json = serde_json::to_string(&values)
-        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        .map_err(|e| Error::Serialization(e.to_string()))?;
     
-    // Return the JSON object as JsValue
-    Ok(JsValue::from_str(&json))
+    // Return the JSON string
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn progress_reports_records_and_cancels() {
+        let content: String = std::iter::once("id,value\n".to_string())
+            .chain((0..3000).map(|i| format!("{},{}\n", i, i * 2)))
+            .collect();
+
+        let mut updates = Vec::new();
+        let records = parse_csv_to_json_with_progress(&content, &mut |fraction, processed| {
+            updates.push((fraction, processed));
+            true
+        })
+        .unwrap();
+        assert_eq!(records, parse_csv_to_json(&content).unwrap());
+        assert_eq!(updates.iter().map(|u| u.1).collect::<Vec<_>>(), vec![1024, 2048, 3000]);
+        assert!(updates.windows(2).all(|w| w[0].0 < w[1].0));
+
+        let cancelled = parse_csv_to_json_with_progress(&content, &mut |_, _| false);
+        assert_eq!(cancelled.unwrap_err().code(), "CANCELLED");
+    }
 }
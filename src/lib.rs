@@ -1,18 +1,33 @@
 // #![feature(stdsimd)]
 
+#[cfg(feature = "array")]
 pub mod array;
+#[cfg(feature = "images")]
 pub mod images;
+#[cfg(feature = "math")]
 pub mod math;
+#[cfg(feature = "crypto")]
 pub mod crypto;
+#[cfg(feature = "byte")]
 pub mod byte;
+#[cfg(feature = "json")]
 pub mod json;
+#[cfg(feature = "string")]
 pub mod string;
 
-use wasm_bindgen::prelude::*;
+pub mod error;
+pub mod progress;
 
-use console_error_panic_hook::set_once;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 
-#[wasm_bindgen(start)]
-pub fn main() {
-    set_once(); // Set panic hook for better error messages
-}
\ No newline at end of file
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "python")]
+mod python;
+
+pub use error::{Error, Result};
@@ -0,0 +1,230 @@
+//! `rust-scripts` command-line tool.
+//!
+//! Runs the same library code as the WebAssembly build so that results computed in the
+//! browser can be reproduced in a shell. Every subcommand reads its input from a file (or
+//! stdin when the path is omitted or `-`) and writes the result to stdout (or `--output`).
+//!
+//! Large inputs are processed with the `*_parallel` variants, which produce the same output
+//! as the single-threaded functions used by the default wasm build.
+//!
+//! ```sh
+//! rust-scripts hash report.pdf
+//! cat data.bin | rust-scripts compress --format deflate > data.deflate
+//! rust-scripts csv2json users.csv
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use rust_scripts::{array, crypto, images, json, string};
+
+#[derive(Parser)]
+#[command(name = "rust-scripts", version, about = "Run the rust-scripts library operations from the shell")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Common input/output options shared by every subcommand.
+#[derive(clap::Args)]
+struct Io {
+    /// Input file. Reads stdin when omitted or `-`.
+    input: Option<PathBuf>,
+
+    /// Output file. Writes stdout when omitted.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the SHA-256 digest as lowercase hex (`crypto::sha256_hash`).
+    Hash {
+        #[command(flatten)]
+        io: Io,
+    },
+    /// Print the CRC32 checksum as a decimal number (`crypto::calculate_crc32`).
+    Crc32 {
+        #[command(flatten)]
+        io: Io,
+        /// Print the checksum as 8 hex digits instead.
+        #[arg(long)]
+        hex: bool,
+    },
+    /// Compress bytes (`crypto::compress_data` or `crypto::deflate_compress`).
+    Compress {
+        #[command(flatten)]
+        io: Io,
+        #[arg(short, long, value_enum, default_value_t = Format::Lz4)]
+        format: Format,
+    },
+    /// Decompress bytes (`crypto::decompress_data` or `crypto::deflate_decompress`).
+    Decompress {
+        #[command(flatten)]
+        io: Io,
+        #[arg(short, long, value_enum, default_value_t = Format::Lz4)]
+        format: Format,
+    },
+    /// Convert CSV to one JSON array per record (`json::parse_csv_to_json`).
+    Csv2json {
+        #[command(flatten)]
+        io: Io,
+    },
+    /// Convert raw RGBA bytes (canvas `ImageData` layout) to grayscale (`images::grayscale`).
+    Grayscale {
+        #[command(flatten)]
+        io: Io,
+    },
+    /// Invert every byte of a raw RGBA buffer (`images::invert_colors`).
+    Invert {
+        #[command(flatten)]
+        io: Io,
+    },
+    /// Sort whitespace or comma separated integers, one result per line.
+    Sort {
+        #[command(flatten)]
+        io: Io,
+        #[arg(short, long, value_enum, default_value_t = Algorithm::Merge)]
+        algorithm: Algorithm,
+    },
+    /// Count word occurrences and print them as a JSON object (`string::word_frequency`).
+    Wordfreq {
+        #[command(flatten)]
+        io: Io,
+    },
+}
+
+/// Compression format, matching the pairs of functions in `crypto`.
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// LZ4 with the original size prepended, as produced by `compress_data`.
+    Lz4,
+    /// Raw Deflate, as produced by `deflate_compress`.
+    Deflate,
+}
+
+/// Sorting algorithm from `array`.
+#[derive(Clone, Copy, ValueEnum)]
+enum Algorithm {
+    Quick,
+    Merge,
+}
+
+type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> CliResult<()> {
+    match command {
+        Command::Hash { io } => {
+            let digest = crypto::sha256_hash(&read_input(&io)?);
+            let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+            write_output(&io, format!("{}\n", hex).as_bytes())
+        }
+        Command::Crc32 { io, hex } => {
+            let checksum = crypto::calculate_crc32(&read_input(&io)?);
+            let line = if hex { format!("{:08x}\n", checksum) } else { format!("{}\n", checksum) };
+            write_output(&io, line.as_bytes())
+        }
+        Command::Compress { io, format } => {
+            let data = read_input(&io)?;
+            let compressed = match format {
+                Format::Lz4 => crypto::compress_data(&data),
+                Format::Deflate => crypto::deflate_compress(&data),
+            };
+            write_output(&io, &compressed)
+        }
+        Command::Decompress { io, format } => {
+            let data = read_input(&io)?;
+            let decompressed = match format {
+                Format::Lz4 => crypto::decompress_data(&data)?,
+                Format::Deflate => crypto::deflate_decompress(&data)?,
+            };
+            write_output(&io, &decompressed)
+        }
+        Command::Csv2json { io } => {
+            let content = String::from_utf8(read_input(&io)?)?;
+            let mut out = String::new();
+            for record in json::parse_csv_to_json(&content)? {
+                out.push_str(&record);
+                out.push('\n');
+            }
+            write_output(&io, out.as_bytes())
+        }
+        Command::Grayscale { io } => {
+            let gray = images::grayscale_parallel(&read_input(&io)?)?;
+            write_output(&io, &gray)
+        }
+        Command::Invert { io } => {
+            let mut bytes = read_input(&io)?;
+            images::invert_colors_parallel(&mut bytes);
+            write_output(&io, &bytes)
+        }
+        Command::Sort { io, algorithm } => {
+            let text = String::from_utf8(read_input(&io)?)?;
+            let numbers = text
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|token| !token.is_empty())
+                .map(|token| token.parse::<i32>().map_err(|e| format!("invalid integer {:?}: {}", token, e)))
+                .collect::<Result<Vec<i32>, String>>()?;
+            let sorted = match algorithm {
+                Algorithm::Quick => array::quick_sort(&numbers),
+                Algorithm::Merge => array::merge_sort_parallel(&numbers),
+            };
+            let mut out = String::new();
+            for n in sorted {
+                out.push_str(&n.to_string());
+                out.push('\n');
+            }
+            write_output(&io, out.as_bytes())
+        }
+        Command::Wordfreq { io } => {
+            let text = String::from_utf8(read_input(&io)?)?;
+            // BTreeMap keeps the keys sorted so the output is reproducible
+            let freq: BTreeMap<_, _> = string::word_frequency_parallel(&text).into_iter().collect();
+            let mut out = serde_json::to_string(&freq)?;
+            out.push('\n');
+            write_output(&io, out.as_bytes())
+        }
+    }
+}
+
+/// Reads the whole input file, or stdin when no path (or `-`) was given.
+fn read_input(io: &Io) -> CliResult<Vec<u8>> {
+    match &io.input {
+        Some(path) if path.as_os_str() != "-" => Ok(fs::read(path)?),
+        _ => {
+            let mut buf = Vec::new();
+            io::stdin().read_to_end(&mut buf)?;
+            Ok(buf)
+        }
+    }
+}
+
+/// Writes the result to `--output`, or stdout when it was not given.
+fn write_output(io: &Io, data: &[u8]) -> CliResult<()> {
+    match &io.output {
+        Some(path) => fs::write(path, data)?,
+        None => {
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(data)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
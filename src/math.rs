@@ -1,5 +1,6 @@
-use wasm_bindgen::prelude::*;
+use std::iter::Sum;
 
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
 use std::arch::wasm32::*;
 
 /// Computes the mean of an array of f64 values.
@@ -10,19 +11,28 @@ use std::arch::wasm32::*;
 /// # Returns
 /// - `f64`: The mean of the values in `data`. If the slice is empty, returns 0.0.
 ///
-/// # Safety
-/// Uses `unsafe` for bounds-less access in SIMD operations. Assumes the caller
-/// guarantees that the input is a valid slice.
-///
-/// # Requirements
-/// SIMD support for WebAssembly (`target-feature=+simd128`).
-#[wasm_bindgen]
+/// # Portability
+/// On `wasm32` targets compiled with `target-feature=+simd128` the sum is computed with
+/// WebAssembly SIMD; every other target uses `sum_f64_scalar`. Both paths add the values
+/// pairwise in the same order, so the result is bit-identical across targets.
 pub fn calculate_mean(data: &[f64]) -> f64 {
     let len = data.len();
     if len == 0 {
         return 0.0;
     }
 
+    // Calculate the mean
+    sum_f64(data) / len as f64
+}
+
+/// Sums a slice of f64 values two lanes at a time using WebAssembly SIMD.
+///
+/// # Safety
+/// Uses `unsafe` for bounds-less access in SIMD operations. The loop guarantees that
+/// `i + 2 <= len` before each load.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+fn sum_f64(data: &[f64]) -> f64 {
+    let len = data.len();
     let mut sum = 0.0;
     let mut i = 0;
 
@@ -39,27 +49,54 @@ pub fn calculate_mean(data: &[f64]) -> f64 {
     }
 
     // Sum remaining elements if any
-    for j in i..len {
-        sum += data[j];
+    for value in &data[i..] {
+        sum += value;
     }
 
-    // Calculate the mean
-    sum / len as f64
+    sum
+}
+
+/// Portable fallback for targets without WebAssembly SIMD.
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+fn sum_f64(data: &[f64]) -> f64 {
+    sum_f64_scalar(data)
+}
+
+/// Sums a slice of f64 values pair by pair, mirroring the lane order of the SIMD path.
+///
+/// Each pair `(data[i], data[i + 1])` is added first and the partial sum is then
+/// accumulated, exactly as the `f64x2` implementation does, so that both paths round
+/// identically.
+#[cfg_attr(all(target_arch = "wasm32", target_feature = "simd128"), allow(dead_code))]
+fn sum_f64_scalar(data: &[f64]) -> f64 {
+    let mut pairs = data.chunks_exact(2);
+    let mut sum = 0.0;
+
+    for pair in &mut pairs {
+        sum += pair[0] + pair[1];
+    }
+
+    // Sum the trailing element if the length is odd
+    for value in pairs.remainder() {
+        sum += value;
+    }
+
+    sum
 }
 
 
-/// Computes the sum of a vector of integers.
+/// Computes the sum of a slice of numbers.
 ///
-/// This function takes a vector of `i32` integers and returns their sum. It utilizes
+/// This function takes a slice of numbers (e.g. `i32`) and returns their sum. It utilizes
 /// Rust's iterator methods to sum the elements efficiently.
 ///
 /// # Arguments
 ///
-/// * `numbers` - A `Vec<i32>` containing the integers to be summed.
+/// * `numbers` - A slice (`&[T]`) containing the numbers to be summed.
 ///
 /// # Returns
 ///
-/// * `i32` - The sum of the integers in the input vector.
+/// * `T` - The sum of the numbers in the input slice.
 ///
 /// # Performance Considerations
 ///
@@ -75,13 +112,14 @@ pub fn calculate_mean(data: &[f64]) -> f64 {
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::math::sum;
+///
 /// let numbers = vec![1, 2, 3, 4, 5];
-/// let result = sum(numbers);
+/// let result = sum(&numbers);
 /// assert_eq!(result, 15);
 /// ```
-#[wasm_bindgen]
-pub fn sum(numbers: Vec<i32>) -> i32 {
-    numbers.iter().sum()
+pub fn sum<T: Copy + Sum<T>>(numbers: &[T]) -> T {
+    numbers.iter().copied().sum()
 }
 
 /// Computes the factorial of a non-negative integer `n`.
@@ -97,7 +135,7 @@ pub fn sum(numbers: Vec<i32>) -> i32 {
 /// # Returns
 ///
 /// * `u32` - The factorial of the input integer `n`. The factorial of `n` is the product:
-///   ```
+///   ```text
 ///   n! = n * (n-1) * (n-2) * ... * 1
 ///   ```
 ///   For example, `5! = 5 * 4 * 3 * 2 * 1 = 120`.
@@ -118,21 +156,61 @@ pub fn sum(numbers: Vec<i32>) -> i32 {
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::math::factorial;
+///
 /// let result = factorial(5);
 /// assert_eq!(result, 120);  // 5! = 120
 /// ```
-#[wasm_bindgen]
 pub fn factorial(n: u32) -> u32 {
     (1..=n).product()
 }
 
 
-#[wasm_bindgen]
-pub fn max(arr: &[i32]) -> i32 {
-    arr.iter().copied().max().unwrap_or(-1) // Return -1 if the array is empty
+/// Returns the largest element of a slice, or `None` if the slice is empty.
+pub fn max<T: Ord + Copy>(arr: &[T]) -> Option<T> {
+    arr.iter().copied().max()
 }
 
-#[wasm_bindgen]
-pub fn min(arr: &[i32]) -> i32 {
-    arr.iter().copied().min().unwrap_or(-1) // Return -1 if the array is empty
-}
\ No newline at end of file
+/// Returns the smallest element of a slice, or `None` if the slice is empty.
+pub fn min<T: Ord + Copy>(arr: &[T]) -> Option<T> {
+    arr.iter().copied().min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn calculate_mean_of_empty_slice_is_zero() {
+        assert_eq!(calculate_mean(&[]), 0.0);
+    }
+
+    #[test]
+    fn calculate_mean_matches_scalar_reference_bitwise() {
+        // Values chosen so that a different summation order would round differently
+        let data: Vec<f64> = (0..101).map(|i| 1.0 / (i as f64 + 0.3) * 1e10_f64.powi(i % 3)).collect();
+
+        for len in [1, 2, 3, 4, 5, 100, 101] {
+            let slice = &data[..len];
+            let expected = sum_f64_scalar(slice) / len as f64;
+            assert_eq!(calculate_mean(slice).to_bits(), expected.to_bits(), "length {}", len);
+        }
+    }
+
+    #[test]
+    fn calculate_mean_simple_values() {
+        assert_eq!(calculate_mean(&[1.0, 2.0, 3.0, 4.0, 5.0]), 3.0);
+    }
+
+    #[test]
+    fn integer_helpers() {
+        assert_eq!(sum(&[1, 2, 3, 4, 5]), 15);
+        assert_eq!(factorial(5), 120);
+        assert_eq!(max(&[3, 9, -2]), Some(9));
+        assert_eq!(min(&[3, 9, -2]), Some(-2));
+        assert_eq!(max::<i32>(&[]), None);
+    }
+}
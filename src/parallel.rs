@@ -0,0 +1,54 @@
+//! Shared helpers for the `*_parallel` functions.
+//!
+//! The parallel variants (`array::merge_sort_parallel`, `images::grayscale_parallel`,
+//! `images::invert_colors_parallel`, `crypto::sha256_hash_many` and
+//! `string::word_frequency_parallel`) run on the global rayon thread pool. Each one first
+//! asks [`should_parallelize`] whether splitting the work is worthwhile; if not, it falls
+//! back to the single-threaded implementation, so callers never have to choose.
+//!
+//! Natively rayon starts one worker per CPU on first use. In the browser the pool only
+//! exists after `init_thread_pool` (from the `wasm-threads` build) has resolved; until then,
+//! and in regular single-threaded wasm builds, everything runs on the calling thread.
+
+#[cfg(target_arch = "wasm32")]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Below this many elements (sort) a parallel split costs more than it saves.
+pub const MIN_PARALLEL_ELEMENTS: usize = 1 << 14;
+
+/// Below this many bytes (images, hashing, text) a parallel split costs more than it saves.
+pub const MIN_PARALLEL_BYTES: usize = 1 << 16;
+
+/// Size of the chunks byte buffers are split into. A multiple of 4 so that RGBA pixels are
+/// never split across chunks.
+pub const PARALLEL_CHUNK_BYTES: usize = 1 << 15;
+
+/// Set once the browser worker pool is running (see `wasm::init_thread_pool`).
+#[cfg(target_arch = "wasm32")]
+static POOL_READY: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` if more than one thread can run rayon jobs.
+///
+/// On wasm32 this never touches rayon's global registry before the worker pool exists,
+/// because initializing it early would make a later `init_thread_pool` fail.
+pub fn threads_available() -> bool {
+    #[cfg(target_arch = "wasm32")]
+    {
+        POOL_READY.load(Ordering::Acquire) && rayon::current_num_threads() > 1
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        rayon::current_num_threads() > 1
+    }
+}
+
+/// Returns `true` if an input of `len` units should be processed in parallel.
+pub fn should_parallelize(len: usize, min_len: usize) -> bool {
+    len >= min_len && threads_available()
+}
+
+/// Records that the browser worker pool has been started.
+#[cfg(all(target_arch = "wasm32", feature = "wasm-threads"))]
+pub(crate) fn mark_pool_ready() {
+    POOL_READY.store(true, Ordering::Release);
+}
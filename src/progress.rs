@@ -0,0 +1,79 @@
+//! Progress reporting and cancellation for long-running operations.
+//!
+//! The `*_with_progress` functions (`json::parse_csv_to_json_with_progress`,
+//! `crypto::deflate_compress_with_progress`, `crypto::deflate_decompress_with_progress` and
+//! `array::merge_sort_with_progress`) periodically hand a [`Progress`] the fraction of work done
+//! and the number of items processed so far. Returning `false` stops the operation, which then
+//! fails with [`Error::Cancelled`]; partial results are discarded.
+//!
+//! Any `FnMut(f64, usize) -> bool` closure is a `Progress`, so a cancellation flag shared with
+//! another thread is just a captured `AtomicBool`:
+//!
+//! ```rust
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//! use rust_scripts::progress::Progress;
+//!
+//! let cancel = AtomicBool::new(false);
+//! let mut progress = |_fraction: f64, _processed: usize| !cancel.load(Ordering::Relaxed);
+//! assert!(progress.update(0.5, 1000));
+//!
+//! cancel.store(true, Ordering::Relaxed);
+//! assert!(!progress.update(0.75, 1500));
+//! ```
+
+use crate::error::{Error, Result};
+
+/// Receives progress updates from a long-running operation.
+pub trait Progress {
+    /// Called periodically with the fraction of work done (`0.0..=1.0`) and the number of items
+    /// (records, bytes or elements, depending on the operation) processed so far.
+    ///
+    /// Returns `false` to cancel the operation.
+    fn update(&mut self, fraction: f64, processed: usize) -> bool;
+}
+
+impl<F: FnMut(f64, usize) -> bool> Progress for F {
+    fn update(&mut self, fraction: f64, processed: usize) -> bool {
+        self(fraction, processed)
+    }
+}
+
+/// A [`Progress`] that ignores updates and never cancels, used by the plain variants.
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+pub(crate) struct NoProgress;
+
+impl Progress for NoProgress {
+    fn update(&mut self, _fraction: f64, _processed: usize) -> bool {
+        true
+    }
+}
+
+/// Reports an update and turns a cancellation into [`Error::Cancelled`].
+#[cfg_attr(not(any(feature = "array", feature = "crypto", feature = "json")), allow(dead_code))]
+pub(crate) fn checkpoint(progress: &mut dyn Progress, fraction: f64, processed: usize) -> Result<()> {
+    if progress.update(fraction.clamp(0.0, 1.0), processed) {
+        Ok(())
+    } else {
+        Err(Error::Cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn checkpoint_clamps_and_cancels() {
+        let mut seen = Vec::new();
+        let mut record = |fraction: f64, processed: usize| {
+            seen.push((fraction, processed));
+            processed < 10
+        };
+        assert_eq!(checkpoint(&mut record, 1.5, 3), Ok(()));
+        assert_eq!(checkpoint(&mut record, 0.5, 10), Err(Error::Cancelled));
+        assert_eq!(seen, vec![(1.0, 3), (0.5, 10)]);
+    }
+}
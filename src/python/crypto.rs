@@ -0,0 +1,34 @@
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use super::with_bytes;
+use crate::crypto;
+
+/// Computes the SHA-256 digest of `data` (32 `bytes`). See [`crypto::sha256_hash`].
+#[pyfunction]
+pub fn sha256_hash<'py>(py: Python<'py>, data: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+    let digest = with_bytes(data, crypto::sha256_hash)?;
+    Ok(PyBytes::new(py, &digest))
+}
+
+/// Computes the CRC32 checksum of `data`. See [`crypto::calculate_crc32`].
+#[pyfunction]
+pub fn calculate_crc32(data: &Bound<'_, PyAny>) -> PyResult<u32> {
+    with_bytes(data, crypto::calculate_crc32)
+}
+
+/// LZ4-compresses `data` with the original size prepended. See [`crypto::compress_data`].
+#[pyfunction]
+pub fn compress_data<'py>(py: Python<'py>, data: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+    let compressed = with_bytes(data, crypto::compress_data)?;
+    Ok(PyBytes::new(py, &compressed))
+}
+
+/// Decompresses the output of `compress_data`. See [`crypto::decompress_data`].
+///
+/// Raises `rust_scripts.Error` with code `DECOMPRESSION_FAILED` on invalid input.
+#[pyfunction]
+pub fn decompress_data<'py>(py: Python<'py>, data: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+    let decompressed = with_bytes(data, crypto::decompress_data)??;
+    Ok(PyBytes::new(py, &decompressed))
+}
@@ -0,0 +1,31 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::error;
+
+create_exception!(
+    rust_scripts,
+    Error,
+    PyValueError,
+    "Raised when a library function fails. The `code` attribute holds the stable error code, e.g. `\"DECOMPRESSION_FAILED\"`."
+);
+
+/// Converts a library error into a `rust_scripts.Error` exception.
+///
+/// ```python
+/// try:
+///     rust_scripts.decompress_data(b"\x01\x02\x03")
+/// except rust_scripts.Error as e:
+///     print(e.code, e)
+/// ```
+impl From<error::Error> for PyErr {
+    fn from(error: error::Error) -> PyErr {
+        Python::attach(|py| {
+            let err = Error::new_err(error.to_string());
+            // Setting an attribute on a freshly created exception cannot fail
+            let _ = err.value(py).setattr("code", error.code());
+            err
+        })
+    }
+}
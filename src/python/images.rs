@@ -0,0 +1,26 @@
+use pyo3::prelude::*;
+
+use super::{bytes_like, with_bytes};
+use crate::images;
+
+/// Converts RGBA pixels to grayscale. See [`images::grayscale`].
+///
+/// Accepts `bytes` or a numpy `uint8` array (e.g. shape `(height, width, 4)`) and returns the
+/// same kind of object with the same shape. Raises `rust_scripts.Error` with code
+/// `INVALID_INPUT` if the length is not a multiple of 4.
+#[pyfunction]
+pub fn grayscale<'py>(pixels: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let gray = with_bytes(pixels, images::grayscale)??;
+    bytes_like(pixels, gray)
+}
+
+/// Inverts every byte of an RGBA buffer. See [`images::invert_colors`].
+///
+/// Accepts `bytes` or a numpy `uint8` array and returns the same kind of object with the same
+/// shape; the input is not modified.
+#[pyfunction]
+pub fn invert_colors<'py>(pixels: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let mut inverted = with_bytes(pixels, <[u8]>::to_vec)?;
+    images::invert_colors(&mut inverted);
+    bytes_like(pixels, inverted)
+}
@@ -0,0 +1,11 @@
+use pyo3::prelude::*;
+
+use crate::json;
+
+/// Parses CSV text into a list of JSON strings, one per record. See [`json::parse_csv_to_json`].
+///
+/// Raises `rust_scripts.Error` with code `CSV_PARSE_FAILED` or `SERIALIZATION_FAILED`.
+#[pyfunction]
+pub fn parse_csv_to_json(content: &str) -> PyResult<Vec<String>> {
+    Ok(json::parse_csv_to_json(content)?)
+}
@@ -0,0 +1,75 @@
+//! Python bindings (PyO3).
+//!
+//! Every function in these modules is a thin `#[pyfunction]` wrapper over the native API of the
+//! module with the same name (e.g. `python::crypto::sha256_hash` wraps `crypto::sha256_hash`), so
+//! Python code gets exactly the same results as the browser. Byte inputs may be `bytes`,
+//! `bytearray` or contiguous numpy `uint8` arrays; failures raise `rust_scripts.Error` (a
+//! `ValueError` subclass) with the stable `code` attribute (see `error.rs`).
+//!
+//! The extension is built into a wheel with maturin (configuration in `pyproject.toml`):
+//!
+//! ```text
+//! maturin build --release
+//! pip install target/wheels/rust_scripts-*.whl
+//! ```
+
+mod crypto;
+mod error;
+mod images;
+mod json;
+
+use numpy::{PyReadonlyArrayDyn, PyUntypedArrayMethods};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// The `rust_scripts` Python module.
+#[pymodule]
+fn rust_scripts(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("Error", m.py().get_type::<error::Error>())?;
+
+    m.add_function(wrap_pyfunction!(crypto::sha256_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(crypto::calculate_crc32, m)?)?;
+    m.add_function(wrap_pyfunction!(crypto::compress_data, m)?)?;
+    m.add_function(wrap_pyfunction!(crypto::decompress_data, m)?)?;
+    m.add_function(wrap_pyfunction!(json::parse_csv_to_json, m)?)?;
+    m.add_function(wrap_pyfunction!(images::grayscale, m)?)?;
+    m.add_function(wrap_pyfunction!(images::invert_colors, m)?)?;
+    Ok(())
+}
+
+/// Calls `f` with the bytes of `data`: `bytes` are borrowed, contiguous numpy `uint8` arrays are
+/// borrowed as their flat buffer, and anything else convertible to a byte sequence (`bytearray`,
+/// `memoryview`, lists of ints) is copied first.
+fn with_bytes<R>(data: &Bound<'_, PyAny>, f: impl FnOnce(&[u8]) -> R) -> PyResult<R> {
+    if let Ok(bytes) = data.cast::<PyBytes>() {
+        return Ok(f(bytes.as_bytes()));
+    }
+    if let Some(array) = numpy_bytes(data) {
+        return Ok(f(array.as_slice()?));
+    }
+    let bytes: Vec<u8> = data.extract()?;
+    Ok(f(&bytes))
+}
+
+/// Returns `obj` as a numpy `uint8` array, or `None` if it is anything else.
+///
+/// numpy is an optional dependency: rust-numpy panics if the numpy module cannot be imported,
+/// so the array conversion is only attempted on objects whose type comes from numpy.
+fn numpy_bytes<'py>(obj: &Bound<'py, PyAny>) -> Option<PyReadonlyArrayDyn<'py, u8>> {
+    let module = obj.get_type().module().ok()?;
+    if module.to_str().ok()? != "numpy" {
+        return None;
+    }
+    obj.extract().ok()
+}
+
+/// Returns `bytes` shaped like `like`: a numpy array of the same shape if `like` is a numpy
+/// array, otherwise a Python `bytes` object.
+fn bytes_like<'py>(like: &Bound<'py, PyAny>, bytes: Vec<u8>) -> PyResult<Bound<'py, PyAny>> {
+    let py = like.py();
+    if let Some(array) = numpy_bytes(like) {
+        let flat = numpy::PyArray1::from_vec(py, bytes);
+        return Ok(numpy::PyArrayMethods::reshape(&flat, array.shape())?.into_any());
+    }
+    Ok(PyBytes::new(py, &bytes).into_any())
+}
@@ -1,16 +1,17 @@
-use wasm_bindgen::prelude::*;
 use std::collections::HashMap;
 
-use serde_wasm_bindgen::to_value;
-/// Calculates the frequency of each word in a given text and returns the result as a JavaScript object.
+#[cfg(feature = "parallel")]
+use crate::parallel;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Calculates the frequency of each word in a given text.
 ///
 /// This function takes a string of text and counts the occurrences of each unique word. It splits the input
 /// text into words by whitespace and then counts how many times each word appears. The result is returned as a
-/// JavaScript object (a `JsValue`), which contains the word frequencies in the form of key-value pairs where the
-/// key is the word (a string) and the value is the frequency (an integer).
+/// `HashMap` where the key is the word (a string slice borrowed from `text`) and the value is the frequency.
 ///
-/// The function utilizes `serde_wasm_bindgen::to_value` to serialize the Rust `HashMap` of word frequencies into
-/// a format that can be used in JavaScript.
+/// The WebAssembly export serializes this map into a plain JavaScript object.
 ///
 /// # Arguments
 ///
@@ -18,14 +19,17 @@ use serde_wasm_bindgen::to_value;
 ///
 /// # Returns
 ///
-/// * `JsValue` - A JavaScript object representing the word frequencies. Each key in the object is a word (string),
-///   and each value is the frequency (integer) of that word in the text.
+/// * `HashMap<&str, u32>` - The word frequencies. Each key is a word and each value is the number of
+///   times that word appears in the text.
 ///
 /// # Example
 /// ```rust
+/// use rust_scripts::string::word_frequency;
+///
 /// let input_text = "hello world hello";
 /// let word_freq = word_frequency(input_text);
-/// console.log(word_freq); // Outputs: {hello: 2, world: 1}
+/// assert_eq!(word_freq["hello"], 2);
+/// assert_eq!(word_freq["world"], 1);
 /// ```
 ///
 /// # Performance Considerations
@@ -39,15 +43,73 @@ use serde_wasm_bindgen::to_value;
 ///
 /// - **HashMap Operations**: Inserting or updating a word in the `HashMap` takes O(1) on average, making the
 ///   word frequency counting efficient.
-#[wasm_bindgen]
-pub fn word_frequency(text: &str) -> JsValue {
+pub fn word_frequency(text: &str) -> HashMap<&str, u32> {
     // Count word frequencies
     let mut freq = HashMap::new();
     for word in text.split_whitespace() {
         *freq.entry(word).or_insert(0) += 1;
     }
 
-    // Serialize the HashMap to a JsValue using serde_wasm_bindgen::to_value
-    to_value(&freq).unwrap()
+    freq
+}
+
+/// Calculates the frequency of each word like [`word_frequency`], counting in parallel.
+///
+/// The text is split on whitespace by rayon; every worker counts its share of the words into a local
+/// `HashMap`, and the partial maps are then merged. The counts are identical to `word_frequency`.
+/// Texts shorter than `parallel::MIN_PARALLEL_BYTES`, or environments without worker threads, use
+/// `word_frequency` directly.
+///
+/// # Example
+/// ```rust
+/// use rust_scripts::string::word_frequency_parallel;
+///
+/// let text = "hello world hello ".repeat(10_000);
+/// let word_freq = word_frequency_parallel(&text);
+/// assert_eq!(word_freq["hello"], 20_000);
+/// ```
+#[cfg(feature = "parallel")]
+pub fn word_frequency_parallel(text: &str) -> HashMap<&str, u32> {
+    if !parallel::should_parallelize(text.len(), parallel::MIN_PARALLEL_BYTES) {
+        return word_frequency(text);
+    }
+
+    text.par_split_whitespace()
+        .fold(HashMap::new, |mut freq, word| {
+            *freq.entry(word).or_insert(0) += 1;
+            freq
+        })
+        .reduce(HashMap::new, |mut left, mut right| {
+            // Merge the smaller map into the larger one
+            if left.len() < right.len() {
+                std::mem::swap(&mut left, &mut right);
+            }
+            for (word, count) in right {
+                *left.entry(word).or_insert(0) += count;
+            }
+            left
+        })
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn word_frequency_counts_words() {
+        let freq = word_frequency("the cat  and\tthe\nhat");
+        assert_eq!(freq["the"], 2);
+        assert_eq!(freq["cat"], 1);
+        assert_eq!(freq.len(), 4);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn word_frequency_parallel_matches_sequential() {
+        let text: String = (0..50_000).map(|i| format!("w{} ", i % 97)).collect();
+        assert_eq!(word_frequency_parallel(&text), word_frequency(&text));
+    }
+}
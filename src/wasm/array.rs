@@ -0,0 +1,1616 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use js_sys::{Array, Float32Array, Float64Array, Function, Int32Array, Number, Object, Reflect, Uint32Array, Uint8Array, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::progress::JsProgress;
+use crate::array::{self, Column};
+use crate::error::Error;
+
+/// Sorts an `Int32Array` with pdqsort. See [`array::quick_sort`].
+#[wasm_bindgen]
+pub fn quick_sort(mut arr: Vec<i32>) -> Vec<i32> {
+    array::quick_sort_in_place(&mut arr);
+    arr
+}
+
+/// Sorts an `Int32Array` with the stable MergeSort. See [`array::merge_sort`].
+#[wasm_bindgen]
+pub fn merge_sort(arr: Vec<i32>) -> Vec<i32> {
+    array::merge_sort(&arr)
+}
+
+/// Sorts an `Int32Array` with MergeSort on the worker pool when available, otherwise on the
+/// calling thread. See [`array::merge_sort_parallel`].
+#[cfg(feature = "parallel")]
+#[wasm_bindgen]
+pub fn merge_sort_parallel(arr: Vec<i32>) -> Vec<i32> {
+    array::merge_sort_parallel(&arr)
+}
+
+/// Sorts an `Int32Array` like `merge_sort`, calling `on_progress(fraction, moves)` periodically and
+/// stopping once `abort` is set. See [`array::merge_sort_with_progress`].
+///
+/// Throws an `Error` with code `CANCELLED` when cancelled.
+#[wasm_bindgen]
+pub fn merge_sort_with_progress(arr: Vec<i32>, on_progress: JsValue, abort: JsValue) -> Result<Vec<i32>, JsValue> {
+    let mut progress = JsProgress::new(on_progress, abort)?;
+    array::merge_sort_with_progress(&arr, &mut progress).map_err(|e| progress.error(e))
+}
+
+/// Where `NaN` values go in `sort_f64` / `sort_f32`: `"first"` or `"last"` (the default, as in
+/// `Float64Array.prototype.sort`). See [`array::NanPlacement`].
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum NanPlacement {
+    First = "first",
+    Last = "last",
+}
+
+impl TryFrom<Option<NanPlacement>> for array::NanPlacement {
+    type Error = JsValue;
+
+    fn try_from(nans: Option<NanPlacement>) -> Result<array::NanPlacement, JsValue> {
+        match nans {
+            None | Some(NanPlacement::Last) => Ok(array::NanPlacement::Last),
+            Some(NanPlacement::First) => Ok(array::NanPlacement::First),
+            // wasm-bindgen maps any other string to a hidden variant
+            Some(_) => Err(Error::InvalidInput("nans must be \"first\" or \"last\"".to_string()).into()),
+        }
+    }
+}
+
+/// Sorts a `Float64Array` (stable, `-0` before `+0`, `NaN`s placed by `nans`). See
+/// [`array::sort_f64`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `nans` is not `"first"` or `"last"`.
+#[wasm_bindgen]
+pub fn sort_f64(arr: Vec<f64>, nans: Option<NanPlacement>) -> Result<Vec<f64>, JsValue> {
+    Ok(array::sort_f64(&arr, nans.try_into()?))
+}
+
+/// Sorts a `Float32Array` (stable, `-0` before `+0`, `NaN`s placed by `nans`). See
+/// [`array::sort_f32`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `nans` is not `"first"` or `"last"`.
+#[wasm_bindgen]
+pub fn sort_f32(arr: Vec<f32>, nans: Option<NanPlacement>) -> Result<Vec<f32>, JsValue> {
+    Ok(array::sort_f32(&arr, nans.try_into()?))
+}
+
+/// Sorts an `Int32Array`, with MergeSort for short inputs and radix sort for large ones. See
+/// [`array::sort_i32`].
+#[wasm_bindgen]
+pub fn sort_i32(arr: Vec<i32>) -> Vec<i32> {
+    array::sort_i32(&arr)
+}
+
+/// Sorts a `Uint32Array`, with MergeSort for short inputs and radix sort for large ones. See
+/// [`array::sort_u32`].
+#[wasm_bindgen]
+pub fn sort_u32(arr: Vec<u32>) -> Vec<u32> {
+    array::sort_u32(&arr)
+}
+
+/// Sorts an `Int32Array` with the LSD radix sort. See [`array::radix_sort_i32`].
+#[wasm_bindgen]
+pub fn radix_sort_i32(arr: Vec<i32>) -> Vec<i32> {
+    array::radix_sort_i32(&arr)
+}
+
+/// Sorts a `Uint32Array` with the LSD radix sort. See [`array::radix_sort_u32`].
+#[wasm_bindgen]
+pub fn radix_sort_u32(arr: Vec<u32>) -> Vec<u32> {
+    array::radix_sort_u32(&arr)
+}
+
+/// Sorts a `Float32Array` with the LSD radix sort, `NaN`s placed as in `sort_f32`. See
+/// [`array::radix_sort_f32`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `nans` is not `"first"` or `"last"`.
+#[wasm_bindgen]
+pub fn radix_sort_f32(arr: Vec<f32>, nans: Option<NanPlacement>) -> Result<Vec<f32>, JsValue> {
+    Ok(array::radix_sort_f32(&arr, nans.try_into()?))
+}
+
+/// Sorts a `BigInt64Array` with the stable MergeSort. See [`array::merge_sort`].
+#[wasm_bindgen]
+pub fn sort_i64(arr: Vec<i64>) -> Vec<i64> {
+    array::merge_sort(&arr)
+}
+
+/// Sorts a `BigUint64Array` with the stable MergeSort. See [`array::merge_sort`].
+#[wasm_bindgen]
+pub fn sort_u64(arr: Vec<u64>) -> Vec<u64> {
+    array::merge_sort(&arr)
+}
+
+/// Sorts an `Int16Array` with the stable MergeSort. See [`array::merge_sort`].
+#[wasm_bindgen]
+pub fn sort_i16(arr: Vec<i16>) -> Vec<i16> {
+    array::merge_sort(&arr)
+}
+
+/// Sorts a `Uint16Array` with the stable MergeSort. See [`array::merge_sort`].
+#[wasm_bindgen]
+pub fn sort_u16(arr: Vec<u16>) -> Vec<u16> {
+    array::merge_sort(&arr)
+}
+
+/// Sorts an `Int8Array` with the stable MergeSort. See [`array::merge_sort`].
+#[wasm_bindgen]
+pub fn sort_i8(arr: Vec<i8>) -> Vec<i8> {
+    array::merge_sort(&arr)
+}
+
+/// Sorts a `Uint8Array` with the stable MergeSort. See [`array::merge_sort`].
+#[wasm_bindgen]
+pub fn sort_u8(arr: Vec<u8>) -> Vec<u8> {
+    array::merge_sort(&arr)
+}
+
+/// Sorts an array of strings with the stable MergeSort, by Unicode code point unless `options`
+/// (`{ natural, caseInsensitive, unicode, accentInsensitive }`) asks for another collation. See
+/// [`array::sort_strings`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` for options that are not booleans, or a collation
+/// this build does not support.
+#[wasm_bindgen]
+pub fn sort_strings(arr: Vec<String>, options: Option<Object>) -> Result<Vec<String>, JsValue> {
+    Ok(array::sort_strings(&arr, string_collation(options)?)?)
+}
+
+/// Reads the collation options of `sort_strings` and `argsort_strings`.
+fn string_collation(options: Option<Object>) -> Result<array::StringCollation, JsValue> {
+    let Some(options) = options else {
+        return Ok(array::StringCollation::default());
+    };
+    let flag = |name: &str| -> Result<bool, JsValue> {
+        let value = Reflect::get(&options, &JsValue::from_str(name))?;
+        if value.is_undefined() {
+            return Ok(false);
+        }
+        value
+            .as_bool()
+            .ok_or_else(|| Error::InvalidInput(format!("collation option {:?} must be a boolean", name)).into())
+    };
+    Ok(array::StringCollation {
+        natural: flag("natural")?,
+        case_insensitive: flag("caseInsensitive")?,
+        unicode: flag("unicode")?,
+        accent_insensitive: flag("accentInsensitive")?,
+    })
+}
+
+/// Direction of the argsort functions: `"asc"` (the default) or `"desc"`. See
+/// [`array::SortOrder`].
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum SortOrder {
+    Asc = "asc",
+    Desc = "desc",
+}
+
+impl TryFrom<Option<SortOrder>> for array::SortOrder {
+    type Error = Error;
+
+    fn try_from(order: Option<SortOrder>) -> Result<array::SortOrder, Error> {
+        match order {
+            None | Some(SortOrder::Asc) => Ok(array::SortOrder::Ascending),
+            Some(SortOrder::Desc) => Ok(array::SortOrder::Descending),
+            // wasm-bindgen maps any other string to a hidden variant
+            Some(_) => Err(Error::InvalidInput("order must be \"asc\" or \"desc\"".to_string())),
+        }
+    }
+}
+
+/// Converts a permutation into the `Uint32Array` returned to JavaScript.
+fn indices(permutation: Vec<usize>) -> Vec<u32> {
+    permutation.into_iter().map(|i| i as u32).collect()
+}
+
+/// Returns the stable sorting permutation of an `Int32Array` as a `Uint32Array`. See
+/// [`array::argsort`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn argsort_i32(arr: Vec<i32>, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort(&arr, order.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of a `Uint32Array`. See [`array::argsort`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn argsort_u32(arr: Vec<u32>, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort(&arr, order.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of a `BigInt64Array`. See [`array::argsort`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn argsort_i64(arr: Vec<i64>, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort(&arr, order.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of a `BigUint64Array`. See [`array::argsort`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn argsort_u64(arr: Vec<u64>, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort(&arr, order.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of an `Int16Array`. See [`array::argsort`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn argsort_i16(arr: Vec<i16>, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort(&arr, order.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of a `Uint16Array`. See [`array::argsort`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn argsort_u16(arr: Vec<u16>, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort(&arr, order.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of an `Int8Array`. See [`array::argsort`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn argsort_i8(arr: Vec<i8>, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort(&arr, order.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of a `Uint8Array`. See [`array::argsort`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn argsort_u8(arr: Vec<u8>, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort(&arr, order.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of a `Float64Array`, ordered like `sort_f64`. See
+/// [`array::argsort_f64`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`, or if
+/// `nans` is not `"first"` or `"last"`.
+#[wasm_bindgen]
+pub fn argsort_f64(arr: Vec<f64>, order: Option<SortOrder>, nans: Option<NanPlacement>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort_f64(&arr, order.try_into()?, nans.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of a `Float32Array`, ordered like `sort_f32`. See
+/// [`array::argsort_f32`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`, or if
+/// `nans` is not `"first"` or `"last"`.
+#[wasm_bindgen]
+pub fn argsort_f32(arr: Vec<f32>, order: Option<SortOrder>, nans: Option<NanPlacement>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort_f32(&arr, order.try_into()?, nans.try_into()?)))
+}
+
+/// Returns the stable sorting permutation of an array of strings, compared like `sort_strings`
+/// with the same `options`. See [`array::argsort_strings`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`, or for the
+/// `options` `sort_strings` rejects.
+#[wasm_bindgen]
+pub fn argsort_strings(arr: Vec<String>, order: Option<SortOrder>, options: Option<Object>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::argsort_strings(&arr, order.try_into()?, string_collation(options)?)?))
+}
+
+/// Reorders an `Array` or typed array by a permutation from an argsort function, returning a new
+/// array of the same type. See [`array::apply_permutation`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `values` is not array-like or `permutation` is
+/// not a permutation of its indices.
+#[wasm_bindgen]
+pub fn apply_permutation(values: &JsValue, permutation: Vec<u32>) -> Result<JsValue, JsValue> {
+    let not_array_like = || Error::InvalidInput("values must be an Array or a typed array".to_string());
+    if !values.is_object() {
+        return Err(not_array_like().into());
+    }
+    let len = Reflect::get(values, &JsValue::from_str("length"))?.as_f64().ok_or_else(not_array_like)?;
+    let elements = (0..len as u32).map(|i| Reflect::get_u32(values, i)).collect::<Result<Vec<_>, _>>()?;
+    let permutation: Vec<usize> = permutation.into_iter().map(|i| i as usize).collect();
+    let reordered = array::apply_permutation(&elements, &permutation)?;
+
+    // `new values.constructor(length)` gives an `Array` or a typed array of the same type
+    let constructor = Reflect::get(values, &JsValue::from_str("constructor"))?;
+    let result = Reflect::construct(constructor.unchecked_ref::<Function>(), &Array::of1(&JsValue::from(reordered.len())))?;
+    for (i, element) in reordered.iter().enumerate() {
+        Reflect::set_u32(&result, i as u32, element)?;
+    }
+    Ok(result)
+}
+
+/// A column passed to `argsort_multi`, copied out of JavaScript.
+enum OwnedColumn {
+    I32(Vec<i32>),
+    U32(Vec<u32>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+    Strings(Vec<String>),
+}
+
+impl OwnedColumn {
+    fn from_js(value: &JsValue) -> Result<OwnedColumn, JsValue> {
+        if let Some(values) = value.dyn_ref::<Int32Array>() {
+            Ok(OwnedColumn::I32(values.to_vec()))
+        } else if let Some(values) = value.dyn_ref::<Uint32Array>() {
+            Ok(OwnedColumn::U32(values.to_vec()))
+        } else if let Some(values) = value.dyn_ref::<Float32Array>() {
+            Ok(OwnedColumn::F32(values.to_vec()))
+        } else if let Some(values) = value.dyn_ref::<Float64Array>() {
+            Ok(OwnedColumn::F64(values.to_vec()))
+        } else if let Some(values) = value.dyn_ref::<Array>() {
+            let strings = values.iter().map(|v| v.as_string()).collect::<Option<Vec<_>>>();
+            strings.map(OwnedColumn::Strings).ok_or_else(unsupported_column)
+        } else {
+            Err(unsupported_column())
+        }
+    }
+
+    fn as_column(&self) -> Column<'_> {
+        match self {
+            OwnedColumn::I32(values) => Column::I32(values),
+            OwnedColumn::U32(values) => Column::U32(values),
+            OwnedColumn::F32(values) => Column::F32(values),
+            OwnedColumn::F64(values) => Column::F64(values),
+            OwnedColumn::Strings(values) => Column::Strings(values),
+        }
+    }
+}
+
+fn unsupported_column() -> JsValue {
+    Error::InvalidInput("columns must be Int32Array, Uint32Array, Float32Array, Float64Array or arrays of strings".to_string())
+        .into()
+}
+
+/// Returns the permutation that sorts the rows of a table by several columns: the first column,
+/// ties broken by the next. `orders` gives the direction of each column (`"asc"` when missing).
+/// See [`array::argsort_multi`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` for unsupported column types, unknown directions
+/// or columns of different lengths.
+#[wasm_bindgen]
+pub fn argsort_multi(columns: Array, orders: Option<Vec<String>>) -> Result<Vec<u32>, JsValue> {
+    let columns = columns.iter().map(|column| OwnedColumn::from_js(&column)).collect::<Result<Vec<_>, _>>()?;
+    let orders = orders.unwrap_or_default();
+    let mut keys = Vec::with_capacity(columns.len());
+    for (i, column) in columns.iter().enumerate() {
+        let order = match orders.get(i).map(String::as_str) {
+            None | Some("asc") => array::SortOrder::Ascending,
+            Some("desc") => array::SortOrder::Descending,
+            Some(other) => {
+                return Err(Error::InvalidInput(format!("unknown sort order {:?}, expected \"asc\" or \"desc\"", other)).into())
+            }
+        };
+        keys.push((column.as_column(), order));
+    }
+    Ok(indices(array::argsort_multi(&keys)?))
+}
+
+/// One key of the `sort_objects` spec.
+struct ObjectKey {
+    /// Property names to follow from the record, e.g. `["user", "lastName"]`.
+    path: Vec<String>,
+    key: array::SortKey,
+}
+
+impl ObjectKey {
+    /// Parses `"path"`, `"path asc"`, `"path desc"` or
+    /// `{ key, order?, collation?, nulls? }`.
+    fn from_js(entry: &JsValue) -> Result<ObjectKey, JsValue> {
+        if let Some(text) = entry.as_string() {
+            let mut words = text.split_whitespace();
+            let path = words.next().ok_or_else(|| invalid_spec("empty key path"))?;
+            let order = parse_option(words.next(), &[("asc", array::SortOrder::Ascending), ("desc", array::SortOrder::Descending)])?;
+            if let Some(extra) = words.next() {
+                return Err(invalid_spec(&format!("unexpected {:?} after the direction", extra)));
+            }
+            return Ok(ObjectKey::new(path, array::SortKey { order, ..array::SortKey::default() }));
+        }
+        if !entry.is_object() {
+            return Err(invalid_spec("keys must be strings or objects"));
+        }
+
+        let path = string_property(entry, "key")?.ok_or_else(|| invalid_spec("missing \"key\" property"))?;
+        let key = array::SortKey {
+            order: parse_option(
+                string_property(entry, "order")?.as_deref(),
+                &[("asc", array::SortOrder::Ascending), ("desc", array::SortOrder::Descending)],
+            )?,
+            collation: parse_option(
+                string_property(entry, "collation")?.as_deref(),
+                &[
+                    ("string", array::Collation::String),
+                    ("numeric", array::Collation::Numeric),
+                    ("natural", array::Collation::Natural),
+                ],
+            )?,
+            nulls: parse_option(
+                string_property(entry, "nulls")?.as_deref(),
+                &[("first", array::NullPlacement::First), ("last", array::NullPlacement::Last)],
+            )?,
+        };
+        Ok(ObjectKey::new(&path, key))
+    }
+
+    fn new(path: &str, key: array::SortKey) -> ObjectKey {
+        ObjectKey { path: path.split('.').map(String::from).collect(), key }
+    }
+
+    /// Looks up the key in `record`, converted for the key's collation.
+    fn value(&self, record: &JsValue) -> Result<array::SortValue, JsValue> {
+        let mut value = record.clone();
+        for name in &self.path {
+            if !value.is_object() {
+                return Ok(array::SortValue::Null);
+            }
+            value = Reflect::get(&value, &JsValue::from_str(name))?;
+        }
+
+        let numeric = self.key.collation == array::Collation::Numeric;
+        Ok(if let Some(text) = value.as_string() {
+            array::SortValue::Text(text)
+        } else if let Some(n) = value.as_f64() {
+            if numeric {
+                array::SortValue::Number(n)
+            } else {
+                // Format like JavaScript, e.g. `1e+21` rather than Rust's digits
+                array::SortValue::Text(Number::from(n).to_string_with_radix(10)?.into())
+            }
+        } else if let Some(flag) = value.as_bool() {
+            if numeric {
+                array::SortValue::Number(f64::from(u8::from(flag)))
+            } else {
+                array::SortValue::Text(flag.to_string())
+            }
+        } else {
+            array::SortValue::Null
+        })
+    }
+}
+
+fn invalid_spec(message: &str) -> JsValue {
+    Error::InvalidInput(format!("invalid sort spec: {}", message)).into()
+}
+
+/// Reads an optional string property of a spec entry.
+fn string_property(entry: &JsValue, name: &str) -> Result<Option<String>, JsValue> {
+    let value = Reflect::get(entry, &JsValue::from_str(name))?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    value.as_string().map(Some).ok_or_else(|| invalid_spec(&format!("\"{}\" must be a string", name)))
+}
+
+/// Maps an optional spec word to its value among `choices`; a missing word gives the default.
+fn parse_option<T: Copy + Default>(word: Option<&str>, choices: &[(&str, T)]) -> Result<T, JsValue> {
+    let Some(word) = word else {
+        return Ok(T::default());
+    };
+    choices.iter().find(|(name, _)| *name == word).map(|(_, value)| *value).ok_or_else(|| {
+        let names: Vec<_> = choices.iter().map(|(name, _)| format!("{:?}", name)).collect();
+        invalid_spec(&format!("unknown option {:?}, expected {}", word, names.join(" or ")))
+    })
+}
+
+/// Sorts an array of objects by key paths, stably, and returns the sorted array (the array
+/// passed in is not modified). See [`array::argsort_records`].
+///
+/// `spec` is a key or an array of keys. A key is either a string, `"user.lastName"` or
+/// `"createdAt desc"`, or an object `{ key, order, collation, nulls }` with `order` `"asc"` or
+/// `"desc"`, `collation` `"string"`, `"numeric"` or `"natural"` and `nulls` `"first"` or
+/// `"last"`. Records can also be JSON strings, like the output of `parse_csv_to_json`; they are
+/// parsed for the lookup and returned unchanged.
+///
+/// Throws an `Error` with code `INVALID_INPUT` for an invalid spec or a record string that is not
+/// valid JSON.
+#[wasm_bindgen]
+pub fn sort_objects(records: Array, spec: JsValue) -> Result<Array, JsValue> {
+    let keys = if Array::is_array(&spec) {
+        Array::from(&spec).iter().map(|entry| ObjectKey::from_js(&entry)).collect::<Result<Vec<_>, _>>()?
+    } else {
+        vec![ObjectKey::from_js(&spec)?]
+    };
+
+    let records: Vec<JsValue> = records.iter().collect();
+    let mut columns = vec![Vec::with_capacity(records.len()); keys.len()];
+    for (i, record) in records.iter().enumerate() {
+        let parsed = match record.as_string() {
+            Some(json) => JSON::parse(&json)
+                .map_err(|_| Error::InvalidInput(format!("record {} is a string but not valid JSON", i)))?,
+            None => record.clone(),
+        };
+        for (column, key) in columns.iter_mut().zip(&keys) {
+            column.push(key.value(&parsed)?);
+        }
+    }
+
+    let columns: Vec<_> = columns.iter().zip(&keys).map(|(column, key)| (column.as_slice(), key.key)).collect();
+    let order = array::argsort_records(&columns)?;
+    Ok(order.into_iter().map(|i| &records[i]).collect())
+}
+
+/// Rearranges an `Int32Array` in place so that `arr[n]` holds the element of a sorted array at
+/// that index, smaller or equal elements before it and greater or equal after, and returns it.
+/// See [`array::nth_element`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `n` is out of range.
+#[wasm_bindgen]
+pub fn nth_element_i32(arr: &mut [i32], n: usize) -> Result<i32, JsValue> {
+    array::nth_element(arr, n)?;
+    Ok(arr[n])
+}
+
+/// Rearranges a `Float64Array` in place like `nth_element_i32`, in the order of `sort_f64` with
+/// `NaN`s last, and returns `arr[n]`. See [`array::nth_element_f64`].
+#[wasm_bindgen]
+pub fn nth_element_f64(arr: &mut [f64], n: usize) -> Result<f64, JsValue> {
+    array::nth_element_f64(arr, n)?;
+    Ok(arr[n])
+}
+
+/// Converts the optional direction of the `top_k` functions, which default to the largest
+/// elements.
+fn top_k_order(order: Option<SortOrder>) -> Result<array::SortOrder, Error> {
+    match order {
+        None | Some(SortOrder::Desc) => Ok(array::SortOrder::Descending),
+        Some(SortOrder::Asc) => Ok(array::SortOrder::Ascending),
+        // wasm-bindgen maps any other string to a hidden variant
+        Some(_) => Err(Error::InvalidInput("order must be \"asc\" or \"desc\"".to_string())),
+    }
+}
+
+/// Returns the `k` largest elements of an `Int32Array` in descending order, or with `"asc"` the
+/// `k` smallest in ascending order. See [`array::top_k`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn top_k_i32(arr: Vec<i32>, k: usize, order: Option<SortOrder>) -> Result<Vec<i32>, JsValue> {
+    Ok(array::top_k(&arr, k, top_k_order(order)?))
+}
+
+/// Returns the indices of the elements `top_k_i32` selects as a `Uint32Array`. See
+/// [`array::top_k_indices`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn top_k_indices_i32(arr: Vec<i32>, k: usize, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::top_k_indices(&arr, k, top_k_order(order)?)))
+}
+
+/// Returns the `k` largest (or with `"asc"` smallest) numbers of a `Float64Array`, never
+/// selecting `NaN`. See [`array::top_k_f64`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn top_k_f64(arr: Vec<f64>, k: usize, order: Option<SortOrder>) -> Result<Vec<f64>, JsValue> {
+    Ok(array::top_k_f64(&arr, k, top_k_order(order)?))
+}
+
+/// Returns the indices of the numbers `top_k_f64` selects as a `Uint32Array`. See
+/// [`array::top_k_indices_f64`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `order` is not `"asc"` or `"desc"`.
+#[wasm_bindgen]
+pub fn top_k_indices_f64(arr: Vec<f64>, k: usize, order: Option<SortOrder>) -> Result<Vec<u32>, JsValue> {
+    Ok(indices(array::top_k_indices_f64(&arr, k, top_k_order(order)?)))
+}
+
+/// Returns the median of an `Int32Array`, or `undefined` if it is empty. See [`array::median`].
+#[wasm_bindgen]
+pub fn median_i32(arr: Vec<i32>) -> Option<f64> {
+    array::median(&arr)
+}
+
+/// Returns the median of the numbers in a `Float64Array`, ignoring `NaN`s, or `undefined` if
+/// there are none. See [`array::median_f64`].
+#[wasm_bindgen]
+pub fn median_f64(arr: Vec<f64>) -> Option<f64> {
+    array::median_f64(&arr)
+}
+
+/// Flattens the ranges of a batched search into the `Uint32Array` returned to JavaScript.
+fn ranges(ranges: Vec<std::ops::Range<usize>>) -> Vec<u32> {
+    ranges.into_iter().flat_map(|range| [range.start as u32, range.end as u32]).collect()
+}
+
+/// Returns the index of the first element equal to `value` in an `Int32Array` sorted in ascending order,
+/// or `undefined`. See [`array::binary_search`].
+#[wasm_bindgen]
+pub fn binary_search_i32(arr: Vec<i32>, value: i32) -> Option<usize> {
+    array::binary_search(&arr, &value)
+}
+
+/// Returns the first index of a sorted `Int32Array` whose element is not less than `value`. See
+/// [`array::lower_bound`].
+#[wasm_bindgen]
+pub fn lower_bound_i32(arr: Vec<i32>, value: i32) -> usize {
+    array::lower_bound(&arr, &value)
+}
+
+/// Returns the first index of a sorted `Int32Array` whose element is greater than `value`. See
+/// [`array::upper_bound`].
+#[wasm_bindgen]
+pub fn upper_bound_i32(arr: Vec<i32>, value: i32) -> usize {
+    array::upper_bound(&arr, &value)
+}
+
+/// Returns `[start, end]`, the range of indices of a sorted `Int32Array` whose elements equal
+/// `value`. See [`array::equal_range`].
+#[wasm_bindgen]
+pub fn equal_range_i32(arr: Vec<i32>, value: i32) -> Vec<u32> {
+    let range = array::equal_range(&arr, &value);
+    vec![range.start as u32, range.end as u32]
+}
+
+/// Counts the elements of a sorted `Int32Array` between `low` and `high`, both included. See
+/// [`array::count_in_range`].
+#[wasm_bindgen]
+pub fn count_in_range_i32(arr: Vec<i32>, low: i32, high: i32) -> usize {
+    array::count_in_range(&arr, &low, &high)
+}
+
+/// Answers `equal_range_i32` for many `queries` in one call. Returns a `Uint32Array` with the
+/// start and end of each query's range, `[start0, end0, start1, end1, ...]`. See
+/// [`array::equal_range_batch`].
+#[wasm_bindgen]
+pub fn equal_range_batch_i32(arr: Vec<i32>, queries: Vec<i32>) -> Vec<u32> {
+    ranges(array::equal_range_batch(&arr, &queries))
+}
+
+/// Returns the index of the first element equal to `value` in a `Float64Array` sorted in ascending order,
+/// or `undefined`. See [`array::binary_search`].
+#[wasm_bindgen]
+pub fn binary_search_f64(arr: Vec<f64>, value: f64) -> Option<usize> {
+    array::binary_search(&arr, &value)
+}
+
+/// Returns the first index of a sorted `Float64Array` whose element is not less than `value`. See
+/// [`array::lower_bound`].
+#[wasm_bindgen]
+pub fn lower_bound_f64(arr: Vec<f64>, value: f64) -> usize {
+    array::lower_bound(&arr, &value)
+}
+
+/// Returns the first index of a sorted `Float64Array` whose element is greater than `value`. See
+/// [`array::upper_bound`].
+#[wasm_bindgen]
+pub fn upper_bound_f64(arr: Vec<f64>, value: f64) -> usize {
+    array::upper_bound(&arr, &value)
+}
+
+/// Returns `[start, end]`, the range of indices of a sorted `Float64Array` whose elements equal
+/// `value`. See [`array::equal_range`].
+#[wasm_bindgen]
+pub fn equal_range_f64(arr: Vec<f64>, value: f64) -> Vec<u32> {
+    let range = array::equal_range(&arr, &value);
+    vec![range.start as u32, range.end as u32]
+}
+
+/// Counts the elements of a sorted `Float64Array` between `low` and `high`, both included. See
+/// [`array::count_in_range`].
+#[wasm_bindgen]
+pub fn count_in_range_f64(arr: Vec<f64>, low: f64, high: f64) -> usize {
+    array::count_in_range(&arr, &low, &high)
+}
+
+/// Answers `equal_range_f64` for many `queries` in one call. Returns a `Uint32Array` with the
+/// start and end of each query's range, `[start0, end0, start1, end1, ...]`. See
+/// [`array::equal_range_batch`].
+#[wasm_bindgen]
+pub fn equal_range_batch_f64(arr: Vec<f64>, queries: Vec<f64>) -> Vec<u32> {
+    ranges(array::equal_range_batch(&arr, &queries))
+}
+
+/// Returns the index of the first element equal to `value` in an array of strings sorted in ascending order,
+/// or `undefined`. See [`array::binary_search`].
+#[wasm_bindgen]
+pub fn binary_search_strings(arr: Vec<String>, value: String) -> Option<usize> {
+    array::binary_search(&arr, &value)
+}
+
+/// Returns the first index of a sorted array of strings whose element is not less than `value`. See
+/// [`array::lower_bound`].
+#[wasm_bindgen]
+pub fn lower_bound_strings(arr: Vec<String>, value: String) -> usize {
+    array::lower_bound(&arr, &value)
+}
+
+/// Returns the first index of a sorted array of strings whose element is greater than `value`. See
+/// [`array::upper_bound`].
+#[wasm_bindgen]
+pub fn upper_bound_strings(arr: Vec<String>, value: String) -> usize {
+    array::upper_bound(&arr, &value)
+}
+
+/// Returns `[start, end]`, the range of indices of a sorted array of strings whose elements equal
+/// `value`. See [`array::equal_range`].
+#[wasm_bindgen]
+pub fn equal_range_strings(arr: Vec<String>, value: String) -> Vec<u32> {
+    let range = array::equal_range(&arr, &value);
+    vec![range.start as u32, range.end as u32]
+}
+
+/// Counts the elements of a sorted array of strings between `low` and `high`, both included. See
+/// [`array::count_in_range`].
+#[wasm_bindgen]
+pub fn count_in_range_strings(arr: Vec<String>, low: String, high: String) -> usize {
+    array::count_in_range(&arr, &low, &high)
+}
+
+/// Answers `equal_range_strings` for many `queries` in one call. Returns a `Uint32Array` with the
+/// start and end of each query's range, `[start0, end0, start1, end1, ...]`. See
+/// [`array::equal_range_batch`].
+#[wasm_bindgen]
+pub fn equal_range_batch_strings(arr: Vec<String>, queries: Vec<String>) -> Vec<u32> {
+    ranges(array::equal_range_batch(&arr, &queries))
+}
+
+/// Copies the arrays passed to a function that takes several of them (`merge_sorted_*`,
+/// `flatten_*`, ...) out of a JavaScript array, failing with `INVALID_INPUT` if `convert` rejects
+/// one of them.
+fn array_inputs<T>(inputs: &Array, expected: &str, convert: impl Fn(&JsValue) -> Option<Vec<T>>) -> Result<Vec<Vec<T>>, JsValue> {
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            convert(&input).ok_or_else(|| Error::InvalidInput(format!("input {} is not {}", i, expected)).into())
+        })
+        .collect()
+}
+
+fn int32_inputs(inputs: &Array) -> Result<Vec<Vec<i32>>, JsValue> {
+    array_inputs(inputs, "an Int32Array", |input| input.dyn_ref::<Int32Array>().map(Int32Array::to_vec))
+}
+
+fn float64_inputs(inputs: &Array) -> Result<Vec<Vec<f64>>, JsValue> {
+    array_inputs(inputs, "a Float64Array", |input| input.dyn_ref::<Float64Array>().map(Float64Array::to_vec))
+}
+
+/// Returns the elements of a sorted `Int32Array` without repetitions. See [`array::unique`].
+#[wasm_bindgen]
+pub fn unique_i32(arr: Vec<i32>) -> Vec<i32> {
+    array::unique(&arr)
+}
+
+/// Returns the sorted, duplicate-free union of two sorted `Int32Array`s. See [`array::union`].
+#[wasm_bindgen]
+pub fn union_i32(a: Vec<i32>, b: Vec<i32>) -> Vec<i32> {
+    array::union(&a, &b)
+}
+
+/// Returns the elements two sorted `Int32Array`s have in common. See [`array::intersection`].
+#[wasm_bindgen]
+pub fn intersection_i32(a: Vec<i32>, b: Vec<i32>) -> Vec<i32> {
+    array::intersection(&a, &b)
+}
+
+/// Returns the elements of sorted `a` that are not in sorted `b`. See [`array::difference`].
+#[wasm_bindgen]
+pub fn difference_i32(a: Vec<i32>, b: Vec<i32>) -> Vec<i32> {
+    array::difference(&a, &b)
+}
+
+/// Returns the elements that are in exactly one of two sorted `Int32Array`s. See
+/// [`array::symmetric_difference`].
+#[wasm_bindgen]
+pub fn symmetric_difference_i32(a: Vec<i32>, b: Vec<i32>) -> Vec<i32> {
+    array::symmetric_difference(&a, &b)
+}
+
+/// Returns `true` if every element of sorted `a` is in sorted `b`. See [`array::is_subset`].
+#[wasm_bindgen]
+pub fn is_subset_i32(a: Vec<i32>, b: Vec<i32>) -> bool {
+    array::is_subset(&a, &b)
+}
+
+/// Merges an array of sorted `Int32Array`s into one sorted array, keeping duplicates. See
+/// [`array::merge_sorted`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if an input is not an `Int32Array`.
+#[wasm_bindgen]
+pub fn merge_sorted_i32(inputs: Array) -> Result<Vec<i32>, JsValue> {
+    Ok(array::merge_sorted(&int32_inputs(&inputs)?))
+}
+
+/// Returns the elements of a sorted `Uint32Array` without repetitions. See [`array::unique`].
+#[wasm_bindgen]
+pub fn unique_u32(arr: Vec<u32>) -> Vec<u32> {
+    array::unique(&arr)
+}
+
+/// Returns the sorted, duplicate-free union of two sorted `Uint32Array`s. See [`array::union`].
+#[wasm_bindgen]
+pub fn union_u32(a: Vec<u32>, b: Vec<u32>) -> Vec<u32> {
+    array::union(&a, &b)
+}
+
+/// Returns the elements two sorted `Uint32Array`s have in common. See [`array::intersection`].
+#[wasm_bindgen]
+pub fn intersection_u32(a: Vec<u32>, b: Vec<u32>) -> Vec<u32> {
+    array::intersection(&a, &b)
+}
+
+/// Returns the elements of sorted `a` that are not in sorted `b`. See [`array::difference`].
+#[wasm_bindgen]
+pub fn difference_u32(a: Vec<u32>, b: Vec<u32>) -> Vec<u32> {
+    array::difference(&a, &b)
+}
+
+/// Returns the elements that are in exactly one of two sorted `Uint32Array`s. See
+/// [`array::symmetric_difference`].
+#[wasm_bindgen]
+pub fn symmetric_difference_u32(a: Vec<u32>, b: Vec<u32>) -> Vec<u32> {
+    array::symmetric_difference(&a, &b)
+}
+
+/// Returns `true` if every element of sorted `a` is in sorted `b`. See [`array::is_subset`].
+#[wasm_bindgen]
+pub fn is_subset_u32(a: Vec<u32>, b: Vec<u32>) -> bool {
+    array::is_subset(&a, &b)
+}
+
+/// Merges an array of sorted `Uint32Array`s into one sorted array, keeping duplicates. See
+/// [`array::merge_sorted`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if an input is not a `Uint32Array`.
+#[wasm_bindgen]
+pub fn merge_sorted_u32(inputs: Array) -> Result<Vec<u32>, JsValue> {
+    let inputs = array_inputs(&inputs, "a Uint32Array", |input| input.dyn_ref::<Uint32Array>().map(Uint32Array::to_vec))?;
+    Ok(array::merge_sorted(&inputs))
+}
+
+/// Returns the elements of a sorted `Float64Array` without repetitions. See [`array::unique`].
+#[wasm_bindgen]
+pub fn unique_f64(arr: Vec<f64>) -> Vec<f64> {
+    array::unique(&arr)
+}
+
+/// Returns the sorted, duplicate-free union of two sorted `Float64Array`s. See [`array::union`].
+#[wasm_bindgen]
+pub fn union_f64(a: Vec<f64>, b: Vec<f64>) -> Vec<f64> {
+    array::union(&a, &b)
+}
+
+/// Returns the elements two sorted `Float64Array`s have in common. See [`array::intersection`].
+#[wasm_bindgen]
+pub fn intersection_f64(a: Vec<f64>, b: Vec<f64>) -> Vec<f64> {
+    array::intersection(&a, &b)
+}
+
+/// Returns the elements of sorted `a` that are not in sorted `b`. See [`array::difference`].
+#[wasm_bindgen]
+pub fn difference_f64(a: Vec<f64>, b: Vec<f64>) -> Vec<f64> {
+    array::difference(&a, &b)
+}
+
+/// Returns the elements that are in exactly one of two sorted `Float64Array`s. See
+/// [`array::symmetric_difference`].
+#[wasm_bindgen]
+pub fn symmetric_difference_f64(a: Vec<f64>, b: Vec<f64>) -> Vec<f64> {
+    array::symmetric_difference(&a, &b)
+}
+
+/// Returns `true` if every element of sorted `a` is in sorted `b`. See [`array::is_subset`].
+#[wasm_bindgen]
+pub fn is_subset_f64(a: Vec<f64>, b: Vec<f64>) -> bool {
+    array::is_subset(&a, &b)
+}
+
+/// Merges an array of sorted `Float64Array`s into one sorted array, keeping duplicates. See
+/// [`array::merge_sorted`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if an input is not a `Float64Array`.
+#[wasm_bindgen]
+pub fn merge_sorted_f64(inputs: Array) -> Result<Vec<f64>, JsValue> {
+    Ok(array::merge_sorted(&float64_inputs(&inputs)?))
+}
+
+/// Returns the elements of a sorted array of strings without repetitions. See [`array::unique`].
+#[wasm_bindgen]
+pub fn unique_strings(arr: Vec<String>) -> Vec<String> {
+    array::unique(&arr)
+}
+
+/// Returns the sorted, duplicate-free union of two sorted arrays of strings. See [`array::union`].
+#[wasm_bindgen]
+pub fn union_strings(a: Vec<String>, b: Vec<String>) -> Vec<String> {
+    array::union(&a, &b)
+}
+
+/// Returns the elements two sorted arrays of strings have in common. See [`array::intersection`].
+#[wasm_bindgen]
+pub fn intersection_strings(a: Vec<String>, b: Vec<String>) -> Vec<String> {
+    array::intersection(&a, &b)
+}
+
+/// Returns the elements of sorted `a` that are not in sorted `b`. See [`array::difference`].
+#[wasm_bindgen]
+pub fn difference_strings(a: Vec<String>, b: Vec<String>) -> Vec<String> {
+    array::difference(&a, &b)
+}
+
+/// Returns the elements that are in exactly one of two sorted arrays of strings. See
+/// [`array::symmetric_difference`].
+#[wasm_bindgen]
+pub fn symmetric_difference_strings(a: Vec<String>, b: Vec<String>) -> Vec<String> {
+    array::symmetric_difference(&a, &b)
+}
+
+/// Returns `true` if every element of sorted `a` is in sorted `b`. See [`array::is_subset`].
+#[wasm_bindgen]
+pub fn is_subset_strings(a: Vec<String>, b: Vec<String>) -> bool {
+    array::is_subset(&a, &b)
+}
+
+/// Merges an array of sorted arrays of strings into one sorted array, keeping duplicates. See
+/// [`array::merge_sorted`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if an input is not an array of strings.
+#[wasm_bindgen]
+pub fn merge_sorted_strings(inputs: Array) -> Result<Vec<String>, JsValue> {
+    let inputs = array_inputs(&inputs, "an array of strings", |input| input.dyn_ref::<Array>()?.iter().map(|v| v.as_string()).collect())?;
+    Ok(array::merge_sorted(&inputs))
+}
+
+/// Returns `true` if an `Int32Array` is sorted in ascending order; with `strict`, also without
+/// repeated elements. See [`array::is_sorted`].
+#[wasm_bindgen]
+pub fn is_sorted_i32(arr: &[i32], strict: Option<bool>) -> bool {
+    array::is_sorted(arr, strict.unwrap_or(false))
+}
+
+/// Returns `true` if a `Float64Array` is sorted in ascending order with `NaN`s last. See
+/// [`array::is_sorted`].
+#[wasm_bindgen]
+pub fn is_sorted_f64(arr: &[f64], strict: Option<bool>) -> bool {
+    array::is_sorted(arr, strict.unwrap_or(false))
+}
+
+/// Returns the index of the first element of an `Int32Array` that breaks the ascending order,
+/// or `undefined` if it is sorted. See [`array::first_unsorted_index`].
+#[wasm_bindgen]
+pub fn first_unsorted_index_i32(arr: &[i32], strict: Option<bool>) -> Option<usize> {
+    array::first_unsorted_index(arr, strict.unwrap_or(false))
+}
+
+/// Returns the index of the first element of a `Float64Array` that breaks the ascending order,
+/// or `undefined` if it is sorted. See [`array::first_unsorted_index`].
+#[wasm_bindgen]
+pub fn first_unsorted_index_f64(arr: &[f64], strict: Option<bool>) -> Option<usize> {
+    array::first_unsorted_index(arr, strict.unwrap_or(false))
+}
+
+/// Returns the number of pairs of elements of an `Int32Array` that are out of order. See
+/// [`array::count_inversions`].
+#[wasm_bindgen]
+pub fn count_inversions_i32(arr: &[i32]) -> f64 {
+    array::count_inversions(arr) as f64
+}
+
+/// Returns the number of pairs of elements of a `Float64Array` that are out of order. See
+/// [`array::count_inversions`].
+#[wasm_bindgen]
+pub fn count_inversions_f64(arr: &[f64]) -> f64 {
+    array::count_inversions(arr) as f64
+}
+
+/// Returns a longest increasing subsequence of an `Int32Array` (strictly increasing if
+/// `strict`). See [`array::longest_increasing_subsequence`].
+#[wasm_bindgen]
+pub fn longest_increasing_subsequence_i32(arr: &[i32], strict: Option<bool>) -> Vec<i32> {
+    array::longest_increasing_subsequence(arr, strict.unwrap_or(false))
+}
+
+/// Returns the indices of a longest increasing subsequence of an `Int32Array` as a
+/// `Uint32Array`. See [`array::longest_increasing_subsequence_indices`].
+#[wasm_bindgen]
+pub fn longest_increasing_subsequence_indices_i32(arr: &[i32], strict: Option<bool>) -> Vec<u32> {
+    indices(array::longest_increasing_subsequence_indices(arr, strict.unwrap_or(false)))
+}
+
+/// Returns a longest increasing subsequence of a `Float64Array` (strictly increasing if
+/// `strict`). See [`array::longest_increasing_subsequence`].
+#[wasm_bindgen]
+pub fn longest_increasing_subsequence_f64(arr: &[f64], strict: Option<bool>) -> Vec<f64> {
+    array::longest_increasing_subsequence(arr, strict.unwrap_or(false))
+}
+
+/// Returns the indices of a longest increasing subsequence of a `Float64Array` as a
+/// `Uint32Array`. See [`array::longest_increasing_subsequence_indices`].
+#[wasm_bindgen]
+pub fn longest_increasing_subsequence_indices_f64(arr: &[f64], strict: Option<bool>) -> Vec<u32> {
+    indices(array::longest_increasing_subsequence_indices(arr, strict.unwrap_or(false)))
+}
+
+/// Adapts the `storage` argument of the external sorters to [`array::RunStore`].
+///
+/// `storage` is an object with two synchronous methods: `spill(id, bytes)` saves a compressed
+/// block (a `Uint8Array` it may keep) and `load(id)` returns it. Each block is loaded once, so
+/// `load` can delete it. If a method throws, the sorter call fails and rethrows the exception.
+struct JsRunStore {
+    storage: Object,
+    spill: Function,
+    load: Function,
+    /// Exception thrown by a callback, shared with the sorter so that it is rethrown as is.
+    exception: Rc<RefCell<Option<JsValue>>>,
+}
+
+impl JsRunStore {
+    fn new(storage: Object, exception: Rc<RefCell<Option<JsValue>>>) -> Result<JsRunStore, JsValue> {
+        let method = |name: &str| -> Result<Function, JsValue> {
+            Reflect::get(&storage, &JsValue::from_str(name))?
+                .dyn_into::<Function>()
+                .map_err(|_| Error::InvalidInput(format!("storage.{} must be a function", name)).into())
+        };
+        Ok(JsRunStore { spill: method("spill")?, load: method("load")?, storage, exception })
+    }
+
+    fn threw(&self, exception: JsValue) -> Error {
+        *self.exception.borrow_mut() = Some(exception);
+        Error::InvalidInput("a storage callback threw".to_string())
+    }
+}
+
+impl array::RunStore for JsRunStore {
+    fn spill(&mut self, id: u32, bytes: Vec<u8>) -> crate::error::Result<()> {
+        let bytes = Uint8Array::from(&bytes[..]);
+        self.spill.call2(&self.storage, &JsValue::from(id), &bytes).map_err(|e| self.threw(e))?;
+        Ok(())
+    }
+
+    fn load(&mut self, id: u32) -> crate::error::Result<Vec<u8>> {
+        let bytes = self.load.call1(&self.storage, &JsValue::from(id)).map_err(|e| self.threw(e))?;
+        bytes
+            .dyn_ref::<Uint8Array>()
+            .map(Uint8Array::to_vec)
+            .ok_or_else(|| Error::InvalidInput(format!("storage.load({}) did not return a Uint8Array", id)))
+    }
+}
+
+/// State shared by the `ExternalSorter*` classes.
+enum SorterState<T> {
+    Collecting(array::ExternalSorter<T>),
+    Merging(array::MergedRuns<T>),
+    /// After `finish()` failed.
+    Failed,
+}
+
+struct JsSorter<T> {
+    state: SorterState<T>,
+    exception: Rc<RefCell<Option<JsValue>>>,
+}
+
+impl<T: array::SpillElement> JsSorter<T> {
+    fn new(run_length: usize, storage: Option<Object>) -> Result<JsSorter<T>, JsValue> {
+        let exception = Rc::new(RefCell::new(None));
+        let mut sorter = array::ExternalSorter::new(run_length)?;
+        if let Some(storage) = storage {
+            sorter = sorter.with_store(JsRunStore::new(storage, exception.clone())?);
+        }
+        Ok(JsSorter { state: SorterState::Collecting(sorter), exception })
+    }
+
+    /// Converts an error, preferring the exception of a storage callback that caused it.
+    fn error(&self, error: Error) -> JsValue {
+        self.exception.borrow_mut().take().unwrap_or_else(|| error.into())
+    }
+
+    /// Error for a call in the wrong state; `message` explains it unless an earlier call failed.
+    fn misuse(&self, message: &str) -> JsValue {
+        let message = match self.state {
+            SorterState::Failed => "the sorter cannot be used after finish() failed",
+            _ => message,
+        };
+        Error::InvalidInput(message.to_string()).into()
+    }
+
+    fn push(&mut self, chunk: &[T]) -> Result<(), JsValue> {
+        let SorterState::Collecting(sorter) = &mut self.state else {
+            return Err(self.misuse("cannot push after finish()"));
+        };
+        sorter.push(chunk).map_err(|e| self.error(e))
+    }
+
+    fn finish(&mut self) -> Result<(), JsValue> {
+        let sorter = match std::mem::replace(&mut self.state, SorterState::Failed) {
+            SorterState::Collecting(sorter) => sorter,
+            state => {
+                self.state = state;
+                return Err(self.misuse("finish() was already called"));
+            }
+        };
+        self.state = SorterState::Merging(sorter.finish().map_err(|e| self.error(e))?);
+        Ok(())
+    }
+
+    fn next_chunk(&mut self, max: usize) -> Result<Option<Vec<T>>, JsValue> {
+        let SorterState::Merging(merged) = &mut self.state else {
+            return Err(self.misuse("call finish() before reading the sorted output"));
+        };
+        merged.next_chunk(max).map_err(|e| self.error(e))
+    }
+}
+
+/// Sorts more numbers than fit in wasm memory: chunks are sorted into runs that can be spilled,
+/// compressed, to storage provided by JavaScript, then merged incrementally. See
+/// [`array::ExternalSorter`].
+///
+/// ```javascript
+/// const blocks = new Map();
+/// const sorter = new ExternalSorterF64(1_000_000, {
+///     spill: (id, bytes) => blocks.set(id, bytes),
+///     load: (id) => { const bytes = blocks.get(id); blocks.delete(id); return bytes; },
+/// });
+/// for (const chunk of chunks) sorter.push(chunk);
+/// sorter.finish();
+/// for (let out; (out = sorter.next_chunk(65536)) !== undefined; ) write(out);
+/// sorter.free();
+/// ```
+#[wasm_bindgen]
+pub struct ExternalSorterF64 {
+    inner: JsSorter<f64>,
+}
+
+#[wasm_bindgen]
+impl ExternalSorterF64 {
+    /// Creates a sorter that sorts runs of `run_length` numbers. With `storage` (see the class
+    /// documentation) every sorted run is spilled to it; without, runs stay in memory.
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if `run_length` is 0 or `storage` lacks a
+    /// `spill` or `load` method.
+    #[wasm_bindgen(constructor)]
+    pub fn new(run_length: usize, storage: Option<Object>) -> Result<ExternalSorterF64, JsValue> {
+        Ok(ExternalSorterF64 { inner: JsSorter::new(run_length, storage)? })
+    }
+
+    /// Adds a chunk of numbers (any array of numbers is accepted).
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` after `finish()`, or rethrows an exception
+    /// of `storage.spill`. After a failed spill the sorter is unusable and every later call
+    /// throws.
+    pub fn push(&mut self, chunk: &[f64]) -> Result<(), JsValue> {
+        self.inner.push(chunk)
+    }
+
+    /// Sorts the last run and starts merging. Call it once, after the last `push`.
+    pub fn finish(&mut self) -> Result<(), JsValue> {
+        self.inner.finish()
+    }
+
+    /// Returns the next (at most) `max` sorted numbers as a `Float64Array`, or `undefined` when
+    /// the output is complete.
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` before `finish()` or if `max` is 0, with code
+    /// `DECOMPRESSION_FAILED` if a loaded block is corrupted, or rethrows an exception of
+    /// `storage.load`. No number is lost when `storage.load` throws: calling `next_chunk`
+    /// again retries the load.
+    pub fn next_chunk(&mut self, max: usize) -> Result<Option<Vec<f64>>, JsValue> {
+        self.inner.next_chunk(max)
+    }
+}
+
+/// Sorts more 32-bit integers than fit in wasm memory. See [`ExternalSorterF64`].
+#[wasm_bindgen]
+pub struct ExternalSorterI32 {
+    inner: JsSorter<i32>,
+}
+
+#[wasm_bindgen]
+impl ExternalSorterI32 {
+    /// Creates a sorter that sorts runs of `run_length` integers. See [`ExternalSorterF64::new`].
+    #[wasm_bindgen(constructor)]
+    pub fn new(run_length: usize, storage: Option<Object>) -> Result<ExternalSorterI32, JsValue> {
+        Ok(ExternalSorterI32 { inner: JsSorter::new(run_length, storage)? })
+    }
+
+    /// Adds a chunk of an `Int32Array`. See [`ExternalSorterF64::push`].
+    pub fn push(&mut self, chunk: &[i32]) -> Result<(), JsValue> {
+        self.inner.push(chunk)
+    }
+
+    /// Sorts the last run and starts merging. Call it once, after the last `push`.
+    pub fn finish(&mut self) -> Result<(), JsValue> {
+        self.inner.finish()
+    }
+
+    /// Returns the next (at most) `max` sorted integers as an `Int32Array`, or `undefined` when
+    /// the output is complete. See [`ExternalSorterF64::next_chunk`].
+    pub fn next_chunk(&mut self, max: usize) -> Result<Option<Vec<i32>>, JsValue> {
+        self.inner.next_chunk(max)
+    }
+}
+
+/// Returns the running totals of an array of numbers (any typed array is accepted; the sums
+/// are computed in `f64`, so an `Int32Array` cannot overflow). See [`array::cumulative_sum`].
+#[wasm_bindgen]
+pub fn cumulative_sum_f64(arr: &[f64]) -> Vec<f64> {
+    array::cumulative_sum(arr)
+}
+
+/// Returns the running products of an array of numbers. See [`array::cumulative_product`].
+#[wasm_bindgen]
+pub fn cumulative_product_f64(arr: &[f64]) -> Vec<f64> {
+    array::cumulative_product(arr)
+}
+
+/// Returns the running minimum of an `Int32Array`. See [`array::cumulative_min`].
+#[wasm_bindgen]
+pub fn cumulative_min_i32(arr: &[i32]) -> Vec<i32> {
+    array::cumulative_min(arr)
+}
+
+/// Returns the running minimum of a `Float64Array`; NaN propagates. See
+/// [`array::cumulative_min`].
+#[wasm_bindgen]
+pub fn cumulative_min_f64(arr: &[f64]) -> Vec<f64> {
+    array::cumulative_min(arr)
+}
+
+/// Returns the running maximum of an `Int32Array`. See [`array::cumulative_max`].
+#[wasm_bindgen]
+pub fn cumulative_max_i32(arr: &[i32]) -> Vec<i32> {
+    array::cumulative_max(arr)
+}
+
+/// Returns the running maximum of a `Float64Array`; NaN propagates. See
+/// [`array::cumulative_max`].
+#[wasm_bindgen]
+pub fn cumulative_max_f64(arr: &[f64]) -> Vec<f64> {
+    array::cumulative_max(arr)
+}
+
+/// Returns the sum of every window of `window` consecutive numbers. See [`array::rolling_sum`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `window` is 0.
+#[wasm_bindgen]
+pub fn rolling_sum_f64(arr: &[f64], window: usize) -> Result<Vec<f64>, JsValue> {
+    Ok(array::rolling_sum(arr, window)?)
+}
+
+/// Returns the mean of every window of `window` consecutive numbers. See
+/// [`array::rolling_mean`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `window` is 0.
+#[wasm_bindgen]
+pub fn rolling_mean_f64(arr: &[f64], window: usize) -> Result<Vec<f64>, JsValue> {
+    Ok(array::rolling_mean(arr, window)?)
+}
+
+/// Returns the minimum of every window of `window` consecutive elements of an `Int32Array`.
+/// See [`array::rolling_min`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `window` is 0.
+#[wasm_bindgen]
+pub fn rolling_min_i32(arr: &[i32], window: usize) -> Result<Vec<i32>, JsValue> {
+    Ok(array::rolling_min(arr, window)?)
+}
+
+/// Returns the minimum of every window of `window` consecutive elements of a `Float64Array`.
+/// See [`array::rolling_min`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `window` is 0.
+#[wasm_bindgen]
+pub fn rolling_min_f64(arr: &[f64], window: usize) -> Result<Vec<f64>, JsValue> {
+    Ok(array::rolling_min(arr, window)?)
+}
+
+/// Returns the maximum of every window of `window` consecutive elements of an `Int32Array`.
+/// See [`array::rolling_max`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `window` is 0.
+#[wasm_bindgen]
+pub fn rolling_max_i32(arr: &[i32], window: usize) -> Result<Vec<i32>, JsValue> {
+    Ok(array::rolling_max(arr, window)?)
+}
+
+/// Returns the maximum of every window of `window` consecutive elements of a `Float64Array`.
+/// See [`array::rolling_max`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `window` is 0.
+#[wasm_bindgen]
+pub fn rolling_max_f64(arr: &[f64], window: usize) -> Result<Vec<f64>, JsValue> {
+    Ok(array::rolling_max(arr, window)?)
+}
+
+/// Splits an `Int32Array` into an array of `Int32Array`s of `size` elements. See
+/// [`array::chunk`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `size` is 0.
+#[wasm_bindgen]
+pub fn chunk_i32(arr: &[i32], size: usize) -> Result<Array, JsValue> {
+    Ok(array::chunk(arr, size)?.iter().map(|chunk| Int32Array::from(&chunk[..])).collect())
+}
+
+/// Splits a `Float64Array` into an array of `Float64Array`s of `size` elements. See
+/// [`array::chunk`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `size` is 0.
+#[wasm_bindgen]
+pub fn chunk_f64(arr: &[f64], size: usize) -> Result<Array, JsValue> {
+    Ok(array::chunk(arr, size)?.iter().map(|chunk| Float64Array::from(&chunk[..])).collect())
+}
+
+/// Concatenates an array of `Int32Array`s. See [`array::flatten`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if a part is not an `Int32Array`.
+#[wasm_bindgen]
+pub fn flatten_i32(parts: Array) -> Result<Vec<i32>, JsValue> {
+    Ok(array::flatten(&int32_inputs(&parts)?))
+}
+
+/// Concatenates an array of `Float64Array`s. See [`array::flatten`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if a part is not a `Float64Array`.
+#[wasm_bindgen]
+pub fn flatten_f64(parts: Array) -> Result<Vec<f64>, JsValue> {
+    Ok(array::flatten(&float64_inputs(&parts)?))
+}
+
+/// Pairs up an array of `Int32Array` columns into rows, stopping at the shortest column. See
+/// [`array::zip`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if a column is not an `Int32Array`.
+#[wasm_bindgen]
+pub fn zip_i32(columns: Array) -> Result<Array, JsValue> {
+    Ok(array::zip(&int32_inputs(&columns)?).iter().map(|row| Int32Array::from(&row[..])).collect())
+}
+
+/// Pairs up an array of `Float64Array` columns into rows, stopping at the shortest column.
+/// See [`array::zip`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if a column is not a `Float64Array`.
+#[wasm_bindgen]
+pub fn zip_f64(columns: Array) -> Result<Array, JsValue> {
+    Ok(array::zip(&float64_inputs(&columns)?).iter().map(|row| Float64Array::from(&row[..])).collect())
+}
+
+/// Swaps the rows and columns of a table given as an array of `Int32Array` rows. See
+/// [`array::transpose`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if a row is not an `Int32Array` or the rows
+/// have different lengths.
+#[wasm_bindgen]
+pub fn transpose_i32(rows: Array) -> Result<Array, JsValue> {
+    Ok(array::transpose(&int32_inputs(&rows)?)?.iter().map(|row| Int32Array::from(&row[..])).collect())
+}
+
+/// Swaps the rows and columns of a table given as an array of `Float64Array` rows. See
+/// [`array::transpose`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if a row is not a `Float64Array` or the rows
+/// have different lengths.
+#[wasm_bindgen]
+pub fn transpose_f64(rows: Array) -> Result<Array, JsValue> {
+    Ok(array::transpose(&float64_inputs(&rows)?)?.iter().map(|row| Float64Array::from(&row[..])).collect())
+}
+
+/// Groups `values` by `keys` into the object returned by the `group_by_*` functions: `keys`
+/// (converted by `to_js`) and `counts`, plus `sums`, `means`, `mins` and `maxs` when there
+/// are values.
+fn group_object<K: PartialOrd + Clone>(keys: &[K], values: Option<Vec<f64>>, to_js: impl FnOnce(Vec<K>) -> JsValue) -> Result<Object, JsValue> {
+    let result = Object::new();
+    let set = |name: &str, value: JsValue| Reflect::set(&result, &JsValue::from_str(name), &value).map(drop);
+    let Some(values) = values else {
+        let (keys, counts): (Vec<K>, Vec<u32>) = array::group_counts(keys).into_iter().map(|(key, count)| (key, count as u32)).unzip();
+        set("keys", to_js(keys))?;
+        set("counts", Uint32Array::from(&counts[..]).into())?;
+        return Ok(result);
+    };
+    let groups = array::group_by(keys, &values)?;
+    let column = |aggregate: fn(&array::Group<K>) -> f64| -> JsValue {
+        Float64Array::from(&groups.iter().map(aggregate).collect::<Vec<_>>()[..]).into()
+    };
+    let counts: Vec<u32> = groups.iter().map(|group| group.count as u32).collect();
+    set("keys", to_js(groups.iter().map(|group| group.key.clone()).collect()))?;
+    set("counts", Uint32Array::from(&counts[..]).into())?;
+    set("sums", column(|group| group.sum))?;
+    set("means", column(array::Group::mean))?;
+    set("mins", column(|group| group.min))?;
+    set("maxs", column(|group| group.max))?;
+    Ok(result)
+}
+
+/// Groups `values` by the `Int32Array` `keys` and returns
+/// `{ keys, counts, sums, means, mins, maxs }`, one entry per distinct key in ascending order.
+/// Without `values` only `keys` and `counts` are returned. See [`array::group_by`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `keys` and `values` differ in length.
+#[wasm_bindgen]
+pub fn group_by_i32(keys: &[i32], values: Option<Vec<f64>>) -> Result<Object, JsValue> {
+    group_object(keys, values, |keys| Int32Array::from(&keys[..]).into())
+}
+
+/// Groups `values` by the `Float64Array` `keys`; NaN keys form the last group. See
+/// [`group_by_i32`] for the result.
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `keys` and `values` differ in length.
+#[wasm_bindgen]
+pub fn group_by_f64(keys: &[f64], values: Option<Vec<f64>>) -> Result<Object, JsValue> {
+    group_object(keys, values, |keys| Float64Array::from(&keys[..]).into())
+}
+
+/// Groups `values` by string `keys`; `keys` in the result is an array of strings. See
+/// [`group_by_i32`] for the result.
+///
+/// Throws an `Error` with code `INVALID_INPUT` if `keys` and `values` differ in length.
+#[wasm_bindgen]
+pub fn group_by_strings(keys: Vec<String>, values: Option<Vec<f64>>) -> Result<Object, JsValue> {
+    group_object(&keys, values, |keys| keys.into_iter().map(JsValue::from).collect::<Array>().into())
+}
+
+/// A seeded pseudo-random number generator for shuffles and samples that can be reproduced
+/// from their seed. See [`array::Random`].
+///
+/// ```javascript
+/// const random = new Random("checkout-test");   // or a number or a BigInt
+/// const groupB = random.sample_strings(userIds, 100);
+/// random.free();
+/// ```
+#[wasm_bindgen]
+pub struct Random {
+    inner: array::Random,
+}
+
+#[wasm_bindgen]
+impl Random {
+    /// Creates a generator from `seed`: a string, which is hashed, a `BigInt` in the signed or
+    /// unsigned 64-bit range, or a number. Integer numbers of magnitude below 2^63 are taken modulo
+    /// 2^64 like a `BigInt`; other numbers, including larger integers such as `2 ** 63`, use their
+    /// IEEE-754 bits.
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if `seed` is of another type or a `BigInt` that
+    /// does not fit in 64 bits.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: JsValue) -> Result<Random, JsValue> {
+        let inner = if let Some(text) = seed.as_string() {
+            array::Random::from_text(&text)
+        } else if let Some(number) = seed.as_f64() {
+            if number.fract() == 0.0 && number.abs() < i64::MAX as f64 {
+                array::Random::new(number as i64 as u64)
+            } else {
+                array::Random::new(number.to_bits())
+            }
+        } else if seed.is_bigint() {
+            let seed = u64::try_from(seed.clone())
+                .or_else(|_| i64::try_from(seed).map(|seed| seed as u64))
+                .map_err(|_| Error::InvalidInput("a BigInt seed must fit in 64 bits".to_string()))?;
+            array::Random::new(seed)
+        } else {
+            return Err(Error::InvalidInput("the seed must be a number, a BigInt or a string".to_string()).into());
+        };
+        Ok(Random { inner })
+    }
+
+    /// Returns a random unsigned 32-bit integer.
+    pub fn next_u32(&mut self) -> u32 {
+        self.inner.next_u32()
+    }
+
+    /// Returns a random number in `[0, 1)`, like `Math.random()`.
+    pub fn next_f64(&mut self) -> f64 {
+        self.inner.next_f64()
+    }
+
+    /// Returns a random integer in `[0, bound)`, without modulo bias. See [`array::Random::below`].
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if `bound` is 0.
+    pub fn next_int(&mut self, bound: u32) -> Result<u32, JsValue> {
+        Ok(self.inner.below(u64::from(bound))? as u32)
+    }
+
+    /// Shuffles an `Int32Array` in place. See [`array::Random::shuffle`].
+    pub fn shuffle_i32(&mut self, arr: &mut [i32]) {
+        self.inner.shuffle(arr);
+    }
+
+    /// Shuffles a `Float64Array` in place. See [`array::Random::shuffle`].
+    pub fn shuffle_f64(&mut self, arr: &mut [f64]) {
+        self.inner.shuffle(arr);
+    }
+
+    /// Returns a shuffled copy of an array of strings. See [`array::Random::shuffle`].
+    pub fn shuffle_strings(&mut self, mut arr: Vec<String>) -> Vec<String> {
+        self.inner.shuffle(&mut arr);
+        arr
+    }
+
+    /// Draws `k` elements of an `Int32Array`, without replacement unless `replacement` is
+    /// `true`. See [`array::Random::sample`].
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if there are not enough elements.
+    pub fn sample_i32(&mut self, arr: Vec<i32>, k: usize, replacement: Option<bool>) -> Result<Vec<i32>, JsValue> {
+        Ok(self.inner.sample(&arr, k, replacement.unwrap_or(false))?)
+    }
+
+    /// Draws `k` elements of a `Float64Array`, without replacement unless `replacement` is
+    /// `true`. See [`array::Random::sample`].
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if there are not enough elements.
+    pub fn sample_f64(&mut self, arr: Vec<f64>, k: usize, replacement: Option<bool>) -> Result<Vec<f64>, JsValue> {
+        Ok(self.inner.sample(&arr, k, replacement.unwrap_or(false))?)
+    }
+
+    /// Draws `k` elements of an array of strings, without replacement unless `replacement` is
+    /// `true`. See [`array::Random::sample`].
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if there are not enough elements.
+    pub fn sample_strings(&mut self, arr: Vec<String>, k: usize, replacement: Option<bool>) -> Result<Vec<String>, JsValue> {
+        Ok(self.inner.sample(&arr, k, replacement.unwrap_or(false))?)
+    }
+
+    /// Draws `k` elements of an `Int32Array` with probability proportional to `weights`. See
+    /// [`array::Random::weighted_sample`].
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if the weights are invalid or there are not
+    /// enough elements with a positive weight.
+    pub fn weighted_sample_i32(&mut self, arr: Vec<i32>, weights: Vec<f64>, k: usize, replacement: Option<bool>) -> Result<Vec<i32>, JsValue> {
+        Ok(self.inner.weighted_sample(&arr, &weights, k, replacement.unwrap_or(false))?)
+    }
+
+    /// Draws `k` elements of a `Float64Array` with probability proportional to `weights`. See
+    /// [`array::Random::weighted_sample`].
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if the weights are invalid or there are not
+    /// enough elements with a positive weight.
+    pub fn weighted_sample_f64(&mut self, arr: Vec<f64>, weights: Vec<f64>, k: usize, replacement: Option<bool>) -> Result<Vec<f64>, JsValue> {
+        Ok(self.inner.weighted_sample(&arr, &weights, k, replacement.unwrap_or(false))?)
+    }
+
+    /// Draws `k` elements of an array of strings with probability proportional to `weights`.
+    /// See [`array::Random::weighted_sample`].
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if the weights are invalid or there are not
+    /// enough elements with a positive weight.
+    pub fn weighted_sample_strings(&mut self, arr: Vec<String>, weights: Vec<f64>, k: usize, replacement: Option<bool>) -> Result<Vec<String>, JsValue> {
+        Ok(self.inner.weighted_sample(&arr, &weights, k, replacement.unwrap_or(false))?)
+    }
+
+    /// Draws `k` elements of an `Int32Array` in one pass. See [`array::Random::reservoir_sample`].
+    pub fn reservoir_sample_i32(&mut self, arr: &[i32], k: usize) -> Vec<i32> {
+        self.inner.reservoir_sample(arr.iter().copied(), k)
+    }
+
+    /// Draws `k` elements of a `Float64Array` in one pass. See [`array::Random::reservoir_sample`].
+    pub fn reservoir_sample_f64(&mut self, arr: &[f64], k: usize) -> Vec<f64> {
+        self.inner.reservoir_sample(arr.iter().copied(), k)
+    }
+
+    /// Draws `k` elements of an array of strings in one pass. See
+    /// [`array::Random::reservoir_sample`].
+    pub fn reservoir_sample_strings(&mut self, arr: Vec<String>, k: usize) -> Vec<String> {
+        self.inner.reservoir_sample(arr, k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn unknown_option_strings_are_rejected() {
+        assert_eq!(array::SortOrder::try_from(None), Ok(array::SortOrder::Ascending));
+        assert_eq!(array::SortOrder::try_from(Some(SortOrder::Desc)), Ok(array::SortOrder::Descending));
+        // What wasm-bindgen passes for a string such as "descending"
+        assert!(matches!(array::SortOrder::try_from(Some(SortOrder::__Invalid)), Err(Error::InvalidInput(_))));
+
+        // `top_k` defaults to the largest elements but rejects the same strings
+        assert_eq!(top_k_order(None), Ok(array::SortOrder::Descending));
+        assert_eq!(top_k_order(Some(SortOrder::Asc)), Ok(array::SortOrder::Ascending));
+        assert!(matches!(top_k_order(Some(SortOrder::__Invalid)), Err(Error::InvalidInput(_))));
+    }
+}
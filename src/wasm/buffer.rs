@@ -0,0 +1,134 @@
+use js_sys::{Float64Array, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use crate::error::Error;
+
+/// A block of WebAssembly memory that JavaScript can read and write without copying.
+///
+/// Regular exports such as `grayscale(input: Uint8Array)` copy their input into wasm memory
+/// and their result back out. For large images or compression jobs those copies dominate the
+/// runtime. A `WasmBuffer` is allocated once inside wasm memory; JavaScript fills it through a
+/// typed-array view and passes the buffer itself to the `*_buffer` exports, which work on it in
+/// place.
+///
+/// The storage is 8-byte aligned, so the same bytes can also be viewed as a `Float64Array`.
+///
+/// ```javascript
+/// const buf = new WasmBuffer(imageData.data.length);
+/// buf.as_uint8_array().set(imageData.data);   // one copy in
+/// grayscale_buffer(buf);                      // no copies
+/// imageData.data.set(buf.as_uint8_array());   // one copy out
+/// buf.free();
+/// ```
+///
+/// # Views and memory growth
+///
+/// The views returned by `as_uint8_array` and `as_float64_array` point directly into wasm
+/// memory. Any allocation on the Rust side may grow the memory, which detaches every existing
+/// view, so take a fresh view after calling other exports instead of keeping one around.
+///
+/// Memory is released by calling `free()` (generated by wasm-bindgen); a buffer that is never
+/// freed stays allocated.
+#[wasm_bindgen]
+pub struct WasmBuffer {
+    /// `u64` words so that the bytes are 8-byte aligned.
+    words: Vec<u64>,
+    /// Length in bytes; the last word may be only partially used.
+    byte_length: usize,
+}
+
+#[wasm_bindgen]
+impl WasmBuffer {
+    /// Allocates a zero-filled buffer of `byte_length` bytes in wasm memory.
+    #[wasm_bindgen(constructor)]
+    pub fn new(byte_length: usize) -> WasmBuffer {
+        WasmBuffer {
+            words: vec![0; byte_length.div_ceil(8)],
+            byte_length,
+        }
+    }
+
+    /// Length of the buffer in bytes.
+    #[wasm_bindgen(getter)]
+    pub fn byte_length(&self) -> usize {
+        self.byte_length
+    }
+
+    /// Byte offset of the buffer inside `memory.buffer`, for callers that build their own views.
+    #[wasm_bindgen(getter)]
+    pub fn byte_offset(&self) -> usize {
+        self.words.as_ptr() as usize
+    }
+
+    /// Returns a `Uint8Array` viewing the buffer's bytes (no copy).
+    pub fn as_uint8_array(&self) -> Uint8Array {
+        // Safety: the view is only valid until wasm memory grows, as documented on the type
+        unsafe { Uint8Array::view(self.as_slice()) }
+    }
+
+    /// Returns a `Float64Array` viewing the buffer's bytes (no copy).
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if the byte length is not a multiple of 8.
+    pub fn as_float64_array(&self) -> Result<Float64Array, JsValue> {
+        if !self.byte_length.is_multiple_of(8) {
+            return Err(Error::InvalidInput(format!(
+                "buffer length {} is not a multiple of 8 bytes",
+                self.byte_length
+            ))
+            .into());
+        }
+
+        // Safety: `u64` and `f64` have the same size and alignment, and every bit pattern is a
+        // valid `f64`. The view is only valid until wasm memory grows.
+        let floats = unsafe { std::slice::from_raw_parts(self.words.as_ptr() as *const f64, self.byte_length / 8) };
+        Ok(unsafe { Float64Array::view(floats) })
+    }
+}
+
+impl WasmBuffer {
+    /// Copies `bytes` into a newly allocated buffer. Used for results whose size is only known
+    /// once they have been computed (e.g. compression output).
+    #[cfg(feature = "crypto")]
+    pub(crate) fn from_bytes(bytes: &[u8]) -> WasmBuffer {
+        let mut buffer = WasmBuffer::new(bytes.len());
+        buffer.as_mut_slice().copy_from_slice(bytes);
+        buffer
+    }
+
+    /// The buffer's contents as bytes.
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        // Safety: `words` owns at least `byte_length` initialized bytes
+        unsafe { std::slice::from_raw_parts(self.words.as_ptr() as *const u8, self.byte_length) }
+    }
+
+    /// The buffer's contents as mutable bytes.
+    #[cfg(any(feature = "byte", feature = "crypto", feature = "images"))]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [u8] {
+        // Safety: `words` owns at least `byte_length` initialized bytes
+        unsafe { std::slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u8, self.byte_length) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[test]
+    fn storage_is_aligned_and_sized_in_bytes() {
+        let buffer = WasmBuffer::new(13);
+        assert_eq!(buffer.byte_length(), 13);
+        assert_eq!(buffer.as_slice(), &[0; 13]);
+        assert_eq!(buffer.byte_offset() % 8, 0);
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn from_bytes_round_trip() {
+        let mut buffer = WasmBuffer::from_bytes(&[1, 2, 3]);
+        buffer.as_mut_slice()[1] = 9;
+        assert_eq!(buffer.as_slice(), &[1, 9, 3]);
+    }
+}
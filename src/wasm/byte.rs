@@ -0,0 +1,293 @@
+use wasm_bindgen::prelude::*;
+
+use super::WasmBuffer;
+use crate::byte;
+use crate::error::Error;
+
+/// Counts the zero bits of a `Uint8Array`. See [`byte::count_zero_bits`].
+#[wasm_bindgen]
+pub fn count_zero_bits(data: &[u8]) -> u32 {
+    byte::count_zero_bits(data)
+}
+
+/// What the bitwise functions do when their inputs have different lengths: `"error"`,
+/// `"truncate"` (stop at the shorter input) or `"cycle"` (repeat the second input as a key).
+/// See [`byte::LengthPolicy`].
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum LengthPolicy {
+    Error = "error",
+    Truncate = "truncate",
+    Cycle = "cycle",
+}
+
+/// Converts `policy`, rejecting strings that are not one of the policies instead of falling back
+/// to `default`.
+fn length_policy(policy: Option<LengthPolicy>, default: byte::LengthPolicy) -> Result<byte::LengthPolicy, JsValue> {
+    match policy {
+        None => Ok(default),
+        Some(LengthPolicy::Error) => Ok(byte::LengthPolicy::Error),
+        Some(LengthPolicy::Truncate) => Ok(byte::LengthPolicy::Truncate),
+        Some(LengthPolicy::Cycle) => Ok(byte::LengthPolicy::CycleKey),
+        // wasm-bindgen maps any other string to a hidden variant
+        Some(_) => Err(Error::InvalidInput("policy must be \"error\", \"truncate\" or \"cycle\"".to_string()).into()),
+    }
+}
+
+/// XORs two `Uint8Array`s byte by byte; `policy` defaults to `"error"` like the other bitwise
+/// functions (pass `"truncate"` to stop at the shorter input). See [`byte::bitwise`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the lengths do not fit `policy`, or if
+/// `policy` is not one of the policies.
+#[wasm_bindgen]
+pub fn xor_bytes(data1: &[u8], data2: &[u8], policy: Option<LengthPolicy>) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::bitwise(data1, data2, byte::BitOp::Xor, length_policy(policy, byte::LengthPolicy::Error)?)?)
+}
+
+/// ANDs two `Uint8Array`s byte by byte; `policy` defaults to `"error"`. See [`byte::bitwise`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the lengths do not fit `policy`, or if
+/// `policy` is not one of the policies.
+#[wasm_bindgen]
+pub fn and_bytes(a: &[u8], b: &[u8], policy: Option<LengthPolicy>) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::bitwise(a, b, byte::BitOp::And, length_policy(policy, byte::LengthPolicy::Error)?)?)
+}
+
+/// ORs two `Uint8Array`s byte by byte; `policy` defaults to `"error"`. See [`byte::bitwise`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the lengths do not fit `policy`, or if
+/// `policy` is not one of the policies.
+#[wasm_bindgen]
+pub fn or_bytes(a: &[u8], b: &[u8], policy: Option<LengthPolicy>) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::bitwise(a, b, byte::BitOp::Or, length_policy(policy, byte::LengthPolicy::Error)?)?)
+}
+
+/// Computes `a & ~b` byte by byte (the bits of `a` not set in `b`); `policy` defaults to
+/// `"error"`. See [`byte::bitwise`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the lengths do not fit `policy`, or if
+/// `policy` is not one of the policies.
+#[wasm_bindgen]
+pub fn andnot_bytes(a: &[u8], b: &[u8], policy: Option<LengthPolicy>) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::bitwise(a, b, byte::BitOp::AndNot, length_policy(policy, byte::LengthPolicy::Error)?)?)
+}
+
+/// Inverts every bit of a `Uint8Array`. See [`byte::not_bytes`].
+#[wasm_bindgen]
+pub fn not_bytes(data: &[u8]) -> Vec<u8> {
+    byte::not_bytes(data)
+}
+
+/// Shifts a `Uint8Array`, read as one big-endian number, left by `bits` bits. See
+/// [`byte::shift_left`].
+#[wasm_bindgen]
+pub fn shift_left_bytes(data: &[u8], bits: usize) -> Vec<u8> {
+    byte::shift_left(data, bits)
+}
+
+/// Shifts a `Uint8Array`, read as one big-endian number, right by `bits` bits. See
+/// [`byte::shift_right`].
+#[wasm_bindgen]
+pub fn shift_right_bytes(data: &[u8], bits: usize) -> Vec<u8> {
+    byte::shift_right(data, bits)
+}
+
+/// Counts the one bits of a `Uint8Array`. See [`byte::count_one_bits`].
+#[wasm_bindgen]
+pub fn count_one_bits(data: &[u8]) -> u32 {
+    byte::count_one_bits(data)
+}
+
+/// Counts the one bits of a `Uint8Array` between bit `start` (included) and bit `end`
+/// (excluded), bit 0 being the most significant bit of the first byte. See
+/// [`byte::count_one_bits_in_range`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the range is reversed or past the end.
+#[wasm_bindgen]
+pub fn count_one_bits_in_range(data: &[u8], start: usize, end: usize) -> Result<usize, JsValue> {
+    Ok(byte::count_one_bits_in_range(data, start..end)?)
+}
+
+/// Counts the zero bits of a `Uint8Array` between bit `start` and bit `end`. See
+/// [`count_one_bits_in_range`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the range is reversed or past the end.
+#[wasm_bindgen]
+pub fn count_zero_bits_in_range(data: &[u8], start: usize, end: usize) -> Result<usize, JsValue> {
+    Ok(byte::count_zero_bits_in_range(data, start..end)?)
+}
+
+/// Counts the zero bits of a [`WasmBuffer`] without copying it.
+#[wasm_bindgen]
+pub fn count_zero_bits_buffer(buffer: &WasmBuffer) -> u32 {
+    byte::count_zero_bits(buffer.as_slice())
+}
+
+/// XORs `key` into `buffer` in place and returns the number of bytes modified. See
+/// [`byte::xor_bytes_in_place`].
+#[wasm_bindgen]
+pub fn xor_bytes_buffer(buffer: &mut WasmBuffer, key: &WasmBuffer) -> usize {
+    byte::xor_bytes_in_place(buffer.as_mut_slice(), key.as_slice())
+}
+
+/// Encodes a `Uint8Array` as hexadecimal, lowercase unless `upper` is `true`. See
+/// [`byte::hex_encode`].
+#[wasm_bindgen]
+pub fn hex_encode(data: &[u8], upper: Option<bool>) -> String {
+    let case = if upper.unwrap_or(false) { byte::HexCase::Upper } else { byte::HexCase::Lower };
+    byte::hex_encode(data, case)
+}
+
+/// Decodes hexadecimal text (either case) into a `Uint8Array`. See [`byte::hex_decode`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` on an odd length or a non-hex character.
+#[wasm_bindgen]
+pub fn hex_decode(text: &str) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::hex_decode(text)?)
+}
+
+/// Encodes a `Uint8Array` as standard Base64, padded with `=` unless `padding` is `false`. See
+/// [`byte::base64_encode`].
+#[wasm_bindgen]
+pub fn base64_encode(data: &[u8], padding: Option<bool>) -> String {
+    byte::base64_encode(data, byte::Base64Alphabet::Standard, padding.unwrap_or(true))
+}
+
+/// Decodes standard Base64, which must be padded unless `padding` is `false`. See
+/// [`byte::base64_decode`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the text is not canonical Base64.
+#[wasm_bindgen]
+pub fn base64_decode(text: &str, padding: Option<bool>) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::base64_decode(text, byte::Base64Alphabet::Standard, padding.unwrap_or(true))?)
+}
+
+/// Encodes a `Uint8Array` as URL-safe Base64, unpadded unless `padding` is `true`. See
+/// [`byte::base64_encode`].
+#[wasm_bindgen]
+pub fn base64url_encode(data: &[u8], padding: Option<bool>) -> String {
+    byte::base64_encode(data, byte::Base64Alphabet::UrlSafe, padding.unwrap_or(false))
+}
+
+/// Decodes URL-safe Base64, which must be unpadded unless `padding` is `true`. See
+/// [`byte::base64_decode`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the text is not canonical Base64URL.
+#[wasm_bindgen]
+pub fn base64url_decode(text: &str, padding: Option<bool>) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::base64_decode(text, byte::Base64Alphabet::UrlSafe, padding.unwrap_or(false))?)
+}
+
+/// Encodes a `Uint8Array` as RFC 4648 Base32, padded unless `padding` is `false`. See
+/// [`byte::base32_encode`].
+#[wasm_bindgen]
+pub fn base32_encode(data: &[u8], padding: Option<bool>) -> String {
+    byte::base32_encode(data, padding.unwrap_or(true))
+}
+
+/// Decodes RFC 4648 Base32, which must be padded unless `padding` is `false`. See
+/// [`byte::base32_decode`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the text is not canonical Base32.
+#[wasm_bindgen]
+pub fn base32_decode(text: &str, padding: Option<bool>) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::base32_decode(text, padding.unwrap_or(true))?)
+}
+
+/// Encodes a `Uint8Array` as Crockford's Base32. See [`byte::crockford_base32_encode`].
+#[wasm_bindgen]
+pub fn crockford_base32_encode(data: &[u8]) -> String {
+    byte::crockford_base32_encode(data)
+}
+
+/// Decodes Crockford's Base32 (case-insensitive, hyphens ignored). See
+/// [`byte::crockford_base32_decode`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` on an invalid character or trailing bits.
+#[wasm_bindgen]
+pub fn crockford_base32_decode(text: &str) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::crockford_base32_decode(text)?)
+}
+
+/// Encodes a `Uint8Array` as Base58 with the Bitcoin alphabet. See [`byte::base58_encode`].
+#[wasm_bindgen]
+pub fn base58_encode(data: &[u8]) -> String {
+    byte::base58_encode(data)
+}
+
+/// Decodes Base58 with the Bitcoin alphabet. See [`byte::base58_decode`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` on a character outside the alphabet.
+#[wasm_bindgen]
+pub fn base58_decode(text: &str) -> Result<Vec<u8>, JsValue> {
+    Ok(byte::base58_decode(text)?)
+}
+
+/// A fixed-size set of bits with rank and select queries. See [`byte::Bitset`].
+///
+/// ```javascript
+/// const seen = new Bitset(userCount);
+/// seen.set(42);
+/// const before = seen.rank(100);   // set bits in 0..100
+/// const ids = seen.ones();         // Uint32Array of the set bits
+/// seen.free();
+/// ```
+#[wasm_bindgen]
+pub struct Bitset {
+    inner: byte::Bitset,
+}
+
+#[wasm_bindgen]
+impl Bitset {
+    /// Creates a set of `length` bits, all clear.
+    #[wasm_bindgen(constructor)]
+    pub fn new(length: usize) -> Bitset {
+        Bitset { inner: byte::Bitset::new(length) }
+    }
+
+    /// Number of bits in the set.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Sets bit `index`.
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if `index >= length`, here and in `clear`
+    /// and `test`.
+    pub fn set(&mut self, index: usize) -> Result<(), JsValue> {
+        Ok(self.inner.set(index)?)
+    }
+
+    /// Clears bit `index`.
+    pub fn clear(&mut self, index: usize) -> Result<(), JsValue> {
+        Ok(self.inner.clear(index)?)
+    }
+
+    /// Returns `true` if bit `index` is set.
+    pub fn test(&self, index: usize) -> Result<bool, JsValue> {
+        Ok(self.inner.test(index)?)
+    }
+
+    /// Number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.inner.count_ones()
+    }
+
+    /// Number of set bits before `index`. See [`byte::Bitset::rank`].
+    ///
+    /// Throws an `Error` with code `INVALID_INPUT` if `index > length`.
+    pub fn rank(&self, index: usize) -> Result<usize, JsValue> {
+        Ok(self.inner.rank(index)?)
+    }
+
+    /// Index of the set bit with rank `k`, or `undefined`. See [`byte::Bitset::select`].
+    pub fn select(&self, k: usize) -> Option<usize> {
+        self.inner.select(k)
+    }
+
+    /// Returns the indices of the set bits in increasing order as a `Uint32Array`.
+    pub fn ones(&self) -> Vec<u32> {
+        self.inner.ones().map(|i| i as u32).collect()
+    }
+}
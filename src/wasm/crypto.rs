@@ -0,0 +1,114 @@
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "parallel")]
+use js_sys::Uint8Array;
+
+use super::progress::JsProgress;
+use super::WasmBuffer;
+use crate::crypto;
+
+/// Computes the CRC32 checksum of a `Uint8Array`. See [`crypto::calculate_crc32`].
+#[wasm_bindgen]
+pub fn calculate_crc32(data: &[u8]) -> u32 {
+    crypto::calculate_crc32(data)
+}
+
+/// LZ4-compresses a `Uint8Array` with the original size prepended. See [`crypto::compress_data`].
+#[wasm_bindgen]
+pub fn compress_data(data: &[u8]) -> Vec<u8> {
+    crypto::compress_data(data)
+}
+
+/// Decompresses the output of `compress_data`. See [`crypto::decompress_data`].
+///
+/// Throws an `Error` with code `DECOMPRESSION_FAILED` on invalid input.
+#[wasm_bindgen]
+pub fn decompress_data(data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Ok(crypto::decompress_data(data)?)
+}
+
+/// Deflate-compresses a `Uint8Array`. See [`crypto::deflate_compress`].
+#[wasm_bindgen]
+pub fn deflate_compress(data: &[u8]) -> Vec<u8> {
+    crypto::deflate_compress(data)
+}
+
+/// Decompresses a raw Deflate stream. See [`crypto::deflate_decompress`].
+///
+/// Throws an `Error` with code `DECOMPRESSION_FAILED` on invalid input.
+#[wasm_bindgen]
+pub fn deflate_decompress(data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Ok(crypto::deflate_decompress(data)?)
+}
+
+/// Deflate-compresses a `Uint8Array` like `deflate_compress`, calling
+/// `on_progress(fraction, bytes_consumed)` every 64 KiB of input or output and stopping once
+/// `abort` is set. See [`crypto::deflate_compress_with_progress`].
+///
+/// Throws an `Error` with code `CANCELLED` when cancelled, or `COMPRESSION_FAILED` if the
+/// compressor fails internally.
+#[wasm_bindgen]
+pub fn deflate_compress_with_progress(data: &[u8], on_progress: JsValue, abort: JsValue) -> Result<Vec<u8>, JsValue> {
+    let mut progress = JsProgress::new(on_progress, abort)?;
+    crypto::deflate_compress_with_progress(data, &mut progress).map_err(|e| progress.error(e))
+}
+
+/// Decompresses a raw Deflate stream like `deflate_decompress`, calling
+/// `on_progress(fraction, bytes_written)` every 64 KiB of output and stopping once `abort` is set.
+/// See [`crypto::deflate_decompress_with_progress`].
+///
+/// Throws an `Error` with code `CANCELLED` when cancelled, or `DECOMPRESSION_FAILED`.
+#[wasm_bindgen]
+pub fn deflate_decompress_with_progress(data: &[u8], on_progress: JsValue, abort: JsValue) -> Result<Vec<u8>, JsValue> {
+    let mut progress = JsProgress::new(on_progress, abort)?;
+    crypto::deflate_decompress_with_progress(data, &mut progress).map_err(|e| progress.error(e))
+}
+
+/// Computes the SHA-256 digest of a `Uint8Array`. See [`crypto::sha256_hash`].
+#[wasm_bindgen]
+pub fn sha256_hash(input: &[u8]) -> Vec<u8> {
+    crypto::sha256_hash(input).to_vec()
+}
+
+/// Computes the SHA-256 digest of every `Uint8Array` in `inputs`, using the worker pool when
+/// available. Returns one 32-byte `Uint8Array` per input. See [`crypto::sha256_hash_many`].
+#[cfg(feature = "parallel")]
+#[wasm_bindgen]
+pub fn sha256_hash_many(inputs: Vec<Uint8Array>) -> Vec<Uint8Array> {
+    let buffers: Vec<Vec<u8>> = inputs.iter().map(Uint8Array::to_vec).collect();
+    crypto::sha256_hash_many(&buffers)
+        .iter()
+        .map(|digest| Uint8Array::from(&digest[..]))
+        .collect()
+}
+
+/// LZ4-compresses a [`WasmBuffer`] into a new buffer that stays in wasm memory. See
+/// [`crypto::compress_data`].
+#[wasm_bindgen]
+pub fn compress_data_buffer(buffer: &WasmBuffer) -> WasmBuffer {
+    WasmBuffer::from_bytes(&crypto::compress_data(buffer.as_slice()))
+}
+
+/// Decompresses a [`WasmBuffer`] produced by `compress_data` into a new buffer. See
+/// [`crypto::decompress_data`].
+///
+/// Throws an `Error` with code `DECOMPRESSION_FAILED` on invalid input.
+#[wasm_bindgen]
+pub fn decompress_data_buffer(buffer: &WasmBuffer) -> Result<WasmBuffer, JsValue> {
+    Ok(WasmBuffer::from_bytes(&crypto::decompress_data(buffer.as_slice())?))
+}
+
+/// Deflate-compresses a [`WasmBuffer`] into a new buffer. See [`crypto::deflate_compress`].
+#[wasm_bindgen]
+pub fn deflate_compress_buffer(buffer: &WasmBuffer) -> WasmBuffer {
+    WasmBuffer::from_bytes(&crypto::deflate_compress(buffer.as_slice()))
+}
+
+/// Decompresses a raw Deflate stream held in a [`WasmBuffer`] into a new buffer. See
+/// [`crypto::deflate_decompress`].
+///
+/// Throws an `Error` with code `DECOMPRESSION_FAILED` on invalid input.
+#[wasm_bindgen]
+pub fn deflate_decompress_buffer(buffer: &WasmBuffer) -> Result<WasmBuffer, JsValue> {
+    Ok(WasmBuffer::from_bytes(&crypto::deflate_decompress(buffer.as_slice())?))
+}
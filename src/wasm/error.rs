@@ -0,0 +1,27 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::Error;
+
+/// Converts a library error into a JavaScript `Error`.
+///
+/// The resulting object is a real `Error` instance (so `instanceof Error` and stack traces
+/// work) with two extra properties:
+///
+/// * `code` - the stable code from [`Error::code`], e.g. `"DECOMPRESSION_FAILED"`.
+/// * `message` - the human readable description.
+///
+/// ```javascript
+/// try {
+///     decompress_data(new Uint8Array([1, 2, 3]));
+/// } catch (e) {
+///     console.log(e.code, e.message);
+/// }
+/// ```
+impl From<Error> for JsValue {
+    fn from(error: Error) -> JsValue {
+        let js_error = js_sys::Error::new(&error.to_string());
+        // Setting a property on a freshly created `Error` object cannot fail
+        let _ = js_sys::Reflect::set(&js_error, &JsValue::from_str("code"), &JsValue::from_str(error.code()));
+        js_error.into()
+    }
+}
@@ -0,0 +1,51 @@
+use wasm_bindgen::prelude::*;
+
+use super::WasmBuffer;
+use crate::images;
+
+/// Inverts the bytes of a `Uint8Array` in place. See [`images::invert_colors`].
+#[wasm_bindgen]
+pub fn invert_colors(bytes: &mut [u8]) {
+    images::invert_colors(bytes);
+}
+
+/// Converts RGBA pixels to grayscale, keeping alpha. See [`images::grayscale`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the length is not a multiple of 4.
+#[wasm_bindgen]
+pub fn grayscale(input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Ok(images::grayscale(input)?)
+}
+
+/// Inverts a `Uint8Array` in place using the worker pool when available. See
+/// [`images::invert_colors_parallel`].
+#[cfg(feature = "parallel")]
+#[wasm_bindgen]
+pub fn invert_colors_parallel(bytes: &mut [u8]) {
+    images::invert_colors_parallel(bytes);
+}
+
+/// Converts RGBA pixels to grayscale using the worker pool when available. See
+/// [`images::grayscale_parallel`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the length is not a multiple of 4.
+#[cfg(feature = "parallel")]
+#[wasm_bindgen]
+pub fn grayscale_parallel(input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Ok(images::grayscale_parallel(input)?)
+}
+
+/// Inverts the bytes of a [`WasmBuffer`] in place, without copying them across the JS boundary.
+#[wasm_bindgen]
+pub fn invert_colors_buffer(buffer: &mut WasmBuffer) {
+    images::invert_colors(buffer.as_mut_slice());
+}
+
+/// Converts the RGBA pixels of a [`WasmBuffer`] to grayscale in place. See
+/// [`images::grayscale_in_place`].
+///
+/// Throws an `Error` with code `INVALID_INPUT` if the length is not a multiple of 4.
+#[wasm_bindgen]
+pub fn grayscale_buffer(buffer: &mut WasmBuffer) -> Result<(), JsValue> {
+    Ok(images::grayscale_in_place(buffer.as_mut_slice())?)
+}
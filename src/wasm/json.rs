@@ -0,0 +1,25 @@
+use wasm_bindgen::prelude::*;
+
+use super::progress::JsProgress;
+use crate::json;
+
+/// Parses CSV text into an array of JSON strings, one per record. See [`json::parse_csv_to_json`].
+///
+/// Throws an `Error` with code `CSV_PARSE_FAILED` or `SERIALIZATION_FAILED`.
+#[wasm_bindgen]
+pub fn parse_csv_to_json(content: String) -> Result<Vec<JsValue>, JsValue> {
+    let records = json::parse_csv_to_json(&content)?;
+    Ok(records.iter().map(|record| JsValue::from_str(record)).collect())
+}
+
+/// Parses CSV text like `parse_csv_to_json`, calling `on_progress(fraction, records)` every 1024
+/// records and stopping once `abort` is set. See [`json::parse_csv_to_json_with_progress`].
+///
+/// Throws an `Error` with code `CANCELLED` when cancelled, or `CSV_PARSE_FAILED` /
+/// `SERIALIZATION_FAILED`.
+#[wasm_bindgen]
+pub fn parse_csv_to_json_with_progress(content: String, on_progress: JsValue, abort: JsValue) -> Result<Vec<JsValue>, JsValue> {
+    let mut progress = JsProgress::new(on_progress, abort)?;
+    let records = json::parse_csv_to_json_with_progress(&content, &mut progress).map_err(|e| progress.error(e))?;
+    Ok(records.iter().map(|record| JsValue::from_str(record)).collect())
+}
@@ -0,0 +1,33 @@
+use wasm_bindgen::prelude::*;
+
+use crate::math;
+
+/// Computes the mean of a `Float64Array`. See [`math::calculate_mean`].
+#[wasm_bindgen]
+pub fn calculate_mean(data: &[f64]) -> f64 {
+    math::calculate_mean(data)
+}
+
+/// Sums an `Int32Array`. See [`math::sum`].
+#[wasm_bindgen]
+pub fn sum(numbers: Vec<i32>) -> i32 {
+    math::sum(&numbers)
+}
+
+/// Computes `n!`. See [`math::factorial`].
+#[wasm_bindgen]
+pub fn factorial(n: u32) -> u32 {
+    math::factorial(n)
+}
+
+/// Returns the largest element of an `Int32Array`, or -1 if it is empty.
+#[wasm_bindgen]
+pub fn max(arr: &[i32]) -> i32 {
+    math::max(arr).unwrap_or(-1) // Return -1 if the array is empty
+}
+
+/// Returns the smallest element of an `Int32Array`, or -1 if it is empty.
+#[wasm_bindgen]
+pub fn min(arr: &[i32]) -> i32 {
+    math::min(arr).unwrap_or(-1) // Return -1 if the array is empty
+}
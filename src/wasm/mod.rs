@@ -0,0 +1,65 @@
+//! WebAssembly bindings.
+//!
+//! Every function in these modules is a thin `#[wasm_bindgen]` wrapper over the native API
+//! of the module with the same name (e.g. `wasm::array::quick_sort` wraps
+//! `array::quick_sort`). The wrappers only convert between JS-shaped types and Rust types;
+//! the algorithms themselves live in the native modules.
+//!
+//! Fallible exports return `Result<_, JsValue>`; the error is a JS `Error` with `code` and
+//! `message` properties (see `error.rs`), so a failure throws instead of aborting the
+//! whole wasm instance.
+
+mod buffer;
+mod error;
+#[cfg(any(feature = "array", feature = "crypto", feature = "json"))]
+mod progress;
+
+pub use buffer::WasmBuffer;
+
+#[cfg(feature = "array")]
+pub mod array;
+#[cfg(feature = "byte")]
+pub mod byte;
+#[cfg(feature = "crypto")]
+pub mod crypto;
+#[cfg(feature = "images")]
+pub mod images;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "math")]
+pub mod math;
+#[cfg(feature = "string")]
+pub mod string;
+
+use wasm_bindgen::prelude::*;
+
+use console_error_panic_hook::set_once;
+
+#[wasm_bindgen(start)]
+pub fn main() {
+    set_once(); // Set panic hook for better error messages
+}
+
+/// Starts the Web Worker pool used by the `*_parallel` exports and resolves once it is ready.
+///
+/// Only available in the `wasm-threads` build (nightly, `+atomics,+bulk-memory`, served with the
+/// COOP/COEP headers that enable `SharedArrayBuffer`). Until the returned promise resolves, and in
+/// regular builds where this function does not exist, the parallel exports run on the calling
+/// thread.
+///
+/// ```javascript
+/// import init, { init_thread_pool, grayscale_parallel } from './pkg/rust_scripts.js';
+///
+/// await init();
+/// await init_thread_pool(navigator.hardwareConcurrency);
+/// const gray = grayscale_parallel(imageData.data);
+/// ```
+#[cfg(all(feature = "wasm-threads", target_arch = "wasm32"))]
+#[wasm_bindgen]
+pub fn init_thread_pool(num_threads: usize) -> js_sys::Promise {
+    let on_ready = Closure::once(|_: JsValue| crate::parallel::mark_pool_ready());
+    let ready = wasm_bindgen_rayon::init_thread_pool(num_threads).then(&on_ready);
+    // The promise keeps a reference to the callback; leak it instead of dropping it early
+    on_ready.forget();
+    ready
+}
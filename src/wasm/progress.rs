@@ -0,0 +1,83 @@
+use js_sys::{Atomics, Function, Int32Array, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::error::Error;
+use crate::progress::Progress;
+
+/// Adapts the `(on_progress, abort)` arguments of the `*_with_progress` exports to [`Progress`].
+///
+/// * `on_progress` is called as `on_progress(fraction, processed)`. Returning `false` cancels the
+///   operation; any other return value (including `undefined`) continues it. If it throws, the
+///   operation stops and the exception is rethrown to the caller. `null`/`undefined` disables
+///   reporting.
+/// * `abort` is checked at every update and cancels the operation once set. It can be an
+///   `AbortSignal` (or any object with an `aborted` property), or an `Int32Array` whose first
+///   element is set to a non-zero value, e.g. from another worker through a `SharedArrayBuffer`
+///   with `Atomics.store`. `null`/`undefined` means no abort token.
+///
+/// A cancelled operation throws an `Error` with code `CANCELLED`.
+///
+/// ```javascript
+/// const controller = new AbortController();
+/// const records = parse_csv_to_json_with_progress(text, (fraction, records) => {
+///     progressBar.value = fraction;
+///     if (records > limit) controller.abort();
+/// }, controller.signal);
+/// ```
+pub(crate) struct JsProgress {
+    on_progress: Option<Function>,
+    abort: JsValue,
+    /// Exception thrown by `on_progress`, rethrown instead of the `CANCELLED` error.
+    exception: Option<JsValue>,
+}
+
+impl JsProgress {
+    pub(crate) fn new(on_progress: JsValue, abort: JsValue) -> Result<JsProgress, JsValue> {
+        let on_progress = if on_progress.is_undefined() || on_progress.is_null() {
+            None
+        } else {
+            Some(on_progress.dyn_into::<Function>().map_err(|_| {
+                JsValue::from(Error::InvalidInput("on_progress must be a function".to_string()))
+            })?)
+        };
+        Ok(JsProgress { on_progress, abort, exception: None })
+    }
+
+    /// Converts the error of a `*_with_progress` call, preferring an exception thrown by
+    /// `on_progress` over the generic `CANCELLED` error it caused.
+    pub(crate) fn error(&mut self, error: Error) -> JsValue {
+        match (error, self.exception.take()) {
+            (Error::Cancelled, Some(exception)) => exception,
+            (error, _) => error.into(),
+        }
+    }
+
+    fn aborted(&self) -> bool {
+        if self.abort.is_undefined() || self.abort.is_null() {
+            return false;
+        }
+        if let Some(flags) = self.abort.dyn_ref::<Int32Array>() {
+            return Atomics::load(flags, 0).map(|flag| flag != 0).unwrap_or(false);
+        }
+        Reflect::get(&self.abort, &JsValue::from_str("aborted"))
+            .map(|aborted| aborted.is_truthy())
+            .unwrap_or(false)
+    }
+}
+
+impl Progress for JsProgress {
+    fn update(&mut self, fraction: f64, processed: usize) -> bool {
+        if let Some(on_progress) = &self.on_progress {
+            match on_progress.call2(&JsValue::NULL, &JsValue::from_f64(fraction), &JsValue::from_f64(processed as f64)) {
+                Ok(result) if result.as_bool() == Some(false) => return false,
+                Ok(_) => {}
+                Err(exception) => {
+                    self.exception = Some(exception);
+                    return false;
+                }
+            }
+        }
+        !self.aborted()
+    }
+}
@@ -0,0 +1,31 @@
+use wasm_bindgen::prelude::*;
+
+use serde_wasm_bindgen::to_value;
+
+use crate::error::Error;
+use crate::string;
+
+/// Counts word occurrences and returns them as a plain JS object. See [`string::word_frequency`].
+///
+/// Throws an `Error` with code `SERIALIZATION_FAILED` if the result cannot be converted.
+#[wasm_bindgen]
+pub fn word_frequency(text: &str) -> Result<JsValue, JsValue> {
+    let freq = string::word_frequency(text);
+
+    // Serialize the HashMap to a JsValue using serde_wasm_bindgen::to_value
+    let value = to_value(&freq).map_err(|e| Error::Serialization(e.to_string()))?;
+    Ok(value)
+}
+
+/// Counts word occurrences using the worker pool when available. See
+/// [`string::word_frequency_parallel`].
+///
+/// Throws an `Error` with code `SERIALIZATION_FAILED` if the result cannot be converted.
+#[cfg(feature = "parallel")]
+#[wasm_bindgen]
+pub fn word_frequency_parallel(text: &str) -> Result<JsValue, JsValue> {
+    let freq = string::word_frequency_parallel(text);
+
+    let value = to_value(&freq).map_err(|e| Error::Serialization(e.to_string()))?;
+    Ok(value)
+}
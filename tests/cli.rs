@@ -0,0 +1,65 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the `rust-scripts` binary with `args`, feeding `stdin` to it.
+fn run(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rust-scripts"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start rust-scripts");
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn hash_prints_hex_digest() {
+    let output = run(&["hash"], b"abc");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad\n"
+    );
+}
+
+#[test]
+fn crc32_matches_library() {
+    let output = run(&["crc32", "--hex"], b"Hello, world!");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "ebe6c6e6\n");
+}
+
+#[test]
+fn compress_round_trip() {
+    for format in ["lz4", "deflate"] {
+        let data = b"round trip round trip round trip".to_vec();
+        let compressed = run(&["compress", "--format", format], &data);
+        assert!(compressed.status.success());
+        let restored = run(&["decompress", "--format", format], &compressed.stdout);
+        assert!(restored.status.success());
+        assert_eq!(restored.stdout, data, "format {}", format);
+    }
+}
+
+#[test]
+fn decompress_reports_errors() {
+    let output = run(&["decompress"], &[1, 2, 3]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with("error: Decompression failed"));
+}
+
+#[test]
+fn csv2json_prints_one_record_per_line() {
+    let output = run(&["csv2json"], b"name,age\nJohn,30\nAlice,25\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "[\"John\",\"30\"]\n[\"Alice\",\"25\"]\n");
+}
+
+#[test]
+fn sort_and_wordfreq() {
+    let sorted = run(&["sort", "--algorithm", "quick"], b"5, 2 9\n-1");
+    assert_eq!(String::from_utf8(sorted.stdout).unwrap(), "-1\n2\n5\n9\n");
+
+    let freq = run(&["wordfreq"], b"b a b");
+    assert_eq!(String::from_utf8(freq.stdout).unwrap(), "{\"a\":1,\"b\":2}\n");
+}
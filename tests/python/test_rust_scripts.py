@@ -0,0 +1,58 @@
+"""Checks the Python bindings against the standard library and the native results.
+
+Run after installing the wheel: `python -m unittest discover tests/python`.
+"""
+
+import hashlib
+import unittest
+import zlib
+
+import rust_scripts
+
+try:
+    import numpy
+except ImportError:
+    numpy = None
+
+
+class CryptoTest(unittest.TestCase):
+    def test_hashes_match_stdlib(self):
+        self.assertEqual(rust_scripts.sha256_hash(b"abc"), hashlib.sha256(b"abc").digest())
+        self.assertEqual(rust_scripts.calculate_crc32(bytearray(b"Hello, world!")), zlib.crc32(b"Hello, world!"))
+
+    def test_lz4_round_trip_and_errors(self):
+        data = b"hello hello hello hello"
+        compressed = rust_scripts.compress_data(data)
+        self.assertEqual(int.from_bytes(compressed[:4], "little"), len(data))
+        self.assertEqual(rust_scripts.decompress_data(compressed), data)
+        with self.assertRaises(rust_scripts.Error) as raised:
+            rust_scripts.decompress_data(b"\x01\x02\x03")
+        self.assertEqual(raised.exception.code, "DECOMPRESSION_FAILED")
+
+
+class JsonTest(unittest.TestCase):
+    def test_parse_csv_to_json(self):
+        records = rust_scripts.parse_csv_to_json("name,age\nJohn,30\nAlice,25\n")
+        self.assertEqual(records, ['["John","30"]', '["Alice","25"]'])
+
+
+class ImagesTest(unittest.TestCase):
+    def test_filters_on_bytes(self):
+        self.assertEqual(rust_scripts.invert_colors(b"\x00\xff\x10"), b"\xff\x00\xef")
+        gray = rust_scripts.grayscale(bytes([10, 200, 30, 255]))
+        self.assertEqual(gray[0], gray[1])
+        self.assertEqual(gray[3], 255)
+        with self.assertRaises(ValueError):
+            rust_scripts.grayscale(b"abc")
+
+    @unittest.skipIf(numpy is None, "numpy is not installed")
+    def test_filters_keep_numpy_shape(self):
+        pixels = numpy.arange(2 * 3 * 4, dtype=numpy.uint8).reshape(2, 3, 4)
+        gray = rust_scripts.grayscale(pixels)
+        self.assertEqual(gray.shape, pixels.shape)
+        self.assertEqual(gray.tobytes(), rust_scripts.grayscale(pixels.tobytes()))
+        self.assertTrue((rust_scripts.invert_colors(pixels) == 255 - pixels).all())
+
+
+if __name__ == "__main__":
+    unittest.main()